@@ -0,0 +1,60 @@
+//! Run the pattern solver over every unfilled entry of a puzzle file.
+//!
+//! `cargo run -p crosswordsolver-jw --example solve_puzzle -- <puzzle.puz|.ipuz|.jpz> [wordlist]`
+
+use std::env;
+
+use anyhow::{Context, Result};
+use crosswordsolver_jw::{Pattern, QueryParams, SortOrder, WordIndex, parse_enumeration};
+use puzzle_model::Puzzle;
+
+const USAGE: &str =
+    "usage: cargo run -p crosswordsolver-jw --example solve_puzzle -- <puzzle> [wordlist]";
+
+fn main() -> Result<()> {
+    let mut args = env::args().skip(1);
+    let puzzle_path = args.next().context(USAGE)?;
+    let wordlist = args
+        .next()
+        .unwrap_or_else(|| "spreadthewordlist.dict".to_string());
+
+    let puzzle =
+        Puzzle::from_path(&puzzle_path).with_context(|| format!("reading puzzle {puzzle_path}"))?;
+    let index = WordIndex::build_from_file(&wordlist)
+        .with_context(|| format!("building index from {wordlist}"))?;
+
+    for entry in puzzle.entries() {
+        if entry.pattern.iter().all(Option::is_some) {
+            continue;
+        }
+        let enumeration = entry
+            .clue
+            .and_then(|c| c.enumeration.as_deref())
+            .and_then(|e| parse_enumeration(e, entry.slot.len).ok());
+        let pattern = Pattern::from(entry.pattern.clone());
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: enumeration.as_deref(),
+            min_score: None,
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 5,
+            overlay: None,
+        });
+        let shown: Vec<&str> = result.items.iter().map(|m| m.display.as_str()).collect();
+        println!(
+            "{}{} {}: {} match(es) {}",
+            entry.slot.number,
+            match entry.slot.direction {
+                puzzle_model::Direction::Across => "A",
+                puzzle_model::Direction::Down => "D",
+            },
+            entry.clue.map_or("", |c| c.text.as_str()),
+            result.total,
+            shown.join(", ")
+        );
+    }
+    Ok(())
+}
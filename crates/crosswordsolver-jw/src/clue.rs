@@ -0,0 +1,285 @@
+//! Ranking candidate answers by how close they are in meaning to a clue.
+//!
+//! The clue's words are looked up in WordNet once: their synsets, the
+//! hypernyms above those, and the synsets they point to directly. A
+//! candidate scores by the shortest link from one of its own synsets (or
+//! their hypernyms) into that neighbourhood, plus a little for every clue
+//! word its definitions use. Each score comes with the reasons behind it.
+
+use std::collections::{HashMap, HashSet};
+
+use wordnet_db::WordNet;
+use wordnet_morphy::Morphy;
+use wordnet_types::{Pos, Synset, SynsetId};
+
+/// Matches ranked per query; the rest of a long result is left out.
+pub const MAX_CLUE_CANDIDATES: usize = 5000;
+/// Hypernym levels walked above clue and candidate synsets.
+const HYPERNYM_DEPTH: usize = 3;
+/// Score for each clue word a candidate's definitions use.
+const GLOSS_WEIGHT: f32 = 0.1;
+/// At most this many gloss words count.
+const MAX_GLOSS_WORDS: usize = 3;
+const POS_ALL: [Pos; 4] = [Pos::Noun, Pos::Verb, Pos::Adj, Pos::Adv];
+pub(crate) const STOPWORDS: &[&str] = &[
+    "a", "an", "and", "are", "as", "at", "be", "by", "for", "from", "in", "into", "is", "it",
+    "its", "of", "on", "or", "so", "that", "the", "this", "to", "was", "with",
+];
+
+/// How a clue synset's neighbour was reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Link {
+    /// A hypernym this many levels above the clue synset (0 for the synset
+    /// itself).
+    Above(usize),
+    /// The target of another relation, such as similar-to or derivation.
+    Related,
+}
+
+impl Link {
+    fn steps(self) -> usize {
+        match self {
+            Link::Above(levels) => levels,
+            Link::Related => 1,
+        }
+    }
+}
+
+/// How close a candidate is to the clue, with the reasons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ClueMatch {
+    pub score: f32,
+    pub reasons: Vec<String>,
+}
+
+/// A clue looked up in WordNet, ready to score candidates against.
+pub struct ClueRanker<'a> {
+    wordnet: &'a WordNet,
+    morphy: &'a Morphy,
+    /// Content words of the clue, lowercase.
+    words: HashSet<String>,
+    /// Synsets near the clue, with the link and the clue word they came from.
+    near: HashMap<SynsetId, (Link, String)>,
+}
+
+impl<'a> ClueRanker<'a> {
+    pub fn new(wordnet: &'a WordNet, morphy: &'a Morphy, clue: &str) -> Self {
+        let tokens: Vec<String> = clue
+            .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+            .map(str::to_lowercase)
+            .filter(|t| !t.is_empty())
+            .collect();
+        let words: HashSet<String> = tokens
+            .iter()
+            .filter(|t| t.len() > 1 && !STOPWORDS.contains(&t.as_str()))
+            .cloned()
+            .collect();
+        // Two-word phrases such as "ice cream" are WordNet lemmas too.
+        let phrases = tokens.windows(2).map(|pair| pair.join(" "));
+
+        let mut ranker = Self {
+            wordnet,
+            morphy,
+            words: HashSet::new(),
+            near: HashMap::new(),
+        };
+        for word in words.iter().cloned().chain(phrases) {
+            for id in ranker.synsets_of(&word) {
+                ranker.add_near(id, &word);
+            }
+        }
+        ranker.words = words;
+        ranker
+    }
+
+    /// Record `id` and its neighbourhood as reached from clue `word`.
+    fn add_near(&mut self, id: SynsetId, word: &str) {
+        let mut level = vec![id];
+        for depth in 0..=HYPERNYM_DEPTH {
+            let mut next = Vec::new();
+            for id in level {
+                self.insert_near(id, Link::Above(depth), word);
+                let Some(synset) = self.wordnet.get_synset(id) else {
+                    continue;
+                };
+                for pointer in &synset.pointers {
+                    if is_hypernym(pointer.symbol) {
+                        next.push(pointer.target);
+                    } else if depth == 0 && is_related(pointer.symbol) {
+                        self.insert_near(pointer.target, Link::Related, word);
+                    }
+                }
+            }
+            level = next;
+        }
+    }
+
+    fn insert_near(&mut self, id: SynsetId, link: Link, word: &str) {
+        let closer = self
+            .near
+            .get(&id)
+            .is_none_or(|(existing, _)| link.steps() < existing.steps());
+        if closer {
+            self.near.insert(id, (link, word.to_string()));
+        }
+    }
+
+    /// Synsets of `word` in any part of speech, after undoing inflections.
+    fn synsets_of(&self, word: &str) -> Vec<SynsetId> {
+        let mut ids = Vec::new();
+        for pos in POS_ALL {
+            let lemmas = self
+                .morphy
+                .lemmas_for(pos, word, |p, lemma| self.wordnet.lemma_exists(p, lemma));
+            for lemma in lemmas {
+                for id in self.wordnet.synsets_for_lemma(pos, &lemma.lemma) {
+                    if !ids.contains(id) {
+                        ids.push(*id);
+                    }
+                }
+            }
+        }
+        ids
+    }
+
+    /// How close `candidate` (an answer as displayed, e.g. "hot dog") is to
+    /// the clue, or `None` when WordNet links it to nothing in the clue.
+    pub fn score(&self, candidate: &str) -> Option<ClueMatch> {
+        let synsets = self.synsets_of(candidate);
+        // Best link so far: its total steps and the reason it gives.
+        let mut best: Option<(usize, String)> = None;
+        let mut gloss_words: Vec<&str> = Vec::new();
+        for id in synsets {
+            let Some(synset) = self.wordnet.get_synset(id) else {
+                continue;
+            };
+            for word in definition_words(&synset) {
+                if let Some(word) = self.words.get(&word)
+                    && !gloss_words.contains(&word.as_str())
+                {
+                    gloss_words.push(word);
+                }
+            }
+
+            let mut level = vec![id];
+            for depth in 0..=HYPERNYM_DEPTH {
+                let mut next = Vec::new();
+                for id in level {
+                    if let Some((link, word)) = self.near.get(&id) {
+                        let steps = depth + link.steps();
+                        if best.as_ref().is_none_or(|(s, _)| steps < *s) {
+                            best = Some((steps, self.reason(id, depth, *link, word)));
+                        }
+                    }
+                    if let Some(synset) = self.wordnet.get_synset(id) {
+                        next.extend(
+                            synset
+                                .pointers
+                                .iter()
+                                .filter(|p| is_hypernym(p.symbol))
+                                .map(|p| p.target),
+                        );
+                    }
+                }
+                level = next;
+            }
+        }
+
+        gloss_words.truncate(MAX_GLOSS_WORDS);
+        let mut score = gloss_words.len() as f32 * GLOSS_WEIGHT;
+        let mut reasons = Vec::new();
+        if let Some((steps, reason)) = best {
+            score += 1.0 / (1 + steps) as f32;
+            reasons.push(reason);
+        }
+        reasons.extend(
+            gloss_words
+                .iter()
+                .map(|word| format!("definition mentions \"{word}\"")),
+        );
+        (score > 0.0).then_some(ClueMatch { score, reasons })
+    }
+
+    /// Why a candidate whose hypernym `depth` levels up is `id` is close to
+    /// clue `word`.
+    fn reason(&self, id: SynsetId, depth: usize, link: Link, word: &str) -> String {
+        match (depth, link) {
+            (0, Link::Above(0)) => format!("shares a sense with \"{word}\""),
+            (0, Link::Above(_)) => format!("\"{word}\" is a kind of it"),
+            (_, Link::Above(0)) => format!("a kind of \"{word}\""),
+            (0, Link::Related) => format!("related to \"{word}\""),
+            _ => {
+                let via = self
+                    .wordnet
+                    .get_synset(id)
+                    .and_then(|s| s.words.first().map(|w| w.text.replace('_', " ")))
+                    .unwrap_or_default();
+                format!("related to \"{word}\" through \"{via}\"")
+            }
+        }
+    }
+}
+
+fn is_hypernym(symbol: &str) -> bool {
+    matches!(symbol, "@" | "@i")
+}
+
+/// Relations close enough to count as one step from a clue sense.
+fn is_related(symbol: &str) -> bool {
+    matches!(symbol, "&" | "^" | "+" | "=" | "\\" | "<" | "$")
+}
+
+/// Lowercase words of a synset's definition.
+fn definition_words(synset: &Synset<'_>) -> Vec<String> {
+    synset
+        .gloss
+        .definition
+        .split(|c: char| !c.is_alphanumeric() && c != '\'' && c != '-')
+        .filter(|w| !w.is_empty())
+        .map(str::to_lowercase)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordnet_db::LoadMode;
+
+    fn fixture() -> (WordNet, Morphy) {
+        let dir = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../wordnet-db/tests/fixtures/wn"
+        );
+        (
+            WordNet::load_with_mode(dir, LoadMode::Owned).unwrap(),
+            Morphy::load(dir).unwrap(),
+        )
+    }
+
+    #[test]
+    fn scores_synonyms_above_kinds() {
+        let (wordnet, morphy) = fixture();
+        let ranker = ClueRanker::new(&wordnet, &morphy, "Dog, perhaps");
+        let cat = ranker.score("cat").unwrap();
+        assert_eq!(cat.reasons, ["shares a sense with \"dog\""]);
+        let animal = ranker.score("animal").unwrap();
+        assert_eq!(animal.reasons, ["\"dog\" is a kind of it"]);
+        assert!(cat.score > animal.score);
+        assert!(ranker.score("apple").is_none());
+
+        let ranker = ClueRanker::new(&wordnet, &morphy, "Animals");
+        assert_eq!(
+            ranker.score("cats").unwrap().reasons,
+            ["a kind of \"animals\""]
+        );
+    }
+
+    #[test]
+    fn counts_definition_words() {
+        let (wordnet, morphy) = fixture();
+        let ranker = ClueRanker::new(&wordnet, &morphy, "Domestic, kept in the house");
+        let cat = ranker.score("cat").unwrap();
+        assert_eq!(cat.reasons, ["definition mentions \"domestic\""]);
+        assert!((cat.score - GLOSS_WEIGHT).abs() < f32::EPSILON);
+    }
+}
@@ -0,0 +1,270 @@
+//! Conventional cryptic substitutions and indicator words.
+//!
+//! The table is a plain text file: `clue word = letters, letters` lines under
+//! `[abbreviations]`, and one indicator per line under a section named for
+//! its kind (`[anagram]`, `[container]`, ...). A curated table is built in;
+//! `CRYPTIC_DB` points the server at another file in the same format.
+
+use std::collections::HashMap;
+use std::path::Path;
+
+use thiserror::Error;
+
+use crate::index::{Alphabet, Fragment};
+
+/// The table shipped with the server.
+const BUILTIN: &str = include_str!("../data/cryptic.txt");
+/// Clue words whose abbreviations one query may use.
+pub const MAX_ABBREV_WORDS: usize = 4;
+
+/// What an indicator word tells the solver to do.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum IndicatorKind {
+    Anagram,
+    Container,
+    Insertion,
+    Reversal,
+    Hidden,
+    Deletion,
+    Homophone,
+}
+
+impl IndicatorKind {
+    const ALL: [IndicatorKind; 7] = [
+        IndicatorKind::Anagram,
+        IndicatorKind::Container,
+        IndicatorKind::Insertion,
+        IndicatorKind::Reversal,
+        IndicatorKind::Hidden,
+        IndicatorKind::Deletion,
+        IndicatorKind::Homophone,
+    ];
+
+    pub fn name(self) -> &'static str {
+        match self {
+            IndicatorKind::Anagram => "anagram",
+            IndicatorKind::Container => "container",
+            IndicatorKind::Insertion => "insertion",
+            IndicatorKind::Reversal => "reversal",
+            IndicatorKind::Hidden => "hidden",
+            IndicatorKind::Deletion => "deletion",
+            IndicatorKind::Homophone => "homophone",
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum CrypticError {
+    #[error("failed to read cryptic table: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("cryptic table line {line}: {message}")]
+    Syntax { line: usize, message: String },
+    #[error("no abbreviations known for {0:?}")]
+    UnknownWord(String),
+    #[error("at most {0} abbreviated words per query")]
+    TooManyWords(usize),
+}
+
+/// Abbreviations and indicators, keyed by normalized clue words.
+#[derive(Debug, Default)]
+pub struct CrypticDb {
+    abbreviations: HashMap<String, Vec<String>>,
+    indicators: HashMap<String, Vec<IndicatorKind>>,
+}
+
+impl CrypticDb {
+    /// The table built into the server.
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN).expect("built-in cryptic table is valid")
+    }
+
+    pub fn load(path: &Path) -> Result<Self, CrypticError> {
+        Self::parse(&std::fs::read_to_string(path)?)
+    }
+
+    pub fn parse(text: &str) -> Result<Self, CrypticError> {
+        let mut db = Self::default();
+        let mut section: Option<Option<IndicatorKind>> = None;
+        for (number, raw) in text.lines().enumerate() {
+            let line_error = |message: &str| CrypticError::Syntax {
+                line: number + 1,
+                message: message.to_string(),
+            };
+            let line = raw.split('#').next().unwrap_or_default().trim();
+            if line.is_empty() {
+                continue;
+            }
+            if let Some(name) = line.strip_prefix('[').and_then(|l| l.strip_suffix(']')) {
+                let name = name.trim();
+                section = Some(if name == "abbreviations" {
+                    None
+                } else {
+                    Some(
+                        IndicatorKind::ALL
+                            .into_iter()
+                            .find(|kind| kind.name() == name)
+                            .ok_or_else(|| line_error("unknown section"))?,
+                    )
+                });
+                continue;
+            }
+            match section {
+                None => return Err(line_error("entry before any section")),
+                Some(None) => {
+                    let (word, letters) = line
+                        .split_once('=')
+                        .ok_or_else(|| line_error("expected `word = letters`"))?;
+                    let letters: Vec<String> = letters
+                        .split(',')
+                        .map(|l| l.trim().to_lowercase())
+                        .filter(|l| !l.is_empty())
+                        .collect();
+                    if letters.is_empty() {
+                        return Err(line_error("no letters given"));
+                    }
+                    let entry = db.abbreviations.entry(normalize(word)).or_default();
+                    for letters in letters {
+                        if !entry.contains(&letters) {
+                            entry.push(letters);
+                        }
+                    }
+                }
+                Some(Some(kind)) => {
+                    let entry = db.indicators.entry(normalize(line)).or_default();
+                    if !entry.contains(&kind) {
+                        entry.push(kind);
+                    }
+                }
+            }
+        }
+        Ok(db)
+    }
+
+    /// Letters `word` conventionally stands for, most common first.
+    pub fn abbreviations(&self, word: &str) -> &[String] {
+        self.abbreviations
+            .get(&normalize(word))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Kinds of wordplay `word` can signal.
+    pub fn indicators(&self, word: &str) -> &[IndicatorKind] {
+        self.indicators
+            .get(&normalize(word))
+            .map_or(&[], Vec::as_slice)
+    }
+
+    /// Every abbreviation of the comma-separated clue `words`, as fragments
+    /// for the wordplay search. Letters outside `alphabet` are skipped.
+    pub fn fragments(
+        &self,
+        words: &str,
+        alphabet: &Alphabet,
+    ) -> Result<Vec<Fragment>, CrypticError> {
+        Ok(self
+            .choices(words, alphabet)?
+            .into_iter()
+            .flatten()
+            .collect())
+    }
+
+    /// Per clue word in the comma-separated `words`, the fragments it may
+    /// stand for. Letters outside `alphabet` are skipped.
+    pub fn choices(
+        &self,
+        words: &str,
+        alphabet: &Alphabet,
+    ) -> Result<Vec<Vec<Fragment>>, CrypticError> {
+        let words: Vec<&str> = words
+            .split(',')
+            .map(str::trim)
+            .filter(|w| !w.is_empty())
+            .collect();
+        if words.len() > MAX_ABBREV_WORDS {
+            return Err(CrypticError::TooManyWords(MAX_ABBREV_WORDS));
+        }
+        words
+            .into_iter()
+            .map(|word| {
+                let letters = self.abbreviations(word);
+                if letters.is_empty() {
+                    return Err(CrypticError::UnknownWord(word.to_string()));
+                }
+                Ok(letters
+                    .iter()
+                    .filter_map(|letters| {
+                        let cells = alphabet.cells_of(letters)?;
+                        Some(Fragment {
+                            cells: String::from_utf8(cells).expect("cell codes are ASCII"),
+                            letters: letters.clone(),
+                            source: normalize(word),
+                        })
+                    })
+                    .collect())
+            })
+            .collect()
+    }
+}
+
+/// Lowercase with single spaces, so "The  French" finds "the french".
+fn normalize(word: &str) -> String {
+    word.split_whitespace()
+        .map(str::to_lowercase)
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_builtin_table() {
+        let db = CrypticDb::builtin();
+        assert_eq!(
+            db.abbreviations("Sailor"),
+            ["ab", "tar", "os", "rn", "salt"]
+        );
+        assert_eq!(db.abbreviations("the  French"), ["le", "la", "les"]);
+        assert_eq!(db.abbreviations("learner"), ["l"]);
+        assert!(db.abbreviations("zebra").is_empty());
+        assert_eq!(
+            db.indicators("about"),
+            [IndicatorKind::Anagram, IndicatorKind::Container]
+        );
+        assert_eq!(db.indicators("we hear"), [IndicatorKind::Homophone]);
+    }
+
+    #[test]
+    fn reports_bad_lines() {
+        let err = CrypticDb::parse("[abbreviations]\nsailor ab\n").unwrap_err();
+        assert!(matches!(err, CrypticError::Syntax { line: 2, .. }));
+        assert!(CrypticDb::parse("[puns]\n").is_err());
+        assert!(CrypticDb::parse("sailor = ab\n").is_err());
+    }
+
+    #[test]
+    fn expands_clue_words() {
+        let db = CrypticDb::parse("[abbreviations]\nsailor = ab, tar\nlearner = l\n").unwrap();
+        let english = Alphabet::english();
+        let choices = db.choices("sailor, learner", &english).unwrap();
+        assert_eq!(choices.len(), 2);
+        assert_eq!(
+            (
+                choices[0][1].source.as_str(),
+                choices[0][1].letters.as_str()
+            ),
+            ("sailor", "tar")
+        );
+        let fragments = db.fragments("sailor", &english).unwrap();
+        assert_eq!(fragments.len(), 2);
+        assert!(matches!(
+            db.choices("zebra", &english),
+            Err(CrypticError::UnknownWord(_))
+        ));
+        assert!(matches!(
+            db.choices("a,b,c,d,e", &english),
+            Err(CrypticError::TooManyWords(_))
+        ));
+    }
+}
@@ -1,30 +1,49 @@
 use std::collections::{HashMap, HashSet};
 use std::sync::{Arc, OnceLock};
+use std::time::{Duration, Instant};
 
 use axum::extract::State;
-use axum::http::{HeaderValue, StatusCode, header};
+use axum::http::{HeaderMap, HeaderValue, StatusCode, header};
 use axum::response::{Html, IntoResponse, Response};
-use axum::routing::get;
+use axum::routing::{get, post};
 use axum::{Json, Router};
 use serde::{Deserialize, Serialize};
 use serde_json::json;
 use thiserror::Error;
+use tracing::{error, info};
 use wordnet_db::WordNet;
 use wordnet_morphy::Morphy;
 use wordnet_types::{Pos, Synset, SynsetId};
 
+use crate::clue::{ClueRanker, MAX_CLUE_CANDIDATES};
+use crate::cryptic::CrypticDb;
 use crate::index::{
-    AnagramParams, MAX_WORD_LEN, QueryParams, WordIndex, parse_letter_bag, parse_letters,
-    parse_pattern,
+    ALPHABET, AbbrevAnagramParams, AnagramParams, Direction, Distance, FillError, FillParams,
+    Fragment, FuzzyParams, Grid, LadderParams, LetterSet, MAX_LADDER_STEPS, MAX_MISMATCHES,
+    MAX_WORD_LEN, Overlay, Pattern, PhraseAnagramParams, QueryParams, QueryResult, RegexParams,
+    SortOrder, Spelled, SubAnagramParams, WildcardParams, WordIndex, WordMatch, WordRegex,
+    WordleConstraints, WordleParams, Wordplay, WordplayParams, mismatched_positions,
+    parse_enumeration,
 };
+use crate::lists::WordLists;
+use crate::reload::{ActiveIndex, IndexStore, ReloadError};
+use crate::reverse::ReverseIndex;
+use crate::thesaurus::{self, parse_relations, related_lemmas};
 
 #[derive(Clone)]
 pub struct AppState {
-    pub index: Arc<WordIndex>,
+    pub lists: Arc<WordLists>,
     pub wordnet: Arc<WordNet>,
     pub morphy: Arc<Morphy>,
     pub max_page_size: usize,
     pub disable_cache: bool,
+    /// Bearer token for `/admin/*`; the admin routes answer 404 without one.
+    pub admin_token: Option<String>,
+    /// Abbreviations and indicators for the cryptic endpoints.
+    pub cryptic: Arc<CrypticDb>,
+    /// Inverted index over WordNet glosses for `/v1/wordnet/reverse`, built
+    /// by the first request that needs it.
+    pub reverse: Arc<OnceLock<ReverseIndex>>,
 }
 
 #[derive(Deserialize)]
@@ -34,6 +53,33 @@ pub struct MatchesQuery {
     pub page_size: Option<usize>,
     pub must_include: Option<String>,
     pub cannot_include: Option<String>,
+    #[serde(rename = "enum")]
+    pub enumeration: Option<String>,
+    pub isomorph: Option<bool>,
+    pub min_score: Option<u8>,
+    pub sort: Option<String>,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub cross: Option<String>,
+    pub crossing_mode: Option<String>,
+    pub max_mismatches: Option<usize>,
+    pub distance: Option<String>,
+    /// Clue text to rank the matches against by meaning.
+    pub clue: Option<String>,
+    pub list: Option<String>,
+}
+
+/// Body of `POST /v1/matches`: the query parameters plus word overlays.
+#[derive(Deserialize)]
+pub struct MatchesRequest {
+    #[serde(flatten)]
+    pub query: MatchesQuery,
+    /// Extra entries, in word-list syntax (`word` or `word;score`).
+    #[serde(default)]
+    pub allow: Vec<String>,
+    /// Words to leave out of the results.
+    #[serde(default)]
+    pub deny: Vec<String>,
 }
 
 #[derive(Deserialize)]
@@ -42,6 +88,99 @@ pub struct AnagramQuery {
     pub pattern: Option<String>,
     pub page: Option<usize>,
     pub page_size: Option<usize>,
+    pub min_score: Option<u8>,
+    pub sort: Option<String>,
+    pub mode: Option<String>,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    /// Clue words whose abbreviations join the letters, e.g. `learner`.
+    pub abbrev: Option<String>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct RegexQuery {
+    pub pattern: String,
+    pub min_len: Option<usize>,
+    pub max_len: Option<usize>,
+    pub min_score: Option<u8>,
+    pub sort: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WordleQuery {
+    /// Guesses with their feedback, e.g. `crane:BYBBG,slate:BBGBB`.
+    pub guesses: Option<String>,
+    /// Answer length when no guess has been made yet (default 5).
+    pub len: Option<usize>,
+    pub min_score: Option<u8>,
+    pub sort: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    /// Next guesses to suggest (default 10).
+    pub suggest: Option<usize>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct LadderQuery {
+    pub from: String,
+    pub to: String,
+    pub max_steps: Option<usize>,
+    /// Shortest ladders to return (default 1).
+    pub paths: Option<usize>,
+    pub min_score: Option<u8>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct AbbrevQuery {
+    pub word: String,
+}
+
+#[derive(Deserialize)]
+pub struct HiddenQuery {
+    pub clue: String,
+    pub pattern: String,
+    pub min_score: Option<u8>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct WordplayQuery {
+    pub pattern: String,
+    /// `charade`, `container` or `all` (default).
+    pub kind: Option<String>,
+    pub min_part_len: Option<usize>,
+    /// Clue words whose abbreviations may be used as pieces.
+    pub abbrev: Option<String>,
+    pub min_score: Option<u8>,
+    pub sort: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct PhraseAnagramQuery {
+    pub letters: String,
+    #[serde(rename = "enum")]
+    pub enumeration: Option<String>,
+    pub max_words: Option<usize>,
+    pub min_score: Option<u8>,
+    pub limit: Option<usize>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct GridFillRequest {
+    /// One string per row: `#` block, `.` empty, letters pre-filled.
+    pub grid: Vec<String>,
+    pub min_score: Option<u8>,
+    pub list: Option<String>,
 }
 
 #[derive(Serialize)]
@@ -51,7 +190,259 @@ pub struct MatchesResponse {
     page_size: usize,
     total: usize,
     has_more: bool,
-    items: Vec<String>,
+    items: Vec<MatchItem>,
+    /// With `clue=`: the matches ranked, which are the only ones paged
+    /// through. Fewer than `total` when the rest were left out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    ranked: Option<usize>,
+    /// Set when a fuzzy query ran out of time, or with `abbrev=` on
+    /// `/v1/anagrams` when there were more choices of abbreviations than
+    /// were tried.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    truncated: bool,
+}
+
+impl MatchesResponse {
+    /// Whether matches were left out of the clue ranking, the fuzzy search
+    /// or the abbreviation choices.
+    fn partial(&self) -> bool {
+        self.truncated || self.ranked.is_some_and(|ranked| ranked < self.total)
+    }
+}
+
+#[derive(Serialize)]
+struct WordleResponse {
+    len: usize,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    has_more: bool,
+    items: Vec<MatchItem>,
+    suggestions: Vec<SuggestionItem>,
+}
+
+#[derive(Serialize)]
+struct SuggestionItem {
+    word: String,
+    display: String,
+    score: Option<u8>,
+    expected_info: f64,
+    expected_remaining: f64,
+    candidate: bool,
+}
+
+#[derive(Serialize)]
+struct LadderResponse {
+    from: String,
+    to: String,
+    /// Letter changes in each ladder; absent when none was found.
+    steps: Option<usize>,
+    paths: Vec<Vec<MatchItem>>,
+    truncated: bool,
+}
+
+#[derive(Serialize)]
+struct AbbrevResponse {
+    word: String,
+    abbreviations: Vec<String>,
+    /// Kinds of wordplay the word can signal.
+    indicators: Vec<&'static str>,
+}
+
+#[derive(Serialize)]
+struct FragmentItem {
+    letters: String,
+    source: String,
+}
+
+impl From<Fragment> for FragmentItem {
+    fn from(fragment: Fragment) -> Self {
+        FragmentItem {
+            letters: fragment.letters,
+            source: fragment.source,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct HiddenResponse {
+    clue: String,
+    pattern: String,
+    total: usize,
+    items: Vec<HiddenItem>,
+}
+
+#[derive(Serialize)]
+struct HiddenItem {
+    word: String,
+    display: String,
+    score: Option<u8>,
+    /// Clue characters holding the word, `end` exclusive.
+    start: usize,
+    end: usize,
+    text: String,
+    reversed: bool,
+}
+
+#[derive(Serialize)]
+struct WordplayResponse {
+    pattern: String,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    has_more: bool,
+    items: Vec<ConstructionItem>,
+    truncated: bool,
+}
+
+#[derive(Serialize)]
+struct ConstructionItem {
+    word: String,
+    display: String,
+    score: Option<u8>,
+    parses: Vec<WordplayNode>,
+}
+
+/// One node of a wordplay parse tree.
+#[derive(Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+enum WordplayNode {
+    Word {
+        word: String,
+        display: String,
+        score: Option<u8>,
+    },
+    Fragment(FragmentItem),
+    Charade {
+        parts: Vec<WordplayNode>,
+    },
+    /// `inner` goes into `outer` after its first `at` letters.
+    Container {
+        outer: Box<WordplayNode>,
+        at: usize,
+        inner: Box<WordplayNode>,
+    },
+}
+
+impl From<Wordplay> for WordplayNode {
+    fn from(play: Wordplay) -> Self {
+        match play {
+            Wordplay::Word(word) => WordplayNode::Word {
+                word: word.word,
+                display: word.display,
+                score: word.score,
+            },
+            Wordplay::Fragment(fragment) => WordplayNode::Fragment(fragment.into()),
+            Wordplay::Charade(parts) => WordplayNode::Charade {
+                parts: parts.into_iter().map(Into::into).collect(),
+            },
+            Wordplay::Container { outer, inner, at } => WordplayNode::Container {
+                outer: Box::new((*outer).into()),
+                at,
+                inner: Box::new((*inner).into()),
+            },
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SubAnagramResponse {
+    letters: String,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    has_more: bool,
+    groups: Vec<LengthGroupItem>,
+}
+
+#[derive(Serialize)]
+struct LengthGroupItem {
+    len: usize,
+    total: usize,
+    items: Vec<MatchItem>,
+}
+
+#[derive(Serialize)]
+struct PhraseAnagramResponse {
+    letters: String,
+    total: usize,
+    truncated: bool,
+    items: Vec<PhraseItem>,
+}
+
+#[derive(Serialize)]
+struct PhraseItem {
+    display: String,
+    score: Option<u8>,
+    words: Vec<MatchItem>,
+}
+
+#[derive(Serialize)]
+struct GridFillResponse {
+    filled: bool,
+    /// Why no fill was returned, when `filled` is false.
+    reason: Option<String>,
+    grid: Vec<String>,
+    entries: Vec<GridEntry>,
+}
+
+#[derive(Serialize)]
+struct GridEntry {
+    number: usize,
+    direction: &'static str,
+    row: usize,
+    col: usize,
+    len: usize,
+    #[serde(flatten)]
+    word: MatchItem,
+}
+
+#[derive(Serialize)]
+struct MatchItem {
+    word: String,
+    display: String,
+    score: Option<u8>,
+    /// Words still fitting each requested crossing if this one is entered.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    crossings: Option<Vec<usize>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    viable: Option<bool>,
+    /// Positions where a fuzzy match disagrees with the pattern.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    mismatches: Option<Vec<usize>>,
+    /// Abbreviations whose letters went into an anagram.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    fragments: Option<Vec<FragmentItem>>,
+    /// How close the word is in meaning to the `clue`, if at all.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    clue_score: Option<f32>,
+    /// Why the word scored against the clue.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    reasons: Option<Vec<String>>,
+}
+
+#[derive(Deserialize)]
+pub struct ReloadQuery {
+    pub list: Option<String>,
+}
+
+#[derive(Serialize)]
+struct ListInfo {
+    name: String,
+    default: bool,
+    words: usize,
+    /// Alphabet preset or spec the list is spelled with.
+    alphabet: String,
+    /// File name of the list; the directory is not exposed.
+    source: Option<String>,
+    list_version: String,
+    generation: u64,
+}
+
+#[derive(Serialize)]
+struct ListsResponse {
+    default: String,
+    lists: Vec<ListInfo>,
 }
 
 #[derive(Serialize)]
@@ -65,6 +456,34 @@ pub struct WordNetQuery {
     pub pos: Option<String>,
 }
 
+#[derive(Deserialize)]
+pub struct SynonymQuery {
+    pub word: String,
+    pub pattern: String,
+    #[serde(rename = "enum")]
+    pub enumeration: Option<String>,
+    pub pos: Option<String>,
+    /// Relation steps to walk; 0 keeps to the word's own synsets.
+    pub depth: Option<usize>,
+    /// Comma-separated relation names, see `thesaurus::RELATIONS`.
+    pub relations: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub list: Option<String>,
+}
+
+#[derive(Deserialize)]
+pub struct ReverseQuery {
+    /// The description to find words for.
+    pub q: String,
+    pub pattern: Option<String>,
+    pub len: Option<usize>,
+    pub pos: Option<String>,
+    pub page: Option<usize>,
+    pub page_size: Option<usize>,
+    pub list: Option<String>,
+}
+
 #[derive(Serialize, Clone)]
 struct SynsetIdResponse {
     pos: char,
@@ -127,6 +546,61 @@ struct RelatedResponse {
     note: Option<String>,
 }
 
+#[derive(Serialize)]
+struct SynonymResponse {
+    word: String,
+    pattern: String,
+    lemmas: Vec<String>,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    has_more: bool,
+    items: Vec<SynonymItem>,
+    /// Set when the walk stopped before reaching every synset in range.
+    truncated: bool,
+}
+
+#[derive(Serialize)]
+struct SynonymItem {
+    word: String,
+    display: String,
+    enumeration: Vec<usize>,
+    pos: String,
+    synset_id: SynsetIdResponse,
+    /// Relations followed from the word; empty for synonyms.
+    path: Vec<&'static str>,
+    sense_count: u32,
+    /// Whether the word list has the entry, and its score there.
+    in_list: bool,
+    score: Option<u8>,
+}
+
+#[derive(Serialize)]
+struct ReverseResponse {
+    q: String,
+    page: usize,
+    page_size: usize,
+    total: usize,
+    has_more: bool,
+    items: Vec<ReverseItem>,
+}
+
+#[derive(Serialize)]
+struct ReverseItem {
+    word: String,
+    display: String,
+    enumeration: Vec<usize>,
+    pos: String,
+    synset_id: SynsetIdResponse,
+    definition: String,
+    /// BM25 score of the synset against `q`.
+    relevance: f32,
+    /// Words of `q` found in the synset's lemmas or gloss.
+    matched: Vec<String>,
+    in_list: bool,
+    score: Option<u8>,
+}
+
 pub fn router(state: AppState) -> Router {
     Router::new()
         .route("/", get(frontend))
@@ -135,15 +609,135 @@ pub fn router(state: AppState) -> Router {
         .route("/about", get(about_frontend))
         .route("/robots.txt", get(robots))
         .route("/healthz", get(healthz))
-        .route("/v1/matches", get(matches))
+        .route("/v1/lists", get(word_lists))
+        .route("/v1/matches", get(matches).post(matches_with_overlay))
+        .route("/v1/regex", get(regex_search))
+        .route("/v1/wordle", get(wordle))
+        .route("/v1/ladder", get(word_ladder))
+        .route("/v1/cryptic/abbrev", get(abbreviations))
+        .route("/v1/cryptic/hidden", get(hidden_words))
+        .route("/v1/cryptic/wordplay", get(wordplay))
         .route("/v1/anagrams", get(anagrams))
+        .route("/v1/anagrams/phrases", get(phrase_anagrams))
+        .route("/v1/grid/fill", post(grid_fill))
         .route("/v1/wordnet/dictionary", get(dictionary_lookup))
         .route("/v1/wordnet/related", get(related_words))
+        .route("/v1/wordnet/synonyms", get(pattern_synonyms))
+        .route("/v1/wordnet/reverse", get(reverse_dictionary))
+        .route("/admin/reload", post(reload_index))
         .with_state(state)
 }
 
-async fn healthz() -> impl IntoResponse {
-    "ok"
+async fn healthz(State(state): State<AppState>) -> Response {
+    let active = state.lists.default_list().active();
+    let lists: serde_json::Map<_, _> = state
+        .lists
+        .iter()
+        .map(|(name, store)| (name.to_string(), active_json(&store.active())))
+        .collect();
+    Json(json!({
+        "status": "ok",
+        "list_version": active.version,
+        "generation": active.generation,
+        "words": active.index.word_count(),
+        "lists": lists,
+    }))
+    .into_response()
+}
+
+fn active_json(active: &ActiveIndex) -> serde_json::Value {
+    json!({
+        "list_version": active.version,
+        "generation": active.generation,
+        "words": active.index.word_count(),
+    })
+}
+
+/// The store for `list=`, or the default list when none was given.
+fn word_list<'a>(state: &'a AppState, name: Option<&str>) -> Result<&'a IndexStore, ApiError> {
+    let name = name.filter(|n| !n.trim().is_empty()).map(str::trim);
+    state.lists.get(name).map(Arc::as_ref).ok_or_else(|| {
+        ApiError::bad_request(format!(
+            "unknown list '{}'; see /v1/lists",
+            name.unwrap_or_default()
+        ))
+    })
+}
+
+async fn word_lists(State(state): State<AppState>) -> Response {
+    let lists = state
+        .lists
+        .iter()
+        .map(|(name, store)| {
+            let active = store.active();
+            ListInfo {
+                name: name.to_string(),
+                default: name == state.lists.default_name(),
+                words: active.index.word_count(),
+                alphabet: active.index.alphabet().name().to_string(),
+                source: store
+                    .source()
+                    .and_then(|p| p.file_name())
+                    .map(|f| f.to_string_lossy().into_owned()),
+                list_version: active.version.clone(),
+                generation: active.generation,
+            }
+        })
+        .collect();
+    Json(ListsResponse {
+        default: state.lists.default_name().to_string(),
+        lists,
+    })
+    .into_response()
+}
+
+/// Rebuild the word index from the configured list and swap it in. With
+/// `list=` only that list reloads; otherwise every list does.
+async fn reload_index(
+    State(state): State<AppState>,
+    headers: HeaderMap,
+    axum::extract::Query(params): axum::extract::Query<ReloadQuery>,
+) -> Result<Response, ApiError> {
+    let Some(token) = state.admin_token.as_deref() else {
+        return Err(ApiError::NotFound);
+    };
+    let presented = headers
+        .get(header::AUTHORIZATION)
+        .and_then(|v| v.to_str().ok())
+        .and_then(|v| v.strip_prefix("Bearer "));
+    if !presented.is_some_and(|p| constant_time_eq(p.as_bytes(), token.as_bytes())) {
+        return Err(ApiError::Unauthorized);
+    }
+
+    let names: Vec<&str> = match params.list.as_deref() {
+        Some(name) => {
+            word_list(&state, Some(name))?;
+            vec![name.trim()]
+        }
+        None => state.lists.iter().map(|(name, _)| name).collect(),
+    };
+    let mut lists = serde_json::Map::new();
+    for name in names {
+        let store = word_list(&state, Some(name))?;
+        let active = store.reload().await.map_err(|err| match err {
+            ReloadError::InProgress | ReloadError::Empty => {
+                ApiError::Conflict(format!("list '{name}': {err}"))
+            }
+            err => {
+                error!("reload of list '{name}' failed: {err}");
+                ApiError::Internal
+            }
+        })?;
+        lists.insert(name.to_string(), active_json(&active));
+    }
+    let default = state.lists.default_list().active();
+    let mut body = active_json(&default);
+    body["lists"] = lists.into();
+    Ok(Json(body).into_response())
+}
+
+fn constant_time_eq(a: &[u8], b: &[u8]) -> bool {
+    a.len() == b.len() && a.iter().zip(b).fold(0u8, |acc, (x, y)| acc | (x ^ y)) == 0
 }
 
 async fn robots(State(state): State<AppState>) -> Response {
@@ -228,9 +822,60 @@ async fn matches(
     State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<MatchesQuery>,
 ) -> Result<Response, ApiError> {
-    let pattern_vec =
-        parse_pattern(&params.pattern).map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let response = run_matches(&state, index, params, None).await?;
+    // A ranking or fuzzy search that left matches out is partial, so only
+    // cache complete answers.
+    if response.partial() {
+        Ok(Json(response).into_response())
+    } else {
+        Ok(cacheable_json(&state, response))
+    }
+}
+
+/// `POST /v1/matches`: the same query with allow/deny overlay lists in the
+/// body. Answers depend on the body, so they are not marked cacheable.
+async fn matches_with_overlay(
+    State(state): State<AppState>,
+    Json(body): Json<MatchesRequest>,
+) -> Result<Response, ApiError> {
+    let index = word_list(&state, body.query.list.as_deref())?.load();
+    let overlay = Overlay::new(index.alphabet(), &body.allow, &body.deny)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let response = run_matches(&state, index, body.query, Some(overlay)).await?;
+    Ok(Json(response).into_response())
+}
+
+/// Time budget for the word-by-word checks of a fuzzy query.
+const FUZZY_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+/// Run [`find_matches`], off the async workers when the query is fuzzy or
+/// ranked by a clue: Levenshtein distance checks every word of up to seven
+/// buckets one by one, and a clue looks up thousands of words in WordNet.
+async fn run_matches(
+    state: &AppState,
+    index: Arc<WordIndex>,
+    params: MatchesQuery,
+    overlay: Option<Overlay>,
+) -> Result<MatchesResponse, ApiError> {
+    let clue = params.clue.as_deref().is_some_and(|c| !c.trim().is_empty());
+    if params.max_mismatches.unwrap_or(0) == 0 && !clue {
+        return find_matches(state, &index, params, overlay.as_ref());
+    }
+    let state = state.clone();
+    tokio::task::spawn_blocking(move || find_matches(&state, &index, params, overlay.as_ref()))
+        .await
+        .map_err(|_| ApiError::Internal)?
+}
 
+fn find_matches(
+    state: &AppState,
+    index: &WordIndex,
+    params: MatchesQuery,
+    overlay: Option<&Overlay>,
+) -> Result<MatchesResponse, ApiError> {
+    let alphabet = index.alphabet();
+    let isomorph = params.isomorph.unwrap_or(false);
     let page = params.page.unwrap_or(1);
     if page == 0 {
         return Err(ApiError::bad_request("page must be >= 1"));
@@ -245,53 +890,258 @@ async fn matches(
 
     let must_include = params
         .must_include
-        .map_or(Ok(Vec::new()), |s| parse_letters(&s))
+        .map_or(Ok(Vec::new()), |s| alphabet.parse_letters(&s))
         .map_err(|e| ApiError::bad_request(e.to_string()))?;
     let cannot_include = params
         .cannot_include
-        .map_or(Ok(Vec::new()), |s| parse_letters(&s))
+        .map_or(Ok(Vec::new()), |s| alphabet.parse_letters(&s))
         .map_err(|e| ApiError::bad_request(e.to_string()))?;
-
-    let result = state.index.query(QueryParams {
-        pattern: &pattern_vec,
-        must_include: &must_include,
-        cannot_include: &cannot_include,
-        page,
-        page_size,
-    });
-
-    let response = MatchesResponse {
-        pattern: params.pattern,
-        page,
-        page_size,
-        total: result.total,
-        has_more: result.has_more,
-        items: result.items,
+    let enumeration = params
+        .enumeration
+        .as_deref()
+        .filter(|s| !s.trim().is_empty());
+    let sort = parse_sort(params.sort.as_deref())?;
+
+    let annotate = match params.crossing_mode.as_deref() {
+        None | Some("drop") => false,
+        Some("annotate") => true,
+        Some(_) => {
+            return Err(ApiError::bad_request(
+                "crossing_mode must be one of drop|annotate",
+            ));
+        }
     };
+    let cross = params.cross.as_deref().filter(|s| !s.trim().is_empty());
+    // Per crossing: the position in this slot and the word count per letter.
+    let mut crossing_counts = Vec::new();
 
-    if state.disable_cache {
-        Ok(Json(response).into_response())
-    } else {
-        Ok((
-            [(
-                header::CACHE_CONTROL,
-                HeaderValue::from_static("public, max-age=300"),
-            )],
-            Json(response),
-        )
-            .into_response())
+    let max_mismatches = params.max_mismatches.unwrap_or(0);
+    if max_mismatches > MAX_MISMATCHES {
+        return Err(ApiError::bad_request(format!(
+            "max_mismatches must be at most {MAX_MISMATCHES}"
+        )));
     }
-}
-
-async fn dictionary_lookup(
-    State(state): State<AppState>,
-    axum::extract::Query(params): axum::extract::Query<WordNetQuery>,
-) -> Result<Response, ApiError> {
-    let word = params.word.trim();
-    if word.is_empty() {
-        return Err(ApiError::bad_request("word is required"));
+    let distance = match params.distance.as_deref() {
+        None | Some("hamming") => Distance::Hamming,
+        Some("levenshtein") => Distance::Levenshtein,
+        Some(_) => {
+            return Err(ApiError::bad_request(
+                "distance must be one of hamming|levenshtein",
+            ));
+        }
+    };
+    if max_mismatches > 0 && (isomorph || params.pattern.contains('*')) {
+        return Err(ApiError::bad_request(
+            "max_mismatches cannot be combined with isomorph or * wildcards",
+        ));
     }
-    let normalized = word.to_ascii_lowercase();
+    // The pattern to report mismatches against, for fuzzy queries.
+    let mut fuzzy_pattern = None;
+    let mut truncated = false;
+
+    // Ranking by a clue needs every match up front; the page is cut after.
+    let clue = params.clue.as_deref().filter(|s| !s.trim().is_empty());
+    let (query_page, query_page_size) = if clue.is_some() {
+        (1, MAX_CLUE_CANDIDATES)
+    } else {
+        (page, page_size)
+    };
+
+    let result = if !isomorph && params.pattern.contains('*') {
+        if enumeration.is_some() {
+            return Err(ApiError::bad_request(
+                "enum cannot be combined with * wildcards",
+            ));
+        }
+        if cross.is_some() {
+            return Err(ApiError::bad_request(
+                "cross cannot be combined with * wildcards",
+            ));
+        }
+        let pattern = alphabet
+            .parse_wildcard_pattern(&params.pattern)
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        let min_len = params.min_len.unwrap_or(pattern.fixed_len().max(1));
+        let max_len = params.max_len.unwrap_or(MAX_WORD_LEN);
+        if min_len == 0 || max_len > MAX_WORD_LEN || min_len > max_len {
+            return Err(ApiError::bad_request(format!(
+                "min_len and max_len must satisfy 1 <= min_len <= max_len <= {MAX_WORD_LEN}"
+            )));
+        }
+        index.query_wildcard(WildcardParams {
+            pattern: &pattern,
+            min_len,
+            max_len,
+            must_include: &must_include,
+            cannot_include: &cannot_include,
+            min_score: params.min_score,
+            sort,
+            page: query_page,
+            page_size: query_page_size,
+            overlay,
+        })
+    } else {
+        let mut pattern_vec = if isomorph {
+            alphabet.parse_isomorph(&params.pattern)
+        } else {
+            alphabet.parse_pattern(&params.pattern)
+        }
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        let enumeration = enumeration
+            .map(|s| parse_enumeration(s, pattern_vec.len()))
+            .transpose()
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+        let crossings = cross
+            .map(|s| alphabet.parse_crossings(s, pattern_vec.len()))
+            .transpose()
+            .map_err(|e| ApiError::bad_request(e.to_string()))?
+            .unwrap_or_default();
+        for crossing in crossings {
+            let counts = index.letter_counts_at(
+                &crossing.pattern,
+                crossing.cross_pos,
+                params.min_score,
+                overlay,
+            );
+            if !annotate {
+                // Only letters that leave the crossing fillable stay allowed.
+                let mut viable = LetterSet::default();
+                for (letter, count) in counts.iter().enumerate() {
+                    if *count > 0 {
+                        viable.insert(b'a' + letter as u8);
+                    }
+                }
+                pattern_vec.restrict(crossing.pos, viable);
+            }
+            crossing_counts.push((crossing.pos, counts));
+        }
+
+        if max_mismatches > 0 {
+            // Levenshtein matches change length, so nothing tied to slot
+            // positions can apply.
+            if distance == Distance::Levenshtein
+                && (enumeration.is_some() || cross.is_some() || pattern_vec.has_vars())
+            {
+                return Err(ApiError::bad_request(
+                    "distance=levenshtein cannot be combined with enum, cross or digit variables",
+                ));
+            }
+            let result = index.query_fuzzy(FuzzyParams {
+                pattern: &pattern_vec,
+                max_mismatches,
+                distance,
+                must_include: &must_include,
+                cannot_include: &cannot_include,
+                enumeration: enumeration.as_deref(),
+                min_score: params.min_score,
+                sort,
+                page: query_page,
+                page_size: query_page_size,
+                overlay,
+                time_limit: FUZZY_TIME_LIMIT,
+            });
+            fuzzy_pattern = Some(pattern_vec);
+            truncated = result.truncated;
+            QueryResult {
+                total: result.total,
+                items: result.items,
+                has_more: result.has_more,
+            }
+        } else {
+            index.query(QueryParams {
+                pattern: &pattern_vec,
+                must_include: &must_include,
+                cannot_include: &cannot_include,
+                enumeration: enumeration.as_deref(),
+                min_score: params.min_score,
+                sort,
+                page: query_page,
+                page_size: query_page_size,
+                overlay,
+            })
+        }
+    };
+
+    let mut items: Vec<MatchItem> = result
+        .items
+        .into_iter()
+        .map(|item| {
+            if crossing_counts.is_empty() && fuzzy_pattern.is_none() {
+                return match_item(item);
+            }
+            let letters = alphabet.cells_of(&item.word).unwrap_or_default();
+            let mismatches = fuzzy_pattern
+                .as_ref()
+                .map(|pattern| mismatched_positions(pattern, &letters, distance));
+            if crossing_counts.is_empty() {
+                return MatchItem {
+                    mismatches,
+                    ..match_item(item)
+                };
+            }
+            let counts: Vec<usize> = crossing_counts
+                .iter()
+                .map(|(pos, counts)| counts[(letters[*pos] - b'a') as usize])
+                .collect();
+            MatchItem {
+                viable: Some(counts.iter().all(|c| *c > 0)),
+                crossings: Some(counts),
+                mismatches,
+                ..match_item(item)
+            }
+        })
+        .collect();
+    let (has_more, ranked) = match clue {
+        None => (result.has_more, None),
+        Some(clue) => {
+            rank_by_clue(state, clue, &mut items);
+            let ranked = items.len();
+            let offset = (page - 1).saturating_mul(page_size).min(ranked);
+            items.truncate(offset.saturating_add(page_size).min(ranked));
+            items.drain(..offset);
+            (offset + items.len() < ranked, Some(ranked))
+        }
+    };
+
+    Ok(MatchesResponse {
+        pattern: params.pattern,
+        page,
+        page_size,
+        total: result.total,
+        has_more,
+        items,
+        ranked,
+        truncated,
+    })
+}
+
+/// Score `items` against `clue` and move the closest first. Words WordNet
+/// does not link to the clue keep their order after those it does.
+fn rank_by_clue(state: &AppState, clue: &str, items: &mut [MatchItem]) {
+    let ranker = ClueRanker::new(&state.wordnet, &state.morphy, clue);
+    for item in items.iter_mut() {
+        if let Some(found) = ranker.score(&item.display) {
+            item.clue_score = Some(found.score);
+            item.reasons = Some(found.reasons);
+        }
+    }
+    items.sort_by(|a, b| {
+        b.clue_score
+            .unwrap_or(0.0)
+            .total_cmp(&a.clue_score.unwrap_or(0.0))
+    });
+}
+
+async fn dictionary_lookup(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<WordNetQuery>,
+) -> Result<Response, ApiError> {
+    let word = params.word.trim();
+    if word.is_empty() {
+        return Err(ApiError::bad_request("word is required"));
+    }
+    let normalized = word.to_ascii_lowercase();
     let pos_filter = parse_pos_filter(params.pos.as_deref())?;
 
     let mut seen_lemmas = HashSet::new();
@@ -328,142 +1178,707 @@ async fn dictionary_lookup(
         }
     }
 
-    let mut results: Vec<_> = synsets.into_values().collect();
-    results.sort_by(|a, b| {
-        let sa = a.sense_count.unwrap_or(0);
-        let sb = b.sense_count.unwrap_or(0);
-        sb.cmp(&sa)
-            .then_with(|| {
-                pos_order(char_to_pos(a.synset_id.pos))
-                    .cmp(&pos_order(char_to_pos(b.synset_id.pos)))
+    let mut results: Vec<_> = synsets.into_values().collect();
+    results.sort_by(|a, b| {
+        let sa = a.sense_count.unwrap_or(0);
+        let sb = b.sense_count.unwrap_or(0);
+        sb.cmp(&sa)
+            .then_with(|| {
+                pos_order(char_to_pos(a.synset_id.pos))
+                    .cmp(&pos_order(char_to_pos(b.synset_id.pos)))
+            })
+            .then_with(|| a.synset_id.offset.cmp(&b.synset_id.offset))
+    });
+
+    let note = if results.is_empty() {
+        Some(format!("no WordNet entries found for \"{word}\""))
+    } else {
+        None
+    };
+
+    let response = DictionaryResponse {
+        word: word.to_string(),
+        normalized,
+        lemmas,
+        results,
+        note,
+    };
+
+    if state.disable_cache {
+        Ok(Json(response).into_response())
+    } else {
+        Ok((
+            [(
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=3600"),
+            )],
+            Json(response),
+        )
+            .into_response())
+    }
+}
+
+async fn related_words(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<WordNetQuery>,
+) -> Result<Response, ApiError> {
+    let word = params.word.trim();
+    if word.is_empty() {
+        return Err(ApiError::bad_request("word is required"));
+    }
+    let normalized = word.to_ascii_lowercase();
+    let pos_filter = parse_pos_filter(params.pos.as_deref())?;
+
+    let mut seen_lemmas = HashSet::new();
+    let mut lemmas = Vec::new();
+    let mut seen_synsets = HashSet::new();
+    let mut synsets_out = Vec::new();
+
+    for pos in pos_filter {
+        let candidates = state
+            .morphy
+            .lemmas_for(pos, word, |p, lemma| state.wordnet.lemma_exists(p, lemma));
+        for cand in candidates {
+            let lemma = cand.lemma.to_string();
+            if seen_lemmas.insert(lemma.clone()) {
+                lemmas.push(lemma.clone());
+            }
+            for sid in state.wordnet.synsets_for_lemma(pos, &lemma) {
+                if !seen_synsets.insert(*sid) {
+                    continue;
+                }
+                if let Some(syn) = state.wordnet.get_synset(*sid) {
+                    let sense_count = best_sense_count_for_synset(&state.wordnet, &syn, &lemmas);
+                    let relations = collect_relations(&state.wordnet, &syn);
+                    synsets_out.push(RelatedSynset {
+                        pos: pos_label(syn.id.pos).to_string(),
+                        synset_id: synset_id_response(syn.id),
+                        lemmas: syn.words.iter().map(|w| w.text.to_string()).collect(),
+                        definition: syn.gloss.definition.to_string(),
+                        examples: syn.gloss.examples.iter().map(|e| e.to_string()).collect(),
+                        sense_count,
+                        relations,
+                    });
+                }
+            }
+        }
+    }
+
+    synsets_out.sort_by(|a, b| {
+        let sa = a.sense_count.unwrap_or(0);
+        let sb = b.sense_count.unwrap_or(0);
+        sb.cmp(&sa)
+            .then_with(|| {
+                pos_order(char_to_pos(a.synset_id.pos))
+                    .cmp(&pos_order(char_to_pos(b.synset_id.pos)))
+            })
+            .then_with(|| a.synset_id.offset.cmp(&b.synset_id.offset))
+    });
+
+    let note = if synsets_out.is_empty() {
+        Some(format!("no WordNet entries found for \"{word}\""))
+    } else {
+        None
+    };
+
+    let response = RelatedResponse {
+        word: word.to_string(),
+        normalized,
+        lemmas,
+        synsets: synsets_out,
+        note,
+    };
+
+    if state.disable_cache {
+        Ok(Json(response).into_response())
+    } else {
+        Ok((
+            [(
+                header::CACHE_CONTROL,
+                HeaderValue::from_static("public, max-age=1800"),
+            )],
+            Json(response),
+        )
+            .into_response())
+    }
+}
+
+/// `/v1/wordnet/synonyms`: lemmas a few relation steps from a word that
+/// fit an answer pattern.
+async fn pattern_synonyms(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<SynonymQuery>,
+) -> Result<Response, ApiError> {
+    let word = params.word.trim();
+    if word.is_empty() {
+        return Err(ApiError::bad_request("word is required"));
+    }
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(ApiError::bad_request("page must be >= 1"));
+    }
+    let page_size = params.page_size.unwrap_or(50);
+    if page_size == 0 {
+        return Err(ApiError::bad_request("page_size must be >= 1"));
+    }
+    let page_size = page_size.min(state.max_page_size);
+    let depth = params.depth.unwrap_or(1);
+    if depth > thesaurus::MAX_DEPTH {
+        return Err(ApiError::bad_request(format!(
+            "depth must be at most {}",
+            thesaurus::MAX_DEPTH
+        )));
+    }
+    let relations = parse_relations(params.relations.as_deref())
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let pos_filter = parse_pos_filter(params.pos.as_deref())?;
+
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let pattern = index
+        .alphabet()
+        .parse_pattern(&params.pattern)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let enumeration = params
+        .enumeration
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| parse_enumeration(s, pattern.len()))
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    // A deep walk can reach thousands of synsets, and every lemma is then
+    // spelled for the list, so this runs off the async workers.
+    let walk_state = state.clone();
+    let walk_word = word.to_string();
+    let (lemmas, items, truncated) = tokio::task::spawn_blocking(move || {
+        let found = related_lemmas(
+            &walk_state.wordnet,
+            &walk_state.morphy,
+            &walk_word,
+            &pos_filter,
+            &relations,
+            depth,
+        );
+        let mut items = Vec::new();
+        for related in found.items {
+            let Some(spelled) = spell_lemma(&index, &related.lemma) else {
+                continue;
+            };
+            if !pattern.matches(&spelled.cells)
+                || enumeration
+                    .as_ref()
+                    .is_some_and(|lengths| *lengths != spelled.enumeration)
+            {
+                continue;
+            }
+            let cells = String::from_utf8(spelled.cells).expect("cell codes are ASCII");
+            items.push(SynonymItem {
+                word: index.alphabet().render(&cells),
+                display: related.lemma,
+                enumeration: spelled.enumeration,
+                pos: pos_label(related.pos).to_string(),
+                synset_id: synset_id_response(related.synset),
+                path: related.path,
+                sense_count: related.sense_count,
+                in_list: spelled.entry.is_some(),
+                score: spelled.entry.and_then(|entry| entry.score),
+            });
+        }
+        (found.lemmas, items, found.truncated)
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?;
+
+    let total = items.len();
+    let offset = (page - 1).saturating_mul(page_size).min(total);
+    let items: Vec<SynonymItem> = items.into_iter().skip(offset).take(page_size).collect();
+    let response = SynonymResponse {
+        word: word.to_string(),
+        pattern: params.pattern,
+        lemmas,
+        page,
+        page_size,
+        total,
+        has_more: offset + items.len() < total,
+        items,
+        truncated,
+    };
+    Ok(cacheable_json(&state, response))
+}
+
+/// `/v1/wordnet/reverse`: words whose WordNet entries match a description,
+/// optionally only those in the list that fit a pattern or length.
+async fn reverse_dictionary(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<ReverseQuery>,
+) -> Result<Response, ApiError> {
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(ApiError::bad_request("page must be >= 1"));
+    }
+    let page_size = params.page_size.unwrap_or(50);
+    if page_size == 0 {
+        return Err(ApiError::bad_request("page_size must be >= 1"));
+    }
+    let page_size = page_size.min(state.max_page_size);
+    let pos_filter = parse_pos_filter(params.pos.as_deref())?;
+
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let pattern = params
+        .pattern
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| index.alphabet().parse_pattern(s))
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let filter = match (pattern, params.len) {
+        (Some(pattern), Some(len)) if pattern.len() != len => {
+            return Err(ApiError::bad_request("len must match the pattern length"));
+        }
+        (Some(pattern), _) => Some(pattern),
+        (None, Some(len)) if !(1..=MAX_WORD_LEN).contains(&len) => {
+            return Err(ApiError::bad_request(format!(
+                "len must be between 1 and {MAX_WORD_LEN}"
+            )));
+        }
+        (None, Some(len)) => Some(Pattern::from(vec![None; len])),
+        (None, None) => None,
+    };
+
+    // The first request builds the index, which takes a while.
+    let search_state = state.clone();
+    let q = params.q.clone();
+    let hits =
+        tokio::task::spawn_blocking(move || reverse_index(&search_state).search(&q, &pos_filter))
+            .await
+            .map_err(|_| ApiError::Internal)?
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let mut seen = HashSet::new();
+    let mut items = Vec::new();
+    for hit in hits {
+        let Some(synset) = state.wordnet.get_synset(hit.synset) else {
+            continue;
+        };
+        for lemma in &synset.words {
+            let display = lemma.text.replace('_', " ");
+            let Some(spelled) = spell_lemma(&index, &display) else {
+                continue;
+            };
+            // With a filter, answers must be entries of the list.
+            if let Some(pattern) = &filter
+                && (!pattern.matches(&spelled.cells) || spelled.entry.is_none())
+            {
+                continue;
+            }
+            if !seen.insert(display.to_lowercase()) {
+                continue;
+            }
+            let cells = String::from_utf8(spelled.cells).expect("cell codes are ASCII");
+            items.push(ReverseItem {
+                word: index.alphabet().render(&cells),
+                display,
+                enumeration: spelled.enumeration,
+                pos: pos_label(synset.id.pos).to_string(),
+                synset_id: synset_id_response(synset.id),
+                definition: synset.gloss.definition.to_string(),
+                relevance: hit.score,
+                matched: hit.matched.clone(),
+                in_list: spelled.entry.is_some(),
+                score: spelled.entry.and_then(|entry| entry.score),
+            });
+        }
+    }
+
+    let total = items.len();
+    let offset = (page - 1).saturating_mul(page_size).min(total);
+    let items: Vec<ReverseItem> = items.into_iter().skip(offset).take(page_size).collect();
+    let response = ReverseResponse {
+        q: params.q,
+        page,
+        page_size,
+        total,
+        has_more: offset + items.len() < total,
+        items,
+    };
+    Ok(cacheable_json(&state, response))
+}
+
+/// The reverse dictionary, built over WordNet on first use.
+fn reverse_index(state: &AppState) -> &ReverseIndex {
+    state.reverse.get_or_init(|| {
+        let start = Instant::now();
+        let reverse = ReverseIndex::build(&state.wordnet);
+        info!(
+            "reverse dictionary over {} synsets ready in {} ms",
+            reverse.synset_count(),
+            start.elapsed().as_millis()
+        );
+        reverse
+    })
+}
+
+/// A WordNet lemma spelled for the list. Lemmas such as "o'clock" and
+/// "st. john's wort" lose their punctuation the way answers do.
+fn spell_lemma(index: &WordIndex, lemma: &str) -> Option<Spelled> {
+    index.spell(&lemma.replace(['\'', '.'], ""))
+}
+
+#[derive(Debug, Error)]
+pub enum ApiError {
+    #[error("{0}")]
+    BadRequest(String),
+    #[error("unauthorized")]
+    Unauthorized,
+    #[error("not found")]
+    NotFound,
+    #[error("{0}")]
+    Conflict(String),
+    #[error("internal server error")]
+    Internal,
+}
+
+impl ApiError {
+    fn bad_request<T: Into<String>>(msg: T) -> Self {
+        ApiError::BadRequest(msg.into())
+    }
+}
+
+const REGEX_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+async fn regex_search(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<RegexQuery>,
+) -> Result<Response, ApiError> {
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(ApiError::bad_request("page must be >= 1"));
+    }
+    let page_size = params.page_size.unwrap_or(50);
+    if page_size == 0 {
+        return Err(ApiError::bad_request("page_size must be >= 1"));
+    }
+    let page_size = page_size.min(state.max_page_size);
+    let min_len = params.min_len.unwrap_or(1);
+    let max_len = params.max_len.unwrap_or(MAX_WORD_LEN);
+    if min_len == 0 || max_len > MAX_WORD_LEN || min_len > max_len {
+        return Err(ApiError::bad_request(format!(
+            "min_len and max_len must satisfy 1 <= min_len <= max_len <= {MAX_WORD_LEN}"
+        )));
+    }
+    let sort = parse_sort(params.sort.as_deref())?;
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let regex = WordRegex::new(&params.pattern, index.alphabet())
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    // Scanning can touch every word of a bucket, so keep it off the async
+    // workers.
+    let min_score = params.min_score;
+    let result = tokio::task::spawn_blocking(move || {
+        index.query_regex(RegexParams {
+            regex: &regex,
+            min_len,
+            max_len,
+            min_score,
+            sort,
+            page,
+            page_size,
+            time_limit: REGEX_TIME_LIMIT,
+        })
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?
+    .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let response = MatchesResponse {
+        pattern: params.pattern,
+        page,
+        page_size,
+        total: result.total,
+        has_more: result.has_more,
+        items: result.items.into_iter().map(match_item).collect(),
+        ranked: None,
+        truncated: false,
+    };
+    Ok(cacheable_json(&state, response))
+}
+
+const DEFAULT_WORDLE_LEN: usize = 5;
+const DEFAULT_SUGGESTIONS: usize = 10;
+const MAX_SUGGESTIONS: usize = 50;
+
+async fn wordle(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<WordleQuery>,
+) -> Result<Response, ApiError> {
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(ApiError::bad_request("page must be >= 1"));
+    }
+    let page_size = params.page_size.unwrap_or(50);
+    if page_size == 0 {
+        return Err(ApiError::bad_request("page_size must be >= 1"));
+    }
+    let page_size = page_size.min(state.max_page_size);
+    let sort = parse_sort(params.sort.as_deref())?;
+    let suggestions = params
+        .suggest
+        .unwrap_or(DEFAULT_SUGGESTIONS)
+        .min(MAX_SUGGESTIONS);
+
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let guesses = index
+        .alphabet()
+        .parse_guesses(params.guesses.as_deref().unwrap_or_default())
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let len = params
+        .len
+        .or_else(|| guesses.first().map(|g| g.letters.len()))
+        .unwrap_or(DEFAULT_WORDLE_LEN);
+    let constraints =
+        WordleConstraints::new(len, &guesses).map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    // Ranking guesses compares every pair of words, so keep it off the
+    // async workers.
+    let min_score = params.min_score;
+    let result = tokio::task::spawn_blocking(move || {
+        index.query_wordle(WordleParams {
+            constraints: &constraints,
+            min_score,
+            sort,
+            page,
+            page_size,
+            suggestions,
+        })
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?;
+
+    let response = WordleResponse {
+        len,
+        page,
+        page_size,
+        total: result.candidates.total,
+        has_more: result.candidates.has_more,
+        items: result
+            .candidates
+            .items
+            .into_iter()
+            .map(match_item)
+            .collect(),
+        suggestions: result
+            .suggestions
+            .into_iter()
+            .map(|s| SuggestionItem {
+                word: s.word.word,
+                display: s.word.display,
+                score: s.word.score,
+                expected_info: s.expected_info,
+                expected_remaining: s.expected_remaining,
+                candidate: s.candidate,
             })
-            .then_with(|| a.synset_id.offset.cmp(&b.synset_id.offset))
-    });
-
-    let note = if results.is_empty() {
-        Some(format!("no WordNet entries found for \"{word}\""))
-    } else {
-        None
+            .collect(),
     };
+    Ok(cacheable_json(&state, response))
+}
 
-    let response = DictionaryResponse {
-        word: word.to_string(),
-        normalized,
-        lemmas,
-        results,
-        note,
+const DEFAULT_LADDER_STEPS: usize = 10;
+const MAX_LADDER_PATHS: usize = 20;
+const LADDER_TIME_LIMIT: Duration = Duration::from_secs(1);
+
+async fn word_ladder(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<LadderQuery>,
+) -> Result<Response, ApiError> {
+    let max_steps = params.max_steps.unwrap_or(DEFAULT_LADDER_STEPS);
+    if max_steps == 0 || max_steps > MAX_LADDER_STEPS {
+        return Err(ApiError::bad_request(format!(
+            "max_steps must be between 1 and {MAX_LADDER_STEPS}"
+        )));
+    }
+    let max_paths = params.paths.unwrap_or(1);
+    if max_paths == 0 || max_paths > MAX_LADDER_PATHS {
+        return Err(ApiError::bad_request(format!(
+            "paths must be between 1 and {MAX_LADDER_PATHS}"
+        )));
+    }
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let ladder_params = LadderParams {
+        max_steps,
+        max_paths,
+        min_score: params.min_score,
+        time_limit: LADDER_TIME_LIMIT,
     };
 
-    if state.disable_cache {
+    // Each level of the search ANDs bitsets for every word on it, so keep it
+    // off the async workers.
+    let (from, to) = (params.from.clone(), params.to.clone());
+    let result = tokio::task::spawn_blocking(move || index.word_ladder(&from, &to, &ladder_params))
+        .await
+        .map_err(|_| ApiError::Internal)?
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let truncated = result.truncated;
+    let response = LadderResponse {
+        from: params.from,
+        to: params.to,
+        steps: result.paths.first().map(|path| path.len() - 1),
+        paths: result
+            .paths
+            .into_iter()
+            .map(|path| path.into_iter().map(match_item).collect())
+            .collect(),
+        truncated,
+    };
+    if truncated {
         Ok(Json(response).into_response())
     } else {
-        Ok((
-            [(
-                header::CACHE_CONTROL,
-                HeaderValue::from_static("public, max-age=3600"),
-            )],
-            Json(response),
-        )
-            .into_response())
+        Ok(cacheable_json(&state, response))
     }
 }
 
-async fn related_words(
+async fn abbreviations(
     State(state): State<AppState>,
-    axum::extract::Query(params): axum::extract::Query<WordNetQuery>,
+    axum::extract::Query(params): axum::extract::Query<AbbrevQuery>,
 ) -> Result<Response, ApiError> {
-    let word = params.word.trim();
-    if word.is_empty() {
+    if params.word.trim().is_empty() {
         return Err(ApiError::bad_request("word is required"));
     }
-    let normalized = word.to_ascii_lowercase();
-    let pos_filter = parse_pos_filter(params.pos.as_deref())?;
-
-    let mut seen_lemmas = HashSet::new();
-    let mut lemmas = Vec::new();
-    let mut seen_synsets = HashSet::new();
-    let mut synsets_out = Vec::new();
+    let response = AbbrevResponse {
+        abbreviations: state.cryptic.abbreviations(&params.word).to_vec(),
+        indicators: state
+            .cryptic
+            .indicators(&params.word)
+            .iter()
+            .map(|kind| kind.name())
+            .collect(),
+        word: params.word,
+    };
+    Ok(cacheable_json(&state, response))
+}
 
-    for pos in pos_filter {
-        let candidates = state
-            .morphy
-            .lemmas_for(pos, word, |p, lemma| state.wordnet.lemma_exists(p, lemma));
-        for cand in candidates {
-            let lemma = cand.lemma.to_string();
-            if seen_lemmas.insert(lemma.clone()) {
-                lemmas.push(lemma.clone());
-            }
-            for sid in state.wordnet.synsets_for_lemma(pos, &lemma) {
-                if !seen_synsets.insert(*sid) {
-                    continue;
-                }
-                if let Some(syn) = state.wordnet.get_synset(*sid) {
-                    let sense_count = best_sense_count_for_synset(&state.wordnet, &syn, &lemmas);
-                    let relations = collect_relations(&state.wordnet, &syn);
-                    synsets_out.push(RelatedSynset {
-                        pos: pos_label(syn.id.pos).to_string(),
-                        synset_id: synset_id_response(syn.id),
-                        lemmas: syn.words.iter().map(|w| w.text.to_string()).collect(),
-                        definition: syn.gloss.definition.to_string(),
-                        examples: syn.gloss.examples.iter().map(|e| e.to_string()).collect(),
-                        sense_count,
-                        relations,
-                    });
-                }
-            }
-        }
+async fn hidden_words(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<HiddenQuery>,
+) -> Result<Response, ApiError> {
+    if params.clue.trim().is_empty() {
+        return Err(ApiError::bad_request("clue is required"));
     }
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let pattern = index
+        .alphabet()
+        .parse_pattern(&params.pattern)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let found = index
+        .hidden_words(&params.clue, &pattern, params.min_score)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
 
-    synsets_out.sort_by(|a, b| {
-        let sa = a.sense_count.unwrap_or(0);
-        let sb = b.sense_count.unwrap_or(0);
-        sb.cmp(&sa)
-            .then_with(|| {
-                pos_order(char_to_pos(a.synset_id.pos))
-                    .cmp(&pos_order(char_to_pos(b.synset_id.pos)))
+    let response = HiddenResponse {
+        clue: params.clue,
+        pattern: params.pattern,
+        total: found.len(),
+        items: found
+            .into_iter()
+            .map(|hidden| HiddenItem {
+                word: hidden.word.word,
+                display: hidden.word.display,
+                score: hidden.word.score,
+                start: hidden.start,
+                end: hidden.end,
+                text: hidden.text,
+                reversed: hidden.reversed,
             })
-            .then_with(|| a.synset_id.offset.cmp(&b.synset_id.offset))
-    });
-
-    let note = if synsets_out.is_empty() {
-        Some(format!("no WordNet entries found for \"{word}\""))
-    } else {
-        None
+            .collect(),
     };
+    Ok(cacheable_json(&state, response))
+}
 
-    let response = RelatedResponse {
-        word: word.to_string(),
-        normalized,
-        lemmas,
-        synsets: synsets_out,
-        note,
-    };
+const DEFAULT_MIN_PART_LEN: usize = 2;
+const WORDPLAY_TIME_LIMIT: Duration = Duration::from_secs(1);
 
-    if state.disable_cache {
-        Ok(Json(response).into_response())
-    } else {
-        Ok((
-            [(
-                header::CACHE_CONTROL,
-                HeaderValue::from_static("public, max-age=1800"),
-            )],
-            Json(response),
-        )
-            .into_response())
+async fn wordplay(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<WordplayQuery>,
+) -> Result<Response, ApiError> {
+    let page = params.page.unwrap_or(1);
+    if page == 0 {
+        return Err(ApiError::bad_request("page must be >= 1"));
     }
-}
+    let page_size = params.page_size.unwrap_or(50);
+    if page_size == 0 {
+        return Err(ApiError::bad_request("page_size must be >= 1"));
+    }
+    let page_size = page_size.min(state.max_page_size);
+    let (charades, containers) = match params.kind.as_deref() {
+        None | Some("all") => (true, true),
+        Some("charade") => (true, false),
+        Some("container") => (false, true),
+        Some(_) => {
+            return Err(ApiError::bad_request(
+                "kind must be one of charade|container|all",
+            ));
+        }
+    };
+    let min_part_len = params.min_part_len.unwrap_or(DEFAULT_MIN_PART_LEN);
+    if min_part_len == 0 {
+        return Err(ApiError::bad_request("min_part_len must be >= 1"));
+    }
+    let sort = parse_sort(params.sort.as_deref())?;
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let pattern = index
+        .alphabet()
+        .parse_pattern(&params.pattern)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let fragments = match params.abbrev.as_deref() {
+        Some(words) => state
+            .cryptic
+            .fragments(words, index.alphabet())
+            .map_err(|e| ApiError::bad_request(e.to_string()))?,
+        None => Vec::new(),
+    };
 
-#[derive(Debug, Error)]
-pub enum ApiError {
-    #[error("{0}")]
-    BadRequest(String),
-    #[error("internal server error")]
-    Internal,
-}
+    // Every answer is cut every way and each piece looked up, so keep it off
+    // the async workers.
+    let min_score = params.min_score;
+    let result = tokio::task::spawn_blocking(move || {
+        index.query_wordplay(WordplayParams {
+            pattern: &pattern,
+            min_part_len,
+            min_score,
+            charades,
+            containers,
+            fragments: &fragments,
+            sort,
+            page,
+            page_size,
+            time_limit: WORDPLAY_TIME_LIMIT,
+        })
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?;
 
-impl ApiError {
-    fn bad_request<T: Into<String>>(msg: T) -> Self {
-        ApiError::BadRequest(msg.into())
+    let truncated = result.truncated;
+    let response = WordplayResponse {
+        pattern: params.pattern,
+        page,
+        page_size,
+        total: result.total,
+        has_more: result.has_more,
+        items: result
+            .items
+            .into_iter()
+            .map(|construction| ConstructionItem {
+                word: construction.answer.word,
+                display: construction.answer.display,
+                score: construction.answer.score,
+                parses: construction.parses.into_iter().map(Into::into).collect(),
+            })
+            .collect(),
+        truncated,
+    };
+    if truncated {
+        Ok(Json(response).into_response())
+    } else {
+        Ok(cacheable_json(&state, response))
     }
 }
 
@@ -471,26 +1886,22 @@ async fn anagrams(
     State(state): State<AppState>,
     axum::extract::Query(params): axum::extract::Query<AnagramQuery>,
 ) -> Result<Response, ApiError> {
-    let letters = params.letters.trim();
+    let letters = params.letters.trim().to_string();
     if letters.is_empty() {
         return Err(ApiError::bad_request("letters is required"));
     }
-    if letters.len() > MAX_WORD_LEN {
+    if letters.chars().count() > MAX_WORD_LEN {
         return Err(ApiError::bad_request(format!(
             "letters must be at most {MAX_WORD_LEN}"
         )));
     }
-
-    let pattern_str = params.pattern.unwrap_or_else(|| "_".repeat(letters.len()));
-    let pattern_vec =
-        parse_pattern(&pattern_str).map_err(|e| ApiError::bad_request(e.to_string()))?;
-    if pattern_vec.len() != letters.len() {
-        return Err(ApiError::bad_request(
-            "pattern length must match letters length",
-        ));
-    }
-    let bag = parse_letter_bag(letters, letters.len())
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let alphabet = index.alphabet();
+    let (bag, blanks) = alphabet
+        .parse_tile_bag(&letters)
         .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    // Cells, not characters: Dutch "ij" is one tile.
+    let tiles = bag.iter().map(|c| *c as usize).sum::<usize>() + blanks as usize;
 
     let page = params.page.unwrap_or(1);
     if page == 0 {
@@ -503,22 +1914,152 @@ async fn anagrams(
     if page_size > state.max_page_size {
         page_size = state.max_page_size;
     }
+    let sort = parse_sort(params.sort.as_deref())?;
+    let abbrev = params.abbrev.as_deref().filter(|a| !a.trim().is_empty());
+
+    match params.mode.as_deref() {
+        None | Some("exact") => {}
+        Some("subset") if abbrev.is_some() => {
+            return Err(ApiError::bad_request(
+                "abbrev is not supported with mode=subset",
+            ));
+        }
+        Some("subset") => {
+            if params.pattern.is_some() {
+                return Err(ApiError::bad_request(
+                    "pattern is not supported with mode=subset; use min_len/max_len",
+                ));
+            }
+            let min_len = params.min_len.unwrap_or(1);
+            let max_len = params.max_len.unwrap_or(tiles);
+            if min_len == 0 || min_len > max_len {
+                return Err(ApiError::bad_request(
+                    "min_len and max_len must satisfy 1 <= min_len <= max_len",
+                ));
+            }
+            let result = index.query_subanagram(SubAnagramParams {
+                bag_counts: bag,
+                blanks,
+                min_len,
+                max_len,
+                min_score: params.min_score,
+                sort,
+                page,
+                page_size,
+            });
+            let response = SubAnagramResponse {
+                letters,
+                page,
+                page_size,
+                total: result.total,
+                has_more: result.has_more,
+                groups: result
+                    .groups
+                    .into_iter()
+                    .map(|group| LengthGroupItem {
+                        len: group.len,
+                        total: group.total,
+                        items: group.items.into_iter().map(match_item).collect(),
+                    })
+                    .collect(),
+            };
+            return Ok(cacheable_json(&state, response));
+        }
+        Some(_) => return Err(ApiError::bad_request("mode must be one of exact|subset")),
+    }
+
+    if let Some(words) = abbrev {
+        let choices = state
+            .cryptic
+            .choices(words, alphabet)
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        let pattern = params
+            .pattern
+            .as_deref()
+            .map(|raw| alphabet.parse_pattern(raw))
+            .transpose()
+            .map_err(|e| ApiError::bad_request(e.to_string()))?;
+        let min_score = params.min_score;
+        let result = tokio::task::spawn_blocking(move || {
+            index.query_abbrev_anagram(AbbrevAnagramParams {
+                pattern: pattern.as_ref(),
+                bag_counts: bag,
+                blanks,
+                choices: &choices,
+                min_score,
+                sort,
+                page,
+                page_size,
+            })
+        })
+        .await
+        .map_err(|_| ApiError::Internal)?;
+        let response = MatchesResponse {
+            pattern: params.pattern.unwrap_or_default(),
+            page,
+            page_size,
+            total: result.total,
+            has_more: result.has_more,
+            items: result
+                .items
+                .into_iter()
+                .map(|item| MatchItem {
+                    fragments: Some(
+                        item.fragments
+                            .into_iter()
+                            .map(|fragment| FragmentItem {
+                                letters: fragment.letters,
+                                source: fragment.source,
+                            })
+                            .collect(),
+                    ),
+                    ..match_item(item.word)
+                })
+                .collect(),
+            ranked: None,
+            truncated: result.truncated,
+        };
+        // Untried choices may hold more matches, so only cache complete
+        // answers.
+        if response.truncated {
+            return Ok(Json(response).into_response());
+        }
+        return Ok(cacheable_json(&state, response));
+    }
+
+    let pattern_str = params.pattern.unwrap_or_else(|| "_".repeat(tiles));
+    let pattern_vec = alphabet
+        .parse_pattern(&pattern_str)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    if pattern_vec.len() != tiles {
+        return Err(ApiError::bad_request(
+            "pattern length must match letters length",
+        ));
+    }
 
-    // Reject patterns that require letters not available in the bag.
-    let mut required_counts = [0u8; 26];
-    for letter in pattern_vec.iter().flatten() {
+    // Reject patterns that require letters not available in the bag, after
+    // letting blanks stand in for missing ones.
+    let mut required_counts = [0u8; ALPHABET];
+    let mut missing = 0u8;
+    for letter in pattern_vec.letters.iter().flatten() {
         let idx = (*letter - b'a') as usize;
         required_counts[idx] = required_counts[idx].saturating_add(1);
         if required_counts[idx] > bag[idx] {
-            return Err(ApiError::bad_request(
-                "pattern requires letters not present in the bag",
-            ));
+            missing = missing.saturating_add(1);
+            if missing > blanks {
+                return Err(ApiError::bad_request(
+                    "pattern requires letters not present in the bag",
+                ));
+            }
         }
     }
 
-    let result = state.index.query_anagram(AnagramParams {
+    let result = index.query_anagram(AnagramParams {
         pattern: &pattern_vec,
         bag_counts: bag,
+        blanks,
+        min_score: params.min_score,
+        sort,
         page,
         page_size,
     });
@@ -529,23 +2070,172 @@ async fn anagrams(
         page_size,
         total: result.total,
         has_more: result.has_more,
-        items: result.items,
+        items: result.items.into_iter().map(match_item).collect(),
+        ranked: None,
+        truncated: false,
     };
 
+    Ok(cacheable_json(&state, response))
+}
+
+fn cacheable_json(state: &AppState, body: impl Serialize) -> Response {
     if state.disable_cache {
-        Ok(Json(response).into_response())
+        Json(body).into_response()
     } else {
-        Ok((
+        (
             [(
                 header::CACHE_CONTROL,
                 HeaderValue::from_static("public, max-age=300"),
             )],
-            Json(response),
+            Json(body),
         )
-            .into_response())
+            .into_response()
+    }
+}
+
+const DEFAULT_PHRASE_WORDS: usize = 3;
+const MAX_PHRASE_WORDS: usize = 4;
+const DEFAULT_PHRASE_RESULTS: usize = 50;
+const MAX_PHRASE_RESULTS: usize = 200;
+const PHRASE_TIME_LIMIT: Duration = Duration::from_millis(250);
+
+async fn phrase_anagrams(
+    State(state): State<AppState>,
+    axum::extract::Query(params): axum::extract::Query<PhraseAnagramQuery>,
+) -> Result<Response, ApiError> {
+    // Anagram fodder is often copied from a clue, so ignore spacing.
+    let letters: String = params
+        .letters
+        .chars()
+        .filter(|c| !c.is_whitespace())
+        .collect::<String>()
+        .to_lowercase();
+    if letters.is_empty() {
+        return Err(ApiError::bad_request("letters is required"));
+    }
+    let index = word_list(&state, params.list.as_deref())?.load();
+    let len = index
+        .alphabet()
+        .cells_of(&letters)
+        .map_or(letters.chars().count(), |cells| cells.len());
+    if len > MAX_WORD_LEN {
+        return Err(ApiError::bad_request(format!(
+            "letters must be at most {MAX_WORD_LEN}"
+        )));
+    }
+    let bag = index
+        .alphabet()
+        .parse_letter_bag(&letters, len)
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let enumeration = params
+        .enumeration
+        .as_deref()
+        .filter(|s| !s.trim().is_empty())
+        .map(|s| parse_enumeration(s, len))
+        .transpose()
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+
+    let max_words = params.max_words.unwrap_or(DEFAULT_PHRASE_WORDS);
+    if !(2..=MAX_PHRASE_WORDS).contains(&max_words) {
+        return Err(ApiError::bad_request(format!(
+            "max_words must be between 2 and {MAX_PHRASE_WORDS}"
+        )));
+    }
+    let limit = params.limit.unwrap_or(DEFAULT_PHRASE_RESULTS);
+    if limit == 0 {
+        return Err(ApiError::bad_request("limit must be >= 1"));
+    }
+    let limit = limit.min(MAX_PHRASE_RESULTS);
+
+    // The search is CPU-bound, so keep it off the async workers.
+    let min_score = params.min_score;
+    let result = tokio::task::spawn_blocking(move || {
+        index.query_phrase_anagram(PhraseAnagramParams {
+            bag_counts: bag,
+            enumeration: enumeration.as_deref(),
+            max_words,
+            min_score,
+            limit,
+            time_limit: PHRASE_TIME_LIMIT,
+        })
+    })
+    .await
+    .map_err(|_| ApiError::Internal)?;
+
+    let response = PhraseAnagramResponse {
+        letters,
+        total: result.items.len(),
+        truncated: result.truncated,
+        items: result
+            .items
+            .into_iter()
+            .map(|item| PhraseItem {
+                display: item.display(),
+                score: item.score,
+                words: item.words.into_iter().map(match_item).collect(),
+            })
+            .collect(),
+    };
+
+    // A truncated search depends on timing, so only cache complete answers.
+    if result.truncated {
+        Ok(Json(response).into_response())
+    } else {
+        Ok(cacheable_json(&state, response))
     }
 }
 
+const GRID_FILL_TIME_LIMIT: Duration = Duration::from_secs(2);
+
+async fn grid_fill(
+    State(state): State<AppState>,
+    Json(body): Json<GridFillRequest>,
+) -> Result<Response, ApiError> {
+    let index = word_list(&state, body.list.as_deref())?.load();
+    let grid = Grid::parse_with_alphabet(&body.grid, Arc::clone(index.alphabet()))
+        .map_err(|e| ApiError::bad_request(e.to_string()))?;
+    let params = FillParams {
+        min_score: body.min_score,
+        time_limit: GRID_FILL_TIME_LIMIT,
+    };
+    let input = grid.clone();
+    let result = tokio::task::spawn_blocking(move || index.fill_grid(&grid, &params))
+        .await
+        .map_err(|_| ApiError::Internal)?;
+
+    let response = match result {
+        Ok(fill) => GridFillResponse {
+            filled: true,
+            reason: None,
+            grid: fill.grid.to_rows(),
+            entries: fill
+                .entries
+                .into_iter()
+                .map(|entry| GridEntry {
+                    number: entry.slot.number,
+                    direction: match entry.slot.direction {
+                        Direction::Across => "across",
+                        Direction::Down => "down",
+                    },
+                    row: entry.slot.row,
+                    col: entry.slot.col,
+                    len: entry.slot.len,
+                    word: match_item(entry.word),
+                })
+                .collect(),
+        },
+        Err(e @ FillError::SlotTooLong(_)) => return Err(ApiError::bad_request(e.to_string())),
+        Err(e) => GridFillResponse {
+            filled: false,
+            reason: Some(e.to_string()),
+            grid: input.to_rows(),
+            entries: Vec::new(),
+        },
+    };
+
+    Ok(Json(response).into_response())
+}
+
 const BASE_HTML: &str = include_str!("../templates/base.html");
 const STYLE_HTML: &str = include_str!("../templates/style.html");
 const HEADER_HTML: &str = include_str!("../templates/header.html");
@@ -647,6 +2337,28 @@ fn parse_pos_filter(pos: Option<&str>) -> Result<Vec<Pos>, ApiError> {
     }
 }
 
+fn parse_sort(sort: Option<&str>) -> Result<SortOrder, ApiError> {
+    match sort.map(|s| s.trim().to_ascii_lowercase()).as_deref() {
+        None | Some("") | Some("score") => Ok(SortOrder::Score),
+        Some("alpha") => Ok(SortOrder::Alpha),
+        Some(_) => Err(ApiError::bad_request("sort must be one of score|alpha")),
+    }
+}
+
+fn match_item(item: WordMatch) -> MatchItem {
+    MatchItem {
+        word: item.word,
+        display: item.display,
+        score: item.score,
+        crossings: None,
+        viable: None,
+        mismatches: None,
+        fragments: None,
+        clue_score: None,
+        reasons: None,
+    }
+}
+
 fn pos_label(pos: Pos) -> &'static str {
     match pos {
         Pos::Noun => "noun",
@@ -833,6 +2545,18 @@ impl IntoResponse for ApiError {
                 let body = Json(ErrorResponse { error: msg });
                 (StatusCode::BAD_REQUEST, body).into_response()
             }
+            ApiError::Unauthorized => {
+                let body = Json(json!({ "error": "unauthorized" }));
+                (StatusCode::UNAUTHORIZED, body).into_response()
+            }
+            ApiError::NotFound => {
+                let body = Json(json!({ "error": "not found" }));
+                (StatusCode::NOT_FOUND, body).into_response()
+            }
+            ApiError::Conflict(msg) => {
+                let body = Json(ErrorResponse { error: msg });
+                (StatusCode::CONFLICT, body).into_response()
+            }
             ApiError::Internal => {
                 let body = Json(json!({ "error": "internal server error" }));
                 (StatusCode::INTERNAL_SERVER_ERROR, body).into_response()
@@ -1,30 +1,91 @@
+use std::cmp::Reverse;
 use std::collections::HashSet;
 use std::fs::File;
 use std::io::{BufRead, BufReader};
 use std::path::Path;
 use std::sync::Arc;
+use std::time::{Duration, Instant};
 
 use bitvec::prelude::*;
 use thiserror::Error;
 use tracing::{info, warn};
 
 pub const MAX_WORD_LEN: usize = 24;
-const ALPHABET: usize = 26;
+/// Upper bound on `*` tokens so wildcard expansion stays cheap.
+pub const MAX_WILDCARDS: usize = 3;
+/// Most cells an [`Alphabet`] may have; cell codes run from `b'a'` to DEL.
+pub const ALPHABET: usize = 31;
 
 type BitSet = BitVec<usize, Lsb0>;
 
+mod abbrev;
+mod alphabet;
+mod fuzzy;
+mod grid;
+mod hidden;
+mod ladder;
+mod overlay;
+mod regex;
+mod snapshot;
+mod wordle;
+mod wordplay;
+
+pub use abbrev::{
+    AbbrevAnagram, AbbrevAnagramParams, AbbrevAnagramResult, MAX_ABBREV_COMBINATIONS,
+};
+pub use alphabet::{ALPHABET_PRESETS, Alphabet, AlphabetError};
+pub use fuzzy::{Distance, FuzzyParams, FuzzyResult, MAX_MISMATCHES, mismatched_positions};
+pub use grid::{
+    Cell, Direction, Fill, FillError, FillParams, FilledSlot, Grid, GridError, MAX_GRID_SIZE, Slot,
+};
+pub use hidden::{HiddenError, HiddenWord, MAX_CLUE_LEN};
+pub use ladder::{LadderError, LadderParams, LadderResult, MAX_LADDER_STEPS};
+pub use overlay::{MAX_OVERLAY_WORDS, Overlay, OverlayError};
+pub use regex::{MAX_REGEX_LEN, RegexError, RegexParams, WordRegex};
+use snapshot::{Bits, Column};
+pub use snapshot::{SNAPSHOT_VERSION, SnapshotError, list_checksum};
+pub use wordle::{
+    Guess, GuessSuggestion, Mark, WordleConstraints, WordleError, WordleParams, WordleResult,
+};
+pub use wordplay::{Construction, Fragment, Wordplay, WordplayParams, WordplayResult};
+
 #[derive(Debug, Clone)]
 pub struct WordIndex {
     lens: Vec<Option<LenIndex>>,
+    alphabet: Arc<Alphabet>,
 }
 
+/// One length bucket. The columns and bitsets are either built in memory or
+/// borrowed straight from a mapped snapshot (see [`snapshot`]).
 #[derive(Debug, Clone)]
 struct LenIndex {
-    words: Vec<String>,
-    all: BitSet,
-    pos_letter: Vec<[BitSet; ALPHABET]>,
-    contains: [BitSet; ALPHABET],
-    letter_counts: Vec<[u8; ALPHABET]>,
+    alphabet: Arc<Alphabet>,
+    /// Cell codes of every word of the bucket back to back,
+    /// `pos_letter.len()` bytes each.
+    words: Column<u8>,
+    display: Vec<Option<String>>,
+    breaks: Column<u32>,
+    phrase: Bits,
+    scores: Vec<Option<u8>>,
+    by_score: Column<u32>,
+    all: Bits,
+    /// One bitset per cell of the alphabet for each position.
+    pos_letter: Vec<Vec<Bits>>,
+    contains: Vec<Bits>,
+    letter_counts: Column<[u8; ALPHABET]>,
+}
+
+/// One normalized word-list line before it is placed into a length bucket.
+#[derive(Debug, Clone)]
+struct Entry {
+    /// Cell codes, see [`alphabet`].
+    word: String,
+    /// Original spelling for multi-word entries such as "hot dog", or when
+    /// folding changed the letters ("café").
+    display: Option<String>,
+    /// Bit `i` set means a word break follows letter `i`.
+    breaks: u32,
+    score: Option<u8>,
 }
 
 #[derive(Debug, Error)]
@@ -33,27 +94,349 @@ pub enum IndexError {
     Io(#[from] std::io::Error),
 }
 
+/// A parsed answer pattern with one slot per letter.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Pattern {
+    /// Fixed letter per position, `None` for open slots.
+    pub letters: Vec<Option<u8>>,
+    /// Allowed letters per position for `[aeiou]`, `[^st]`, `@` and `#` slots.
+    pub classes: Vec<Option<LetterSet>>,
+    /// Repeated-letter variable per position. Slots sharing an id hold the
+    /// same letter; slots with different ids hold different letters.
+    pub vars: Vec<Option<u8>>,
+}
+
+impl Pattern {
+    pub fn len(&self) -> usize {
+        self.letters.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.letters.is_empty()
+    }
+
+    pub fn has_vars(&self) -> bool {
+        self.vars.iter().any(Option::is_some)
+    }
+
+    /// Narrow slot `pos` to `allowed` on top of whatever it already requires.
+    pub fn restrict(&mut self, pos: usize, allowed: LetterSet) {
+        let current = self.classes[pos].unwrap_or(LetterSet::ALL);
+        self.classes[pos] = Some(current.intersection(allowed));
+    }
+
+    /// Whether the cell codes of one word fit every slot, for words that are
+    /// not in an index.
+    pub fn matches(&self, cells: &[u8]) -> bool {
+        if cells.len() != self.len() {
+            return false;
+        }
+        // Letter held by each variable seen so far.
+        let mut held: Vec<(u8, u8)> = Vec::new();
+        for (pos, &letter) in cells.iter().enumerate() {
+            if self.letters[pos].is_some_and(|fixed| fixed != letter)
+                || self.classes[pos].is_some_and(|class| !class.contains(letter))
+            {
+                return false;
+            }
+            let Some(var) = self.vars[pos] else {
+                continue;
+            };
+            match held.iter().find(|(v, _)| *v == var) {
+                Some((_, first)) if *first != letter => return false,
+                Some(_) => {}
+                None if held.iter().any(|(_, first)| *first == letter) => return false,
+                None => held.push((var, letter)),
+            }
+        }
+        true
+    }
+}
+
+/// A partially filled slot crossing the one being searched: letter `pos` of
+/// the searched word is letter `cross_pos` of `pattern`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Crossing {
+    pub pos: usize,
+    pub pattern: Pattern,
+    pub cross_pos: usize,
+}
+
+impl From<Vec<Option<u8>>> for Pattern {
+    fn from(letters: Vec<Option<u8>>) -> Self {
+        let len = letters.len();
+        Self {
+            letters,
+            classes: vec![None; len],
+            vars: vec![None; len],
+        }
+    }
+}
+
+/// A set of cell codes (`b'a'` up to `b'a' + ALPHABET`) stored as a
+/// bitmask. Vowel and consonant classes depend on the alphabet; see
+/// [`Alphabet::vowels`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct LetterSet(u32);
+
+impl LetterSet {
+    /// Every cell code of the largest possible alphabet.
+    pub const ALL: LetterSet = LetterSet((1 << ALPHABET) - 1);
+
+    pub fn insert(&mut self, letter: u8) {
+        self.0 |= 1 << (letter - b'a');
+    }
+
+    pub fn contains(&self, letter: u8) -> bool {
+        (b'a'..b'a' + ALPHABET as u8).contains(&letter) && self.0 & (1 << (letter - b'a')) != 0
+    }
+
+    pub fn complement(self) -> LetterSet {
+        LetterSet(Self::ALL.0 & !self.0)
+    }
+
+    pub fn intersection(self, other: LetterSet) -> LetterSet {
+        LetterSet(self.0 & other.0)
+    }
+
+    pub fn union(self, other: LetterSet) -> LetterSet {
+        LetterSet(self.0 | other.0)
+    }
+
+    pub fn len(&self) -> usize {
+        self.0.count_ones() as usize
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.0 == 0
+    }
+
+    pub fn iter(self) -> impl Iterator<Item = u8> {
+        (0..ALPHABET as u8)
+            .filter(move |idx| self.0 & (1 << idx) != 0)
+            .map(|idx| b'a' + idx)
+    }
+}
+
+/// A pattern containing `*` wildcards (zero or more letters), kept as the
+/// fixed-length segments between them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WildcardPattern {
+    pub segments: Vec<Pattern>,
+}
+
+impl WildcardPattern {
+    /// Number of letters pinned by the segments themselves.
+    pub fn fixed_len(&self) -> usize {
+        self.segments.iter().map(Pattern::len).sum()
+    }
+
+    /// Every fixed-length pattern of `len` letters this pattern can stand for.
+    pub fn expand(&self, len: usize) -> Vec<Pattern> {
+        let Some(free) = len.checked_sub(self.fixed_len()) else {
+            return Vec::new();
+        };
+        let stars = self.segments.len() - 1;
+        if stars == 0 {
+            return if free == 0 {
+                self.segments.clone()
+            } else {
+                Vec::new()
+            };
+        }
+
+        let mut out = Vec::new();
+        let mut gaps = vec![0usize; stars];
+        expand_gaps(&mut gaps, 0, free, &mut |gaps| {
+            let mut pattern = Pattern::from(Vec::with_capacity(len));
+            for (i, segment) in self.segments.iter().enumerate() {
+                pattern.letters.extend_from_slice(&segment.letters);
+                pattern.classes.extend_from_slice(&segment.classes);
+                pattern.vars.extend_from_slice(&segment.vars);
+                if let Some(gap) = gaps.get(i) {
+                    pattern.letters.extend(std::iter::repeat_n(None, *gap));
+                    pattern.classes.extend(std::iter::repeat_n(None, *gap));
+                    pattern.vars.extend(std::iter::repeat_n(None, *gap));
+                }
+            }
+            out.push(pattern);
+        });
+        out
+    }
+}
+
+/// Visit every way of spreading `free` blanks over the wildcard gaps.
+fn expand_gaps(gaps: &mut [usize], at: usize, free: usize, visit: &mut dyn FnMut(&[usize])) {
+    if at + 1 == gaps.len() {
+        gaps[at] = free;
+        visit(gaps);
+        return;
+    }
+    for take in 0..=free {
+        gaps[at] = take;
+        expand_gaps(gaps, at + 1, free - take, visit);
+    }
+}
+
+/// Order in which matching words are paged out.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq)]
+pub enum SortOrder {
+    /// Highest score first; unscored words last; ties alphabetical.
+    #[default]
+    Score,
+    /// Plain alphabetical order.
+    Alpha,
+}
+
 #[derive(Debug)]
 pub struct QueryParams<'a> {
-    pub pattern: &'a [Option<u8>],
+    pub pattern: &'a Pattern,
     pub must_include: &'a [u8],
     pub cannot_include: &'a [u8],
+    /// Word lengths of a multi-word answer, e.g. `[3, 4]` for (3,4).
+    pub enumeration: Option<&'a [usize]>,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
     pub page: usize,
     pub page_size: usize,
+    /// Extra and excluded words for this query only.
+    pub overlay: Option<&'a Overlay>,
+}
+
+#[derive(Debug)]
+pub struct WildcardParams<'a> {
+    pub pattern: &'a WildcardPattern,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub must_include: &'a [u8],
+    pub cannot_include: &'a [u8],
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+    pub overlay: Option<&'a Overlay>,
+}
+
+/// Filters applied after the slot constraints of a pattern.
+struct Filters<'a> {
+    must_include: &'a [u8],
+    cannot_include: &'a [u8],
+    enumeration: Option<&'a [usize]>,
+    min_score: Option<u8>,
 }
 
 #[derive(Debug)]
 pub struct AnagramParams<'a> {
-    pub pattern: &'a [Option<u8>],
+    pub pattern: &'a Pattern,
     pub bag_counts: [u8; ALPHABET],
+    /// Number of `?` blank tiles that may stand for any letter.
+    pub blanks: u8,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
     pub page: usize,
     pub page_size: usize,
 }
 
+/// A matching word together with its score from the word list, if any.
+///
+/// `word` holds the letters only, folded onto the list's alphabet; `display`
+/// keeps the original spelling of multi-word and folded entries ("hot dog",
+/// "café") and equals `word` otherwise.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordMatch {
+    pub word: String,
+    pub display: String,
+    pub score: Option<u8>,
+}
+
+/// A phrase from outside the list, such as a WordNet lemma, spelled with
+/// the list's alphabet.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Spelled {
+    /// Cell codes of the letters, without spaces or hyphens.
+    pub cells: Vec<u8>,
+    /// Word lengths, e.g. `[3, 3]` for "hot dog".
+    pub enumeration: Vec<usize>,
+    /// The list's entry with the same letters, if there is one.
+    pub entry: Option<WordMatch>,
+}
+
 #[derive(Debug, Clone)]
 pub struct QueryResult {
     pub total: usize,
-    pub items: Vec<String>,
+    pub items: Vec<WordMatch>,
+    pub has_more: bool,
+}
+
+/// Parameters for "what words can I make from these tiles": the bag is an
+/// upper bound rather than an exact multiset.
+#[derive(Debug)]
+pub struct SubAnagramParams {
+    pub bag_counts: [u8; ALPHABET],
+    pub blanks: u8,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// Parameters for splitting a bag of letters into several words that use it
+/// exactly, e.g. "dormitory" into "dirty room".
+#[derive(Debug)]
+pub struct PhraseAnagramParams<'a> {
+    pub bag_counts: [u8; ALPHABET],
+    /// Word lengths in order, e.g. `[5, 4]` for "(5,4)". Without one, any
+    /// split into two to `max_words` words is tried.
+    pub enumeration: Option<&'a [usize]>,
+    pub max_words: usize,
+    pub min_score: Option<u8>,
+    /// Stop after this many phrases.
+    pub limit: usize,
+    /// Stop searching once this much time has passed.
+    pub time_limit: Duration,
+}
+
+/// One multi-word anagram; its score is that of its weakest word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PhraseMatch {
+    pub words: Vec<WordMatch>,
+    pub score: Option<u8>,
+}
+
+impl PhraseMatch {
+    /// The words joined with spaces, each in its original spelling.
+    pub fn display(&self) -> String {
+        self.words
+            .iter()
+            .map(|w| w.display.as_str())
+            .collect::<Vec<_>>()
+            .join(" ")
+    }
+}
+
+#[derive(Debug, Clone)]
+pub struct PhraseResult {
+    pub items: Vec<PhraseMatch>,
+    /// Set when the result limit or time budget cut the search short.
+    pub truncated: bool,
+}
+
+/// Matches of a single word length within a [`GroupedResult`].
+#[derive(Debug, Clone)]
+pub struct LengthGroup {
+    pub len: usize,
+    /// Matches of this length across all pages.
+    pub total: usize,
+    pub items: Vec<WordMatch>,
+}
+
+/// A page of results grouped by word length, longest first.
+#[derive(Debug, Clone)]
+pub struct GroupedResult {
+    pub total: usize,
+    pub groups: Vec<LengthGroup>,
     pub has_more: bool,
 }
 
@@ -61,128 +444,233 @@ impl WordIndex {
     pub fn empty() -> Self {
         Self {
             lens: vec![None; MAX_WORD_LEN + 1],
+            alphabet: Alphabet::english(),
         }
     }
 
+    /// Build an index from a word list with one entry per line.
+    ///
+    /// Lines may carry an optional score in the `word;score` form used by
+    /// `spreadthewordlist.dict`; bare words are indexed without a score.
+    /// Spaces and hyphens mark word breaks in phrases ("hot dog", "well-read").
     pub fn build_from_file<P: AsRef<Path>>(path: P) -> Result<Arc<Self>, IndexError> {
+        Self::build_from_file_with_alphabet(path, Alphabet::english())
+    }
+
+    /// Like [`WordIndex::build_from_file`], folding entries onto `alphabet`.
+    /// Entries with characters outside the alphabet are skipped.
+    pub fn build_from_file_with_alphabet<P: AsRef<Path>>(
+        path: P,
+        alphabet: Arc<Alphabet>,
+    ) -> Result<Arc<Self>, IndexError> {
         let path_ref = path.as_ref();
-        let mut buckets: Vec<Vec<String>> = vec![Vec::new(); MAX_WORD_LEN + 1];
+        let mut buckets: Vec<Vec<Entry>> = vec![Vec::new(); MAX_WORD_LEN + 1];
 
         let file = File::open(path_ref)?;
         let reader = BufReader::new(file);
+        let mut skipped = 0usize;
+        let mut bad_scores = 0usize;
         for line in reader.lines() {
             let raw = line?;
-            if let Some(word) = normalize_word(&raw) {
-                buckets[word.len()].push(word);
+            let Some(entry) = parse_entry(&raw, &alphabet) else {
+                if !raw.trim().is_empty() {
+                    skipped += 1;
+                }
+                continue;
+            };
+            if entry.score.is_none() && malformed_score(&raw) {
+                bad_scores += 1;
+            }
+            buckets[entry.word.len()].push(entry);
+        }
+        if skipped > 0 {
+            warn!(
+                "skipped {skipped} entries of {} with characters outside the {} alphabet",
+                path_ref.display(),
+                alphabet.name()
+            );
+        }
+        if bad_scores > 0 {
+            warn!(
+                "{bad_scores} entries of {} have a score that is not a number from 0 to 255; \
+                 they are indexed without one",
+                path_ref.display()
+            );
+        }
+
+        let index = Self::from_buckets(buckets, alphabet);
+        for (len, len_index) in index.lens.iter().enumerate() {
+            if let Some(len_index) = len_index {
+                info!("loaded {} words of length {len}", len_index.count());
             }
         }
+        info!("total words indexed: {}", index.word_count());
+        Ok(Arc::new(index))
+    }
 
+    /// Index entries already grouped by length.
+    fn from_buckets(buckets: Vec<Vec<Entry>>, alphabet: Arc<Alphabet>) -> Self {
         let mut lens = vec![None; MAX_WORD_LEN + 1];
-        let mut total = 0usize;
         for (len, bucket) in buckets.into_iter().enumerate() {
             if len == 0 || bucket.is_empty() {
                 continue;
             }
             let mut sorted = bucket;
-            sorted.sort();
-            sorted.dedup();
-
-            let count = sorted.len();
-            if let Some(len_index) = LenIndex::build(len, sorted) {
-                info!("loaded {count} words of length {len}");
-                total += count;
-                lens[len] = Some(len_index);
-            } else {
-                warn!("skipped length {len} with empty bucket after normalization");
-            }
+            // Highest score first within equal entries so dedup keeps the best one.
+            sorted.sort_by(|a, b| {
+                a.word
+                    .cmp(&b.word)
+                    .then_with(|| a.breaks.cmp(&b.breaks))
+                    .then_with(|| a.display.cmp(&b.display))
+                    .then_with(|| b.score.cmp(&a.score))
+            });
+            sorted.dedup_by(|a, b| a.word == b.word && a.display == b.display);
+            lens[len] = LenIndex::build(len, sorted, &alphabet);
         }
+        Self { lens, alphabet }
+    }
+
+    /// The alphabet the index was built with; queries must be parsed with it.
+    pub fn alphabet(&self) -> &Arc<Alphabet> {
+        &self.alphabet
+    }
 
-        info!("total words indexed: {total}");
-        Ok(Arc::new(Self { lens }))
+    /// Number of distinct entries across all length buckets.
+    pub fn word_count(&self) -> usize {
+        self.lens.iter().flatten().map(LenIndex::count).sum()
+    }
+
+    /// Spell `text` the way a word-list line would be read, or `None` if
+    /// it uses letters outside the alphabet.
+    pub fn spell(&self, text: &str) -> Option<Spelled> {
+        let (cells, breaks) = normalize_word(text, &self.alphabet)?;
+        let mut enumeration = Vec::new();
+        let mut start = 0;
+        for pos in 0..cells.len() {
+            if breaks & (1 << pos) != 0 || pos + 1 == cells.len() {
+                enumeration.push(pos + 1 - start);
+                start = pos + 1;
+            }
+        }
+        let entry = self
+            .lens
+            .get(cells.len())
+            .and_then(Option::as_ref)
+            .and_then(|len_index| {
+                let mut found = len_index.find(&cells);
+                found.next().map(|idx| len_index.word_match(idx))
+            });
+        Some(Spelled {
+            cells: cells.into_bytes(),
+            enumeration,
+            entry,
+        })
     }
 
     pub fn query(&self, params: QueryParams<'_>) -> QueryResult {
-        let len = params.pattern.len();
-        let Some(len_index) = self.lens.get(len).and_then(|o| o.as_ref()) else {
-            return QueryResult {
-                total: 0,
-                items: Vec::new(),
-                has_more: false,
-            };
+        let filters = Filters {
+            must_include: params.must_include,
+            cannot_include: params.cannot_include,
+            enumeration: params.enumeration,
+            min_score: params.min_score,
         };
+        let hits = self.select(params.pattern.len(), params.overlay, |len_index| {
+            let mut candidates = len_index.pattern_candidates(params.pattern);
+            len_index.apply_filters(&mut candidates, &filters);
+            candidates
+        });
 
-        let mut candidates = len_index.all.clone();
+        let buckets: Vec<(&LenIndex, &BitSet)> = hits.iter().map(|(l, c)| (*l, c)).collect();
+        page_buckets(&buckets, params.sort, params.page, params.page_size)
+    }
 
-        for (pos, ch) in params.pattern.iter().enumerate() {
-            if let Some(letter) = ch {
-                let idx = (letter - b'a') as usize;
-                candidates &= &len_index.pos_letter[pos][idx];
-                if candidates.not_any() {
-                    break;
-                }
+    /// Run `candidates` against the bucket of length `len` and, with an
+    /// overlay, against its allowed words too, then drop what the overlay
+    /// excludes. Buckets left without candidates are skipped.
+    fn select<'a>(
+        &'a self,
+        len: usize,
+        overlay: Option<&'a Overlay>,
+        candidates: impl Fn(&LenIndex) -> BitSet,
+    ) -> Vec<(&'a LenIndex, BitSet)> {
+        let mut hits = Vec::new();
+        if let Some(len_index) = self.lens.get(len).and_then(|o| o.as_ref()) {
+            let mut found = candidates(len_index);
+            if let Some(overlay) = overlay {
+                overlay.mask_base(len_index, &mut found);
+            }
+            if found.any() {
+                hits.push((len_index, found));
             }
         }
-
-        if candidates.not_any() {
-            return QueryResult {
-                total: 0,
-                items: Vec::new(),
-                has_more: false,
-            };
-        }
-
-        for letter in params.must_include {
-            let idx = (*letter - b'a') as usize;
-            candidates &= &len_index.contains[idx];
-            if candidates.not_any() {
-                break;
+        if let Some(overlay) = overlay
+            && let Some(extra) = overlay.extra(len)
+        {
+            let mut found = candidates(extra);
+            overlay.mask_extra(extra, &mut found);
+            if found.any() {
+                hits.push((extra, found));
             }
         }
+        hits
+    }
 
-        if candidates.not_any() {
-            return QueryResult {
-                total: 0,
-                items: Vec::new(),
-                has_more: false,
-            };
+    /// For each letter, how many words fit `pattern` with that letter at
+    /// `pos`. Used to tell which letters keep a crossing slot fillable.
+    pub fn letter_counts_at(
+        &self,
+        pattern: &Pattern,
+        pos: usize,
+        min_score: Option<u8>,
+        overlay: Option<&Overlay>,
+    ) -> [usize; ALPHABET] {
+        let mut counts = [0usize; ALPHABET];
+        if pos >= pattern.len() {
+            return counts;
         }
-
-        for letter in params.cannot_include {
-            let idx = (*letter - b'a') as usize;
-            let mask = !len_index.contains[idx].clone();
-            candidates &= &mask;
-            if candidates.not_any() {
-                break;
+        let hits = self.select(pattern.len(), overlay, |len_index| {
+            let mut candidates = len_index.pattern_candidates(pattern);
+            if let Some(min_score) = min_score {
+                candidates &= &len_index.score_mask(min_score);
+            }
+            candidates
+        });
+        for (len_index, candidates) in &hits {
+            for idx in candidates.iter_ones() {
+                counts[(len_index.word(idx).as_bytes()[pos] - b'a') as usize] += 1;
             }
         }
+        counts
+    }
 
-        let total = candidates.count_ones();
-        if total == 0 {
-            return QueryResult {
-                total: 0,
-                items: Vec::new(),
-                has_more: false,
-            };
-        }
+    /// Match a `*` pattern against every length bucket in `min_len..=max_len`
+    /// and page through the combined results.
+    pub fn query_wildcard(&self, params: WildcardParams<'_>) -> QueryResult {
+        let filters = Filters {
+            must_include: params.must_include,
+            cannot_include: params.cannot_include,
+            enumeration: None,
+            min_score: params.min_score,
+        };
 
-        let offset = params
-            .page
-            .saturating_sub(1)
-            .saturating_mul(params.page_size);
-        let mut items = Vec::with_capacity(params.page_size.min(total));
-        for idx in candidates.iter_ones().skip(offset).take(params.page_size) {
-            if let Some(word) = len_index.words.get(idx) {
-                items.push(word.clone());
+        let mut hits = Vec::new();
+        for len in params.min_len.max(1)..=params.max_len.min(MAX_WORD_LEN) {
+            let patterns = params.pattern.expand(len);
+            if patterns.is_empty() {
+                continue;
             }
+            hits.extend(self.select(len, params.overlay, |len_index| {
+                let mut candidates = bitvec![usize, Lsb0; 0; len_index.count()];
+                for pattern in &patterns {
+                    candidates |= &len_index.pattern_candidates(pattern);
+                }
+                len_index.apply_filters(&mut candidates, &filters);
+                candidates
+            }));
         }
 
-        let has_more = offset + items.len() < total;
-
-        QueryResult {
-            total,
-            items,
-            has_more,
-        }
+        let buckets: Vec<(&LenIndex, &BitSet)> = hits.iter().map(|(l, c)| (*l, c)).collect();
+        page_buckets(&buckets, params.sort, params.page, params.page_size)
     }
 
     pub fn query_anagram(&self, params: AnagramParams<'_>) -> QueryResult {
@@ -195,16 +683,7 @@ impl WordIndex {
             };
         };
 
-        let mut candidates = len_index.all.clone();
-        for (pos, ch) in params.pattern.iter().enumerate() {
-            if let Some(letter) = ch {
-                let idx = (letter - b'a') as usize;
-                candidates &= &len_index.pos_letter[pos][idx];
-                if candidates.not_any() {
-                    break;
-                }
-            }
-        }
+        let mut candidates = len_index.pattern_candidates(params.pattern);
 
         if candidates.not_any() {
             return QueryResult {
@@ -214,54 +693,159 @@ impl WordIndex {
             };
         }
 
+        if let Some(min_score) = params.min_score {
+            candidates &= &len_index.score_mask(min_score);
+        }
+
+        // The pattern fixes the length, so fitting the bag means using it exactly.
+        len_index.retain_bag(&mut candidates, &params.bag_counts, params.blanks);
+
+        len_index.page(&candidates, params.sort, params.page, params.page_size)
+    }
+
+    /// Find word combinations that use the bag exactly, best-scoring words
+    /// first, within the result and time limits. Results are ordered by
+    /// score, then alphabetically.
+    pub fn query_phrase_anagram(&self, params: PhraseAnagramParams<'_>) -> PhraseResult {
+        let mut bag = params.bag_counts;
+        let remaining = bag.iter().map(|c| *c as usize).sum();
+        let mut search = PhraseSearch {
+            index: self,
+            params: &params,
+            deadline: Instant::now() + params.time_limit,
+            path: Vec::new(),
+            found: Vec::new(),
+            truncated: false,
+        };
+        search.descend(&mut bag, remaining, 0, 0);
+
+        let truncated = search.truncated;
+        let mut items: Vec<PhraseMatch> = search
+            .found
+            .into_iter()
+            .map(|path| {
+                let words: Vec<WordMatch> = path
+                    .into_iter()
+                    .map(|(len, idx)| self.bucket(len).word_match(idx))
+                    .collect();
+                let score = words.iter().map(|w| w.score).min().flatten();
+                PhraseMatch { words, score }
+            })
+            .collect();
+        items.sort_by_cached_key(|item| (Reverse(item.score), item.display()));
+        PhraseResult { items, truncated }
+    }
+
+    fn bucket(&self, len: usize) -> &LenIndex {
+        self.lens[len]
+            .as_ref()
+            .expect("bucket of a found word exists")
+    }
+
+    /// Find every word buildable from a subset of the bag, grouped by length
+    /// (longest first) and paged across the groups in that order.
+    pub fn query_subanagram(&self, params: SubAnagramParams) -> GroupedResult {
+        let tiles =
+            params.bag_counts.iter().map(|c| *c as usize).sum::<usize>() + params.blanks as usize;
+        let max_len = params.max_len.min(tiles).min(MAX_WORD_LEN);
+
+        let mut hits = Vec::new();
+        for len in (params.min_len.max(1)..=max_len).rev() {
+            let Some(len_index) = self.lens.get(len).and_then(|o| o.as_ref()) else {
+                continue;
+            };
+            let mut candidates = len_index.all.to_bitvec();
+            if params.blanks == 0 {
+                let cells = len_index.contains.len();
+                for (letter, count) in params.bag_counts.iter().enumerate().take(cells) {
+                    if *count == 0 {
+                        let mask = !len_index.contains[letter].to_bitvec();
+                        candidates &= &mask;
+                    }
+                }
+            }
+            if let Some(min_score) = params.min_score {
+                candidates &= &len_index.score_mask(min_score);
+            }
+            len_index.retain_bag(&mut candidates, &params.bag_counts, params.blanks);
+            let total = candidates.count_ones();
+            if total > 0 {
+                hits.push((len, len_index, candidates, total));
+            }
+        }
+
+        let total: usize = hits.iter().map(|hit| hit.3).sum();
         let offset = params
             .page
             .saturating_sub(1)
             .saturating_mul(params.page_size);
-        let mut total = 0usize;
-        let mut items = Vec::with_capacity(params.page_size);
-
-        for idx in candidates.iter_ones() {
-            if let Some(counts) = len_index.letter_counts.get(idx)
-                && *counts == params.bag_counts
-            {
-                total += 1;
-                if total > offset
-                    && items.len() < params.page_size
-                    && let Some(word) = len_index.words.get(idx)
-                {
-                    items.push(word.clone());
-                }
+        let mut skip = offset;
+        let mut remaining = params.page_size;
+        let mut groups = Vec::new();
+        for (len, len_index, candidates, group_total) in &hits {
+            if remaining == 0 {
+                break;
+            }
+            if skip >= *group_total {
+                skip -= group_total;
+                continue;
             }
+            let items: Vec<WordMatch> = len_index
+                .ordered(candidates, params.sort)
+                .skip(skip)
+                .take(remaining)
+                .map(|idx| len_index.word_match(idx))
+                .collect();
+            skip = 0;
+            remaining -= items.len();
+            groups.push(LengthGroup {
+                len: *len,
+                total: *group_total,
+                items,
+            });
         }
 
-        let has_more = offset + items.len() < total;
-
-        QueryResult {
+        let shown: usize = groups.iter().map(|g| g.items.len()).sum();
+        GroupedResult {
             total,
-            items,
-            has_more,
+            groups,
+            has_more: offset + shown < total,
         }
     }
 }
 
 impl LenIndex {
-    fn build(len: usize, words: Vec<String>) -> Option<Self> {
-        let n = words.len();
+    fn build(len: usize, entries: Vec<Entry>, alphabet: &Arc<Alphabet>) -> Option<Self> {
+        let n = entries.len();
         if n == 0 {
             return None;
         }
+        let mut words = Vec::with_capacity(n * len);
+        let mut display = Vec::with_capacity(n);
+        let mut breaks = Vec::with_capacity(n);
+        let mut scores = Vec::with_capacity(n);
+        for entry in entries {
+            words.extend_from_slice(entry.word.as_bytes());
+            display.push(entry.display);
+            breaks.push(entry.breaks);
+            scores.push(entry.score);
+        }
+        let phrase: BitSet = breaks.iter().map(|b| *b != 0).collect();
+
+        // Words are already alphabetical, so a stable sort keeps ties in order.
+        let mut by_score: Vec<u32> = (0..n as u32).collect();
+        by_score.sort_by_key(|idx| std::cmp::Reverse(scores[*idx as usize]));
 
-        let mut pos_letter: Vec<[BitSet; ALPHABET]> = (0..len)
-            .map(|_| array_init::array_init(|_| bitvec![usize, Lsb0; 0; n]))
+        let cells = alphabet.len();
+        let mut pos_letter: Vec<Vec<BitSet>> = (0..len)
+            .map(|_| vec![bitvec![usize, Lsb0; 0; n]; cells])
             .collect();
-        let mut contains: [BitSet; ALPHABET] =
-            array_init::array_init(|_| bitvec![usize, Lsb0; 0; n]);
+        let mut contains: Vec<BitSet> = vec![bitvec![usize, Lsb0; 0; n]; cells];
         let mut letter_counts: Vec<[u8; ALPHABET]> = Vec::with_capacity(n);
 
-        for (idx, word) in words.iter().enumerate() {
+        for (idx, word) in words.chunks_exact(len).enumerate() {
             let mut counts = [0u8; ALPHABET];
-            for (pos, ch) in word.bytes().enumerate() {
+            for (pos, &ch) in word.iter().enumerate() {
                 let letter_idx = (ch - b'a') as usize;
                 counts[letter_idx] = counts[letter_idx].saturating_add(1);
                 if let Some(bitvec) = pos_letter
@@ -278,80 +862,811 @@ impl LenIndex {
         }
 
         Some(Self {
-            words,
-            all: bitvec![usize, Lsb0; 1; n],
-            pos_letter,
-            contains,
-            letter_counts,
+            alphabet: Arc::clone(alphabet),
+            words: words.into(),
+            display,
+            breaks: breaks.into(),
+            phrase: phrase.into(),
+            scores,
+            by_score: by_score.into(),
+            all: bitvec![usize, Lsb0; 1; n].into(),
+            pos_letter: pos_letter
+                .into_iter()
+                .map(|letters| letters.into_iter().map(Bits::from).collect())
+                .collect(),
+            contains: contains.into_iter().map(Bits::from).collect(),
+            letter_counts: letter_counts.into(),
         })
     }
-}
 
-fn normalize_word(raw: &str) -> Option<String> {
-    let mut normalized = String::with_capacity(raw.len());
-    for c in raw.chars() {
-        if !c.is_ascii_alphabetic() {
-            normalized.clear();
-            return None;
-        }
-        normalized.push(c.to_ascii_lowercase());
+    /// Number of words in the bucket.
+    fn count(&self) -> usize {
+        self.all.len()
     }
-    let len = normalized.len();
-    if (1..=MAX_WORD_LEN).contains(&len) {
-        Some(normalized)
-    } else {
-        None
+
+    fn word(&self, idx: usize) -> &str {
+        let len = self.pos_letter.len();
+        std::str::from_utf8(&self.words[idx * len..(idx + 1) * len])
+            .expect("indexed words are ascii")
     }
-}
 
-pub fn parse_pattern(raw: &str) -> Result<Vec<Option<u8>>, PatternError> {
-    let mut result = Vec::with_capacity(raw.len());
-    for c in raw.chars() {
-        match c {
-            '_' | '?' | '.' => result.push(None),
-            letter if letter.is_ascii_alphabetic() => {
-                result.push(Some(letter.to_ascii_lowercase() as u8));
+    /// Words matching every slot of `pattern`: fixed letters, letter classes
+    /// and repeated-letter variables.
+    fn pattern_candidates(&self, pattern: &Pattern) -> BitSet {
+        let mut candidates = self.all.to_bitvec();
+
+        for (pos, ch) in pattern.letters.iter().enumerate() {
+            if let Some(letter) = ch {
+                let idx = (letter - b'a') as usize;
+                candidates &= &*self.pos_letter[pos][idx];
+                if candidates.not_any() {
+                    return candidates;
+                }
             }
-            other => return Err(PatternError::InvalidChar(other)),
         }
-    }
-    let len = result.len();
-    if len == 0 || len > MAX_WORD_LEN {
-        return Err(PatternError::InvalidLength(MAX_WORD_LEN, len));
-    }
-    Ok(result)
-}
 
-pub fn parse_letters(raw: &str) -> Result<Vec<u8>, PatternError> {
-    let mut seen = HashSet::new();
-    let mut result = Vec::new();
-    for c in raw.chars() {
-        if !c.is_ascii_alphabetic() {
-            return Err(PatternError::InvalidChar(c));
-        }
-        let lower = c.to_ascii_lowercase() as u8;
-        if seen.insert(lower) {
-            result.push(lower);
+        for (pos, class) in pattern.classes.iter().enumerate() {
+            if let Some(class) = class {
+                candidates &= &self.class_mask(pos, *class);
+                if candidates.not_any() {
+                    return candidates;
+                }
+            }
         }
+
+        self.retain_variables(&mut candidates, &pattern.vars);
+        candidates
     }
-    Ok(result)
-}
 
-pub fn parse_letter_bag(raw: &str, expected_len: usize) -> Result<[u8; ALPHABET], PatternError> {
-    let mut counts = [0u8; ALPHABET];
-    let mut seen_len = 0usize;
-    for c in raw.chars() {
-        if !c.is_ascii_alphabetic() {
-            return Err(PatternError::InvalidChar(c));
+    /// Words whose letter at `pos` belongs to `class`, built by OR-ing the
+    /// positional bitsets (or clearing the excluded ones when that is cheaper).
+    fn class_mask(&self, pos: usize, class: LetterSet) -> BitSet {
+        let cells = self.alphabet.all();
+        let class = class.intersection(cells);
+        if class.len() * 2 <= cells.len() {
+            let mut mask = bitvec![usize, Lsb0; 0; self.count()];
+            for letter in class.iter() {
+                mask |= &*self.pos_letter[pos][(letter - b'a') as usize];
+            }
+            mask
+        } else {
+            let mut mask = self.all.to_bitvec();
+            for letter in class.complement().intersection(cells).iter() {
+                mask &= &!self.pos_letter[pos][(letter - b'a') as usize].to_bitvec();
+            }
+            mask
         }
-        let lower = c.to_ascii_lowercase() as u8;
-        counts[(lower - b'a') as usize] = counts[(lower - b'a') as usize].saturating_add(1);
-        seen_len += 1;
     }
-    if seen_len != expected_len {
-        return Err(PatternError::InvalidLength(expected_len, seen_len));
+
+    /// Enforce repeated-letter variables: equal ids must share a letter and
+    /// distinct ids must differ.
+    ///
+    /// Equality is applied with the positional bitsets by pinning every slot
+    /// of a variable to the same letter as its first slot; inequality between
+    /// variables is then checked word by word on the survivors.
+    fn retain_variables(&self, candidates: &mut BitSet, vars: &[Option<u8>]) {
+        let mut first_pos: Vec<(u8, usize)> = Vec::new();
+        for (pos, var) in vars.iter().enumerate() {
+            let Some(var) = var else {
+                continue;
+            };
+            match first_pos.iter().find(|(v, _)| v == var) {
+                Some((_, first)) => {
+                    *candidates &= &self.same_letter(*first, pos);
+                    if candidates.not_any() {
+                        return;
+                    }
+                }
+                None => first_pos.push((*var, pos)),
+            }
+        }
+
+        if first_pos.len() < 2 {
+            return;
+        }
+        let survivors: Vec<usize> = candidates.iter_ones().collect();
+        for idx in survivors {
+            let bytes = self.word(idx).as_bytes();
+            let mut seen = 0u32;
+            let distinct = first_pos.iter().all(|(_, pos)| {
+                let bit = 1 << (bytes[*pos] - b'a');
+                let fresh = seen & bit == 0;
+                seen |= bit;
+                fresh
+            });
+            if !distinct {
+                candidates.set(idx, false);
+            }
+        }
+    }
+
+    /// Words whose letters at positions `a` and `b` are equal.
+    fn same_letter(&self, a: usize, b: usize) -> BitSet {
+        let mut mask = bitvec![usize, Lsb0; 0; self.count()];
+        for letter in 0..self.contains.len() {
+            let mut both = self.pos_letter[a][letter].to_bitvec();
+            both &= &*self.pos_letter[b][letter];
+            mask |= &both;
+        }
+        mask
+    }
+
+    /// Apply the letter-inclusion, enumeration and score filters shared by
+    /// every pattern query.
+    fn apply_filters(&self, candidates: &mut BitSet, filters: &Filters<'_>) {
+        for letter in filters.must_include {
+            if candidates.not_any() {
+                return;
+            }
+            let idx = (*letter - b'a') as usize;
+            *candidates &= &*self.contains[idx];
+        }
+
+        for letter in filters.cannot_include {
+            if candidates.not_any() {
+                return;
+            }
+            let idx = (*letter - b'a') as usize;
+            let mask = !self.contains[idx].to_bitvec();
+            *candidates &= &mask;
+        }
+
+        if let Some(lengths) = filters.enumeration {
+            if lengths.iter().sum::<usize>() == self.pos_letter.len() {
+                self.retain_enumeration(candidates, enumeration_breaks(lengths));
+            } else {
+                candidates.fill(false);
+            }
+        }
+
+        if let Some(min_score) = filters.min_score {
+            *candidates &= &self.score_mask(min_score);
+        }
+    }
+
+    /// Keep only candidates that can be spelled from the bag, with each blank
+    /// covering one missing letter.
+    fn retain_bag(&self, candidates: &mut BitSet, bag: &[u8; ALPHABET], blanks: u8) {
+        let matching: Vec<usize> = candidates.iter_ones().collect();
+        for idx in matching {
+            let missing: usize = self.letter_counts[idx]
+                .iter()
+                .zip(bag)
+                .map(|(need, have)| need.saturating_sub(*have) as usize)
+                .sum();
+            if missing > blanks as usize {
+                candidates.set(idx, false);
+            }
+        }
+    }
+
+    /// Single words (not phrases) that can be spelled from `bag` without blanks.
+    fn bag_candidates(&self, bag: &[u8; ALPHABET], min_score: Option<u8>) -> BitSet {
+        let mut candidates = !self.phrase.to_bitvec();
+        for (letter, count) in bag.iter().enumerate().take(self.contains.len()) {
+            if *count == 0 {
+                let mask = !self.contains[letter].to_bitvec();
+                candidates &= &mask;
+            }
+        }
+        if let Some(min_score) = min_score {
+            candidates &= &self.score_mask(min_score);
+        }
+        self.retain_bag(&mut candidates, bag, 0);
+        candidates
+    }
+
+    /// Keep only candidates whose word breaks fall exactly at `breaks`.
+    fn retain_enumeration(&self, candidates: &mut BitSet, breaks: u32) {
+        if breaks == 0 {
+            *candidates &= &!self.phrase.to_bitvec();
+            return;
+        }
+        *candidates &= &*self.phrase;
+        let phrases: Vec<usize> = candidates.iter_ones().collect();
+        for idx in phrases {
+            if self.breaks[idx] != breaks {
+                candidates.set(idx, false);
+            }
+        }
+    }
+
+    /// Bitset of words whose score is at least `min_score`; unscored words never pass.
+    fn score_mask(&self, min_score: u8) -> BitSet {
+        self.scores
+            .iter()
+            .map(|score| score.is_some_and(|s| s >= min_score))
+            .collect()
+    }
+
+    fn page(
+        &self,
+        candidates: &BitSet,
+        sort: SortOrder,
+        page: usize,
+        page_size: usize,
+    ) -> QueryResult {
+        page_buckets(&[(self, candidates)], sort, page, page_size)
+    }
+
+    /// Candidate indices in the requested order.
+    fn ordered<'a>(
+        &'a self,
+        candidates: &'a BitSet,
+        sort: SortOrder,
+    ) -> Box<dyn Iterator<Item = usize> + 'a> {
+        match sort {
+            SortOrder::Alpha => Box::new(candidates.iter_ones()),
+            SortOrder::Score => Box::new(
+                self.by_score
+                    .iter()
+                    .map(|idx| *idx as usize)
+                    .filter(|idx| candidates[*idx]),
+            ),
+        }
+    }
+
+    fn sort_key(&self, idx: usize, sort: SortOrder) -> (Reverse<Option<u8>>, &str) {
+        match sort {
+            SortOrder::Alpha => (Reverse(None), self.word(idx)),
+            SortOrder::Score => (Reverse(self.scores[idx]), self.word(idx)),
+        }
+    }
+
+    fn word_match(&self, idx: usize) -> WordMatch {
+        let word = self.alphabet.render(self.word(idx));
+        WordMatch {
+            display: self.display[idx].clone().unwrap_or_else(|| word.clone()),
+            word,
+            score: self.scores[idx],
+        }
+    }
+}
+
+/// Depth-first state for [`WordIndex::query_phrase_anagram`]. Words are
+/// recorded as `(len, idx)` pairs into their length bucket.
+struct PhraseSearch<'a> {
+    index: &'a WordIndex,
+    params: &'a PhraseAnagramParams<'a>,
+    deadline: Instant,
+    path: Vec<(usize, usize)>,
+    found: Vec<Vec<(usize, usize)>>,
+    truncated: bool,
+}
+
+impl PhraseSearch<'_> {
+    /// Extend the current path with words spelled from `bag`. Without an
+    /// enumeration, words are chosen in `(len, idx)` order from `min_len` /
+    /// `min_idx` so each combination is found once.
+    fn descend(
+        &mut self,
+        bag: &mut [u8; ALPHABET],
+        remaining: usize,
+        min_len: usize,
+        min_idx: usize,
+    ) {
+        if self.truncated {
+            return;
+        }
+        if self.found.len() >= self.params.limit || Instant::now() >= self.deadline {
+            self.truncated = true;
+            return;
+        }
+
+        let depth = self.path.len();
+        let lengths = match self.params.enumeration {
+            Some(lengths) => {
+                if depth == lengths.len() {
+                    if remaining == 0 {
+                        self.found.push(self.path.clone());
+                    }
+                    return;
+                }
+                lengths[depth]..=lengths[depth]
+            }
+            None => {
+                if remaining == 0 {
+                    if depth >= 2 {
+                        self.found.push(self.path.clone());
+                    }
+                    return;
+                }
+                if depth >= self.params.max_words {
+                    return;
+                }
+                let min = min_len.max(1);
+                // A lone word is not a phrase, and the last word must finish the bag.
+                let max = if depth == 0 { remaining - 1 } else { remaining };
+                if depth + 1 == self.params.max_words {
+                    remaining.max(min)..=max
+                } else {
+                    min..=max
+                }
+            }
+        };
+
+        for len in lengths {
+            if len > remaining || len > MAX_WORD_LEN {
+                break;
+            }
+            let Some(len_index) = self.index.lens.get(len).and_then(|o| o.as_ref()) else {
+                continue;
+            };
+            let candidates = len_index.bag_candidates(bag, self.params.min_score);
+            let floor = if self.params.enumeration.is_none() && len == min_len {
+                min_idx
+            } else {
+                0
+            };
+            for idx in len_index.ordered(&candidates, SortOrder::Score) {
+                if idx < floor {
+                    continue;
+                }
+                let counts = &len_index.letter_counts[idx];
+                for (have, used) in bag.iter_mut().zip(counts) {
+                    *have -= used;
+                }
+                self.path.push((len, idx));
+                self.descend(bag, remaining - len, len, idx);
+                self.path.pop();
+                for (have, used) in bag.iter_mut().zip(counts) {
+                    *have += used;
+                }
+                if self.truncated {
+                    return;
+                }
+            }
+        }
+    }
+}
+
+/// Page through candidates from one or more length buckets, merging them so
+/// the order (and therefore paging) is the same no matter how many buckets
+/// matched.
+fn page_buckets(
+    buckets: &[(&LenIndex, &BitSet)],
+    sort: SortOrder,
+    page: usize,
+    page_size: usize,
+) -> QueryResult {
+    page_tiers(&[buckets.to_vec()], sort, page, page_size)
+}
+
+/// Like [`page_buckets`], with the buckets split into tiers: every match of
+/// a tier comes before those of the next, and each tier is merged by `sort`.
+fn page_tiers(
+    tiers: &[Vec<(&LenIndex, &BitSet)>],
+    sort: SortOrder,
+    page: usize,
+    page_size: usize,
+) -> QueryResult {
+    let total: usize = tiers.iter().flatten().map(|(_, c)| c.count_ones()).sum();
+    if total == 0 {
+        return QueryResult {
+            total: 0,
+            items: Vec::new(),
+            has_more: false,
+        };
+    }
+
+    let offset = page.saturating_sub(1).saturating_mul(page_size);
+    let items: Vec<WordMatch> = tiers
+        .iter()
+        .flat_map(|buckets| merge_buckets(buckets, sort))
+        .skip(offset)
+        .take(page_size)
+        .map(|(len_index, idx)| len_index.word_match(idx))
+        .collect();
+
+    let has_more = offset + items.len() < total;
+
+    QueryResult {
+        total,
+        items,
+        has_more,
+    }
+}
+
+/// Candidates of several buckets as one stream in `sort` order.
+fn merge_buckets<'a>(
+    buckets: &'a [(&'a LenIndex, &'a BitSet)],
+    sort: SortOrder,
+) -> impl Iterator<Item = (&'a LenIndex, usize)> + 'a {
+    let mut streams: Vec<_> = buckets
+        .iter()
+        .map(|(len_index, candidates)| (*len_index, len_index.ordered(candidates, sort).peekable()))
+        .collect();
+    std::iter::from_fn(move || {
+        let (len_index, stream) = streams
+            .iter_mut()
+            .filter_map(|(len_index, stream)| {
+                let idx = *stream.peek()?;
+                Some((len_index.sort_key(idx, sort), *len_index, stream))
+            })
+            .min_by(|a, b| a.0.cmp(&b.0))
+            .map(|(_, len_index, stream)| (len_index, stream))?;
+        stream.next().map(|idx| (len_index, idx))
+    })
+}
+
+/// Split a word-list line into a normalized word and its optional `;score` suffix.
+fn parse_entry(raw: &str, alphabet: &Alphabet) -> Option<Entry> {
+    let (word, score) = match raw.split_once(';') {
+        Some((word, score)) => (word, score.trim().parse::<u8>().ok()),
+        None => (raw, None),
+    };
+    let word = word.trim();
+    let (normalized, breaks) = normalize_word(word, alphabet)?;
+    let folded = !word.is_ascii() && alphabet.render(&normalized) != word.to_lowercase();
+    Some(Entry {
+        display: (breaks != 0 || folded).then(|| word.to_string()),
+        word: normalized,
+        breaks,
+        score,
+    })
+}
+
+/// Whether the line has a `;score` suffix that is not a number from 0 to
+/// 255, such as `word;300` or `word;25x`. An empty suffix is no score.
+fn malformed_score(raw: &str) -> bool {
+    raw.split_once(';').is_some_and(|(_, score)| {
+        let score = score.trim();
+        !score.is_empty() && score.parse::<u8>().is_err()
+    })
+}
+
+/// Fold the letters of an entry onto cell codes and record where its word
+/// breaks fall.
+fn normalize_word(raw: &str, alphabet: &Alphabet) -> Option<(String, u32)> {
+    let lower = raw.to_lowercase();
+    let mut rest = lower.as_str();
+    let mut normalized = String::with_capacity(raw.len());
+    let mut breaks = 0u32;
+    let mut pending_break = false;
+    while let Some(c) = rest.chars().next() {
+        if c == ' ' || c == '-' {
+            pending_break = !normalized.is_empty();
+            rest = &rest[1..];
+            continue;
+        }
+        let (used, codes) = alphabet.next_cells(rest)?;
+        if pending_break {
+            breaks |= 1 << (normalized.len() - 1);
+            pending_break = false;
+        }
+        normalized.extend(codes.iter().map(|code| *code as char));
+        if normalized.len() > MAX_WORD_LEN {
+            return None;
+        }
+        rest = &rest[used..];
+    }
+    let len = normalized.len();
+    if (1..=MAX_WORD_LEN).contains(&len) {
+        Some((normalized, breaks))
+    } else {
+        None
     }
-    Ok(counts)
+}
+
+/// Break mask for an enumeration such as `[3, 4]`.
+fn enumeration_breaks(lengths: &[usize]) -> u32 {
+    let mut breaks = 0u32;
+    let mut pos = 0usize;
+    for len in lengths.iter().take(lengths.len().saturating_sub(1)) {
+        pos += len;
+        if (1..MAX_WORD_LEN).contains(&pos) {
+            breaks |= 1 << (pos - 1);
+        }
+    }
+    breaks
+}
+
+/// Parse a pattern of letters, blanks (`_`, `?`, `.`), letter classes
+/// (`[aeiou]`, `[^st]`, `@` for a vowel, `#` for a consonant) and
+/// repeated-letter variables (`0`-`9`), e.g. `1__1` for a word that starts
+/// and ends with the same letter.
+///
+/// This and the other free parsers use the English alphabet; the methods of
+/// the same name on [`Alphabet`] parse for other word lists.
+pub fn parse_pattern(raw: &str) -> Result<Pattern, PatternError> {
+    Alphabet::english().parse_pattern(raw)
+}
+
+/// Parse a pattern containing `*` wildcards, each standing for zero or more
+/// letters, e.g. `un*able` or `*xq*`. Segments between wildcards use the
+/// same syntax as [`parse_pattern`].
+pub fn parse_wildcard_pattern(raw: &str) -> Result<WildcardPattern, PatternError> {
+    Alphabet::english().parse_wildcard_pattern(raw)
+}
+
+/// Parse a cryptogram-style letter pattern such as `ABCA`, where every letter
+/// is a variable: repeated letters match repeated letters and different
+/// letters match different letters. Blanks stay unconstrained.
+pub fn parse_isomorph(raw: &str) -> Result<Pattern, PatternError> {
+    Alphabet::english().parse_isomorph(raw)
+}
+
+impl Alphabet {
+    /// [`parse_pattern`] for this alphabet: letters fold onto cells, so in
+    /// Dutch `ij___` fixes one cell and in German `stra_e` matches "straße"
+    /// only if written `stra__e`.
+    pub fn parse_pattern(&self, raw: &str) -> Result<Pattern, PatternError> {
+        let pattern = self.parse_slots(raw)?;
+        let len = pattern.len();
+        if len == 0 || len > MAX_WORD_LEN {
+            return Err(PatternError::InvalidLength(MAX_WORD_LEN, len));
+        }
+        Ok(pattern)
+    }
+
+    /// [`parse_wildcard_pattern`] for this alphabet.
+    pub fn parse_wildcard_pattern(&self, raw: &str) -> Result<WildcardPattern, PatternError> {
+        let parts: Vec<&str> = raw.split('*').collect();
+        let last = parts.len() - 1;
+        let mut segments = Vec::with_capacity(parts.len());
+        for (i, part) in parts.into_iter().enumerate() {
+            // `**` is the same as `*`, so drop empty segments between wildcards.
+            if part.is_empty() && i != 0 && i != last {
+                continue;
+            }
+            segments.push(self.parse_slots(part)?);
+        }
+        if segments.len() - 1 > MAX_WILDCARDS {
+            return Err(PatternError::TooManyWildcards(MAX_WILDCARDS));
+        }
+        let pattern = WildcardPattern { segments };
+        let fixed = pattern.fixed_len();
+        if fixed > MAX_WORD_LEN {
+            return Err(PatternError::InvalidLength(MAX_WORD_LEN, fixed));
+        }
+        Ok(pattern)
+    }
+
+    /// [`parse_isomorph`] for this alphabet.
+    pub fn parse_isomorph(&self, raw: &str) -> Result<Pattern, PatternError> {
+        let raw = raw.to_lowercase();
+        let mut rest = raw.as_str();
+        let mut vars = Vec::with_capacity(raw.len());
+        while let Some(c) = rest.chars().next() {
+            if matches!(c, '_' | '?' | '.') {
+                vars.push(None);
+                rest = &rest[1..];
+                continue;
+            }
+            let (used, codes) = self.next_cells(rest).ok_or(PatternError::InvalidChar(c))?;
+            vars.extend(codes.iter().map(|code| Some(code - b'a')));
+            rest = &rest[used..];
+        }
+        let len = vars.len();
+        if len == 0 || len > MAX_WORD_LEN {
+            return Err(PatternError::InvalidLength(MAX_WORD_LEN, len));
+        }
+        Ok(Pattern {
+            letters: vec![None; len],
+            classes: vec![None; len],
+            vars,
+        })
+    }
+
+    fn parse_slots(&self, raw: &str) -> Result<Pattern, PatternError> {
+        let raw = raw.to_lowercase();
+        let mut pattern = Pattern::from(Vec::with_capacity(raw.len()));
+        let mut push = |letter, class, var| {
+            pattern.letters.push(letter);
+            pattern.classes.push(class);
+            pattern.vars.push(var);
+        };
+        let mut rest = raw.as_str();
+        while let Some(c) = rest.chars().next() {
+            let mut used = c.len_utf8();
+            match c {
+                '_' | '?' | '.' => push(None, None, None),
+                '@' => push(None, Some(self.vowels()), None),
+                '#' => push(None, Some(self.consonants()), None),
+                '[' => {
+                    let (class, len) = self.parse_class(&rest[1..])?;
+                    used += len;
+                    match class.len() {
+                        1 => push(class.iter().next(), None, None),
+                        _ => push(None, Some(class), None),
+                    }
+                }
+                digit if digit.is_ascii_digit() => push(None, None, Some(digit as u8 - b'0')),
+                other => {
+                    let (len, codes) = self
+                        .next_cells(rest)
+                        .ok_or(PatternError::InvalidChar(other))?;
+                    used = len;
+                    for code in codes {
+                        push(Some(*code), None, None);
+                    }
+                }
+            }
+            rest = &rest[used..];
+        }
+        Ok(pattern)
+    }
+
+    /// Parse the body of a `[...]` class after the opening bracket, returning
+    /// the class and the bytes read including the closing bracket.
+    fn parse_class(&self, raw: &str) -> Result<(LetterSet, usize), PatternError> {
+        let mut set = LetterSet::default();
+        let mut negated = false;
+        let mut rest = raw;
+        loop {
+            let Some(c) = rest.chars().next() else {
+                return Err(PatternError::UnclosedClass);
+            };
+            match c {
+                ']' => {
+                    rest = &rest[1..];
+                    break;
+                }
+                '^' if rest.len() == raw.len() => {
+                    negated = true;
+                    rest = &rest[1..];
+                }
+                other => match self.next_cells(rest) {
+                    Some((used, [code])) => {
+                        set.insert(*code);
+                        rest = &rest[used..];
+                    }
+                    _ => return Err(PatternError::InvalidChar(other)),
+                },
+            }
+        }
+        let set = if negated {
+            self.all().intersection(set.complement())
+        } else {
+            set
+        };
+        if set.is_empty() {
+            return Err(PatternError::EmptyClass);
+        }
+        Ok((set, raw.len() - rest.len()))
+    }
+
+    /// [`parse_crossings`] for this alphabet.
+    pub fn parse_crossings(
+        &self,
+        raw: &str,
+        slot_len: usize,
+    ) -> Result<Vec<Crossing>, PatternError> {
+        raw.split(',')
+            .map(|part| {
+                let invalid = || PatternError::InvalidCrossing(part.to_string());
+                let mut fields = part.trim().split(':');
+                let (Some(pos), Some(pattern), Some(cross_pos), None) =
+                    (fields.next(), fields.next(), fields.next(), fields.next())
+                else {
+                    return Err(invalid());
+                };
+                let pos: usize = pos.parse().map_err(|_| invalid())?;
+                let cross_pos: usize = cross_pos.parse().map_err(|_| invalid())?;
+                let pattern = self.parse_pattern(pattern)?;
+                if pos >= slot_len || cross_pos >= pattern.len() {
+                    return Err(invalid());
+                }
+                Ok(Crossing {
+                    pos,
+                    pattern,
+                    cross_pos,
+                })
+            })
+            .collect()
+    }
+
+    /// [`parse_letters`] for this alphabet.
+    pub fn parse_letters(&self, raw: &str) -> Result<Vec<u8>, PatternError> {
+        let mut seen = HashSet::new();
+        let mut result = Vec::new();
+        for code in self.parse_cells(raw)? {
+            if seen.insert(code) {
+                result.push(code);
+            }
+        }
+        Ok(result)
+    }
+
+    /// [`parse_tile_bag`] for this alphabet.
+    pub fn parse_tile_bag(&self, raw: &str) -> Result<([u8; ALPHABET], u8), PatternError> {
+        let mut counts = [0u8; ALPHABET];
+        let mut blanks = 0u8;
+        let mut tiles = 0usize;
+        let raw = raw.to_lowercase();
+        let mut rest = raw.as_str();
+        while let Some(c) = rest.chars().next() {
+            if matches!(c, '?' | '_') {
+                blanks = blanks.saturating_add(1);
+                tiles += 1;
+                rest = &rest[1..];
+                continue;
+            }
+            let (used, codes) = self.next_cells(rest).ok_or(PatternError::InvalidChar(c))?;
+            for code in codes {
+                let idx = (code - b'a') as usize;
+                counts[idx] = counts[idx].saturating_add(1);
+                tiles += 1;
+            }
+            rest = &rest[used..];
+        }
+        if tiles == 0 || tiles > MAX_WORD_LEN {
+            return Err(PatternError::InvalidLength(MAX_WORD_LEN, tiles));
+        }
+        Ok((counts, blanks))
+    }
+
+    /// [`parse_letter_bag`] for this alphabet; `expected_len` counts cells.
+    pub fn parse_letter_bag(
+        &self,
+        raw: &str,
+        expected_len: usize,
+    ) -> Result<[u8; ALPHABET], PatternError> {
+        let mut counts = [0u8; ALPHABET];
+        let codes = self.parse_cells(raw)?;
+        for code in &codes {
+            let idx = (code - b'a') as usize;
+            counts[idx] = counts[idx].saturating_add(1);
+        }
+        if codes.len() != expected_len {
+            return Err(PatternError::InvalidLength(expected_len, codes.len()));
+        }
+        Ok(counts)
+    }
+
+    /// Cell codes of `raw`, failing on the first character outside the
+    /// alphabet.
+    fn parse_cells(&self, raw: &str) -> Result<Vec<u8>, PatternError> {
+        self.cells_of(raw).ok_or_else(|| {
+            let lower = raw.to_lowercase();
+            let mut rest = lower.as_str();
+            while let Some((used, _)) = self.next_cells(rest) {
+                rest = &rest[used..];
+            }
+            PatternError::InvalidChar(rest.chars().next().unwrap_or_default())
+        })
+    }
+}
+
+/// Parse an enumeration like `3,4`, `3-4` or `(3,4)` into word lengths that
+/// must add up to `expected_len`.
+pub fn parse_enumeration(raw: &str, expected_len: usize) -> Result<Vec<usize>, PatternError> {
+    let trimmed = raw.trim().trim_start_matches('(').trim_end_matches(')');
+    let mut lengths = Vec::new();
+    for part in trimmed.split([',', '-', ' ']) {
+        let len = part
+            .trim()
+            .parse::<usize>()
+            .ok()
+            .filter(|len| *len > 0)
+            .ok_or_else(|| PatternError::InvalidEnumeration(raw.to_string()))?;
+        lengths.push(len);
+    }
+    let total: usize = lengths.iter().sum();
+    if total != expected_len {
+        return Err(PatternError::EnumerationMismatch(expected_len, total));
+    }
+    Ok(lengths)
+}
+
+/// Parse comma-separated crossings of the form `pos:pattern:cross_pos`
+/// (0-based positions), e.g. `0:c_t:1,2:__e_:3` for a slot of `slot_len`.
+pub fn parse_crossings(raw: &str, slot_len: usize) -> Result<Vec<Crossing>, PatternError> {
+    Alphabet::english().parse_crossings(raw, slot_len)
+}
+
+pub fn parse_letters(raw: &str) -> Result<Vec<u8>, PatternError> {
+    Alphabet::english().parse_letters(raw)
+}
+
+/// Parse a bag of tiles where `?` (or `_`) is a blank, returning the letter
+/// counts and the number of blanks.
+pub fn parse_tile_bag(raw: &str) -> Result<([u8; ALPHABET], u8), PatternError> {
+    Alphabet::english().parse_tile_bag(raw)
+}
+
+pub fn parse_letter_bag(raw: &str, expected_len: usize) -> Result<[u8; ALPHABET], PatternError> {
+    Alphabet::english().parse_letter_bag(raw, expected_len)
 }
 
 #[derive(Debug, Error)]
@@ -360,6 +1675,18 @@ pub enum PatternError {
     InvalidChar(char),
     #[error("pattern length must be between 1 and {0}, got {1}")]
     InvalidLength(usize, usize),
+    #[error("pattern may contain at most {0} * wildcards")]
+    TooManyWildcards(usize),
+    #[error("unclosed letter class in pattern")]
+    UnclosedClass,
+    #[error("letter class in pattern matches no letters")]
+    EmptyClass,
+    #[error("invalid crossing (expected pos:pattern:cross_pos): {0}")]
+    InvalidCrossing(String),
+    #[error("invalid enumeration: {0}")]
+    InvalidEnumeration(String),
+    #[error("enumeration must add up to {0} letters, got {1}")]
+    EnumerationMismatch(usize, usize),
 }
 
 #[cfg(test)]
@@ -368,7 +1695,7 @@ mod tests {
     use std::io::Write;
     use tempfile::NamedTempFile;
 
-    fn make_index(words: &[&str]) -> Arc<WordIndex> {
+    pub(super) fn make_index(words: &[&str]) -> Arc<WordIndex> {
         let mut file = NamedTempFile::new().expect("temp file");
         for word in words {
             writeln!(file, "{word}").unwrap();
@@ -376,20 +1703,49 @@ mod tests {
         WordIndex::build_from_file(file.path()).expect("build index")
     }
 
+    fn words(result: &QueryResult) -> Vec<&str> {
+        result.items.iter().map(|m| m.word.as_str()).collect()
+    }
+
+    #[test]
+    fn indexes_malformed_scores_unscored() {
+        let index = make_index(&["apple;300", "ample;25x", "angle;", "addle;40"]);
+        let pattern = parse_pattern("a___e").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), ["addle", "ample", "angle", "apple"]);
+        assert!(result.items[1..].iter().all(|m| m.score.is_none()));
+
+        assert!(malformed_score("apple;300"));
+        assert!(malformed_score("ample; 25x"));
+        assert!(!malformed_score("angle;"));
+        assert!(!malformed_score("addle;40"));
+        assert!(!malformed_score("hot dog"));
+    }
+
     #[test]
     fn parses_patterns_with_blanks() {
         let parsed = parse_pattern("A__le").unwrap();
         assert_eq!(parsed.len(), 5);
-        assert_eq!(parsed[0], Some(b'a'));
-        assert_eq!(parsed[1], None);
+        assert_eq!(parsed.letters[0], Some(b'a'));
+        assert_eq!(parsed.letters[1], None);
         let parsed_dots = parse_pattern("a..le").unwrap();
-        assert_eq!(parsed_dots[3], Some(b'l'));
+        assert_eq!(parsed_dots.letters[3], Some(b'l'));
         assert!(parse_pattern("").is_err());
     }
 
     #[test]
     fn parse_pattern_rejects_invalid_chars() {
-        assert!(parse_pattern("a1b").is_err());
+        assert!(parse_pattern("a!b").is_err());
         assert!(parse_pattern("🙂🙂").is_err());
     }
 
@@ -407,12 +1763,16 @@ mod tests {
             pattern: &pattern,
             must_include: &[],
             cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 10,
+            overlay: None,
         });
         assert_eq!(result.total, 5);
-        assert!(result.items.contains(&"apple".to_string()));
-        assert!(result.items.contains(&"angle".to_string()));
+        assert!(words(&result).contains(&"apple"));
+        assert!(words(&result).contains(&"angle"));
     }
 
     #[test]
@@ -424,8 +1784,12 @@ mod tests {
             pattern: &pattern,
             must_include: &must,
             cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 10,
+            overlay: None,
         });
         assert_eq!(result.total, 2);
 
@@ -434,10 +1798,14 @@ mod tests {
             pattern: &pattern,
             must_include: &[],
             cannot_include: &cannot,
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 10,
+            overlay: None,
         });
-        assert!(!result.items.iter().any(|w| w.contains('n')));
+        assert!(!words(&result).iter().any(|w| w.contains('n')));
     }
 
     #[test]
@@ -448,15 +1816,23 @@ mod tests {
             pattern: &pattern,
             must_include: &[],
             cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 2,
+            overlay: None,
         });
         let second_page = index.query(QueryParams {
             pattern: &pattern,
             must_include: &[],
             cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 2,
             page_size: 2,
+            overlay: None,
         });
         assert!(first_page.has_more);
         assert_eq!(first_page.items.len(), 2);
@@ -472,11 +1848,14 @@ mod tests {
         let result = index.query_anagram(AnagramParams {
             pattern: &pattern,
             bag_counts: bag,
+            blanks: 0,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 10,
         });
-        assert!(result.items.contains(&"silent".to_string()));
-        assert!(result.items.contains(&"listen".to_string()));
+        assert!(words(&result).contains(&"silent"));
+        assert!(words(&result).contains(&"listen"));
         assert_eq!(result.total, 5);
     }
 
@@ -488,10 +1867,599 @@ mod tests {
         let result = index.query_anagram(AnagramParams {
             pattern: &pattern,
             bag_counts: bag,
+            blanks: 0,
+            min_score: None,
+            sort: SortOrder::Alpha,
             page: 1,
             page_size: 10,
         });
-        assert!(result.items.contains(&"manchego".to_string()));
+        assert!(words(&result).contains(&"manchego"));
         assert_eq!(result.total, 1);
     }
+
+    #[test]
+    fn loads_scores_and_ranks_by_score() {
+        let index = make_index(&["aaaaah;20", "apple;50", "ample;40", "angle"]);
+        let pattern = parse_pattern("a____").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["apple", "ample", "angle"]);
+        assert_eq!(result.items[0].score, Some(50));
+        assert_eq!(result.items[2].score, None);
+
+        let alpha = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&alpha), vec!["ample", "angle", "apple"]);
+    }
+
+    #[test]
+    fn filters_by_min_score() {
+        let index = make_index(&["apple;50", "ample;40", "ankle;20", "angle"]);
+        let pattern = parse_pattern("a____").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: Some(40),
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 1,
+            overlay: None,
+        });
+        assert_eq!(result.total, 2);
+        assert!(result.has_more);
+        assert_eq!(words(&result), vec!["apple"]);
+    }
+
+    #[test]
+    fn duplicate_entries_keep_highest_score() {
+        let index = make_index(&["apple;20", "Apple;50", "apple"]);
+        let pattern = parse_pattern("apple").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].score, Some(50));
+    }
+
+    #[test]
+    fn indexes_phrases_with_word_breaks() {
+        let index = make_index(&["hot dog;50", "hotdog", "well-read", "hotrod", "it's"]);
+        let pattern = parse_pattern("hot___").unwrap();
+        let enumeration = parse_enumeration("3,3", pattern.len()).unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: Some(&enumeration),
+            min_score: None,
+            sort: SortOrder::Score,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].word, "hotdog");
+        assert_eq!(result.items[0].display, "hot dog");
+
+        let single = parse_enumeration("6", pattern.len()).unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: Some(&single),
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["hotdog", "hotrod"]);
+
+        let pattern = parse_pattern("________").unwrap();
+        let hyphenated = parse_enumeration("4-4", pattern.len()).unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: Some(&hyphenated),
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(result.items[0].display, "well-read");
+    }
+
+    #[test]
+    fn spells_outside_phrases() {
+        let index = make_index(&["hot dog;50", "ice"]);
+        let spelled = index.spell("Hot-Dog").unwrap();
+        assert_eq!(spelled.cells, b"hotdog");
+        assert_eq!(spelled.enumeration, [3, 3]);
+        assert_eq!(spelled.entry.unwrap().score, Some(50));
+        let spelled = index.spell("ice cream").unwrap();
+        assert_eq!(spelled.enumeration, [3, 5]);
+        assert!(spelled.entry.is_none());
+        assert!(index.spell("o'clock").is_none());
+
+        let pattern = parse_pattern("1_t1[aeiou]_").unwrap();
+        assert!(pattern.matches(b"hothog"));
+        assert!(!pattern.matches(b"hotdog"));
+        assert!(!pattern.matches(b"hotho"));
+        assert!(!parse_pattern("12").unwrap().matches(b"aa"));
+    }
+
+    #[test]
+    fn parse_enumeration_checks_total() {
+        assert_eq!(parse_enumeration("(3,4)", 7).unwrap(), vec![3, 4]);
+        assert_eq!(parse_enumeration("3-4", 7).unwrap(), vec![3, 4]);
+        assert!(parse_enumeration("3,3", 7).is_err());
+        assert!(parse_enumeration("3,,4", 7).is_err());
+        assert!(parse_enumeration("x", 1).is_err());
+    }
+
+    #[test]
+    fn parses_digit_variables_and_isomorphs() {
+        let parsed = parse_pattern("1a_1").unwrap();
+        assert_eq!(parsed.letters, vec![None, Some(b'a'), None, None]);
+        assert_eq!(parsed.vars, vec![Some(1), None, None, Some(1)]);
+        assert!(parsed.has_vars());
+
+        let iso = parse_isomorph("ABCA").unwrap();
+        assert_eq!(iso.letters, vec![None; 4]);
+        assert_eq!(iso.vars, vec![Some(0), Some(1), Some(2), Some(0)]);
+        assert!(parse_isomorph("AB1").is_err());
+    }
+
+    #[test]
+    fn enforces_repeated_letter_variables() {
+        let index = make_index(&[
+            "that", "tilt", "test", "tent", "abba", "noon", "deed", "dada",
+        ]);
+        let pattern = parse_pattern("1__1").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(
+            words(&result),
+            vec!["abba", "deed", "noon", "tent", "test", "that", "tilt"]
+        );
+
+        let pattern = parse_pattern("1221").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["abba", "deed", "noon"]);
+
+        let pattern = parse_isomorph("ABCA").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["tent", "test", "that", "tilt"]);
+    }
+
+    #[test]
+    fn parses_letter_classes() {
+        let parsed = parse_pattern("[aeiou][^st]@#[b]").unwrap();
+        assert_eq!(parsed.len(), 5);
+        let english = Alphabet::english();
+        assert_eq!(parsed.classes[0], Some(english.vowels()));
+        let not_st = parsed.classes[1].unwrap();
+        assert!(!not_st.contains(b's') && !not_st.contains(b't') && not_st.contains(b'a'));
+        assert_eq!(not_st.len(), 24);
+        assert_eq!(parsed.classes[2], Some(english.vowels()));
+        assert_eq!(parsed.classes[3], Some(english.consonants()));
+        assert_eq!(parsed.letters[4], Some(b'b'));
+        assert_eq!(parsed.classes[4], None);
+
+        assert!(matches!(
+            parse_pattern("a[bc"),
+            Err(PatternError::UnclosedClass)
+        ));
+        assert!(matches!(
+            parse_pattern("a[]"),
+            Err(PatternError::EmptyClass)
+        ));
+        assert!(parse_pattern("[a1]").is_err());
+    }
+
+    #[test]
+    fn filters_by_letter_classes() {
+        let index = make_index(&["crane", "crate", "grate", "irate", "orate", "trace"]);
+        let pattern = parse_pattern("[^c]#ate").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["grate", "irate", "orate"]);
+
+        let pattern = parse_pattern("@rate").unwrap();
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["irate", "orate"]);
+    }
+
+    fn wildcard_params(
+        pattern: &WildcardPattern,
+        sort: SortOrder,
+        page: usize,
+    ) -> WildcardParams<'_> {
+        WildcardParams {
+            pattern,
+            min_len: 1,
+            max_len: MAX_WORD_LEN,
+            must_include: &[],
+            cannot_include: &[],
+            min_score: None,
+            sort,
+            page,
+            page_size: 2,
+            overlay: None,
+        }
+    }
+
+    #[test]
+    fn parses_wildcard_patterns() {
+        let parsed = parse_wildcard_pattern("un*able").unwrap();
+        assert_eq!(parsed.segments.len(), 2);
+        assert_eq!(parsed.fixed_len(), 6);
+        assert_eq!(parse_wildcard_pattern("a**b").unwrap().segments.len(), 2);
+        assert_eq!(parse_wildcard_pattern("*").unwrap().fixed_len(), 0);
+        assert!(parse_wildcard_pattern("*a*b*c*d*").is_err());
+        assert!(parse_pattern("un*able").is_err());
+
+        let expanded = parse_wildcard_pattern("*xq*").unwrap().expand(4);
+        assert_eq!(expanded.len(), 3);
+        assert!(parse_wildcard_pattern("ab*").unwrap().expand(1).is_empty());
+    }
+
+    #[test]
+    fn wildcard_query_spans_length_buckets() {
+        let index = make_index(&[
+            "unable;10",
+            "unstable;60",
+            "unbearable;50",
+            "unsinkable;40",
+            "undo;90",
+            "table;70",
+        ]);
+        let pattern = parse_wildcard_pattern("un*able").unwrap();
+        let result = index.query_wildcard(WildcardParams {
+            page_size: 10,
+            overlay: None,
+            ..wildcard_params(&pattern, SortOrder::Alpha, 1)
+        });
+        assert_eq!(
+            words(&result),
+            vec!["unable", "unbearable", "unsinkable", "unstable"]
+        );
+
+        let result = index.query_wildcard(WildcardParams {
+            max_len: 8,
+            page_size: 10,
+            overlay: None,
+            ..wildcard_params(&pattern, SortOrder::Alpha, 1)
+        });
+        assert_eq!(words(&result), vec!["unable", "unstable"]);
+
+        let pattern = parse_wildcard_pattern("*bl*").unwrap();
+        let result = index.query_wildcard(WildcardParams {
+            cannot_include: b"u",
+            ..wildcard_params(&pattern, SortOrder::Alpha, 1)
+        });
+        assert_eq!(words(&result), vec!["table"]);
+    }
+
+    #[test]
+    fn wildcard_paging_is_stable_across_buckets() {
+        let index = make_index(&[
+            "unable;10",
+            "unstable;60",
+            "unbearable;50",
+            "unsinkable;40",
+            "unusable;60",
+        ]);
+        let pattern = parse_wildcard_pattern("un*").unwrap();
+        let pages: Vec<Vec<String>> = (1..=3)
+            .map(|page| {
+                let result =
+                    index.query_wildcard(wildcard_params(&pattern, SortOrder::Score, page));
+                assert_eq!(result.total, 5);
+                assert_eq!(result.has_more, page < 3);
+                result.items.into_iter().map(|item| item.word).collect()
+            })
+            .collect();
+        assert_eq!(
+            pages,
+            vec![
+                vec!["unstable", "unusable"],
+                vec!["unbearable", "unsinkable"],
+                vec!["unable"],
+            ]
+        );
+    }
+
+    #[test]
+    fn anagram_blanks_cover_missing_letters() {
+        let index = make_index(&["listen", "silent", "lister", "tinsel"]);
+        let pattern = parse_pattern("______").unwrap();
+        let (bag, blanks) = parse_tile_bag("liste?").unwrap();
+        assert_eq!(blanks, 1);
+        let result = index.query_anagram(AnagramParams {
+            pattern: &pattern,
+            bag_counts: bag,
+            blanks,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+        });
+        assert_eq!(words(&result), vec!["listen", "lister", "silent", "tinsel"]);
+    }
+
+    #[test]
+    fn subanagrams_group_by_length() {
+        let index = make_index(&[
+            "a", "at", "ate", "eat", "tea", "teas", "seat", "treat", "tease",
+        ]);
+        let (bag, blanks) = parse_tile_bag("eat?").unwrap();
+        let params = |page| SubAnagramParams {
+            bag_counts: bag,
+            blanks,
+            min_len: 2,
+            max_len: MAX_WORD_LEN,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page,
+            page_size: 3,
+        };
+
+        let first = index.query_subanagram(params(1));
+        assert_eq!(first.total, 6);
+        assert!(first.has_more);
+        let lens: Vec<(usize, usize, usize)> = first
+            .groups
+            .iter()
+            .map(|g| (g.len, g.total, g.items.len()))
+            .collect();
+        assert_eq!(lens, vec![(4, 2, 2), (3, 3, 1)]);
+        assert_eq!(first.groups[0].items[0].word, "seat");
+
+        let second = index.query_subanagram(params(2));
+        assert!(!second.has_more);
+        let rest: Vec<&str> = second
+            .groups
+            .iter()
+            .flat_map(|g| g.items.iter().map(|i| i.word.as_str()))
+            .collect();
+        assert_eq!(rest, vec!["eat", "tea", "at"]);
+
+        let (bag, blanks) = parse_tile_bag("eat").unwrap();
+        let exact = index.query_subanagram(SubAnagramParams {
+            bag_counts: bag,
+            blanks,
+            ..params(1)
+        });
+        assert_eq!(exact.total, 4);
+    }
+
+    fn phrase_params(bag: &str) -> PhraseAnagramParams<'static> {
+        PhraseAnagramParams {
+            bag_counts: parse_letter_bag(bag, bag.len()).unwrap(),
+            enumeration: None,
+            max_words: 3,
+            min_score: None,
+            limit: 100,
+            time_limit: Duration::from_secs(5),
+        }
+    }
+
+    fn phrases(result: &PhraseResult) -> Vec<String> {
+        result.items.iter().map(PhraseMatch::display).collect()
+    }
+
+    #[test]
+    fn finds_multi_word_anagrams() {
+        let index = make_index(&[
+            "dirty;50",
+            "room;50",
+            "dormitory;60",
+            "rot;30",
+            "my;40",
+            "dirt;20",
+            "yo",
+            "hot dog",
+        ]);
+        let result = index.query_phrase_anagram(phrase_params("dormitory"));
+        assert!(!result.truncated);
+        assert_eq!(phrases(&result), vec!["room dirty"]);
+
+        let enumeration = [5, 4];
+        let result = index.query_phrase_anagram(PhraseAnagramParams {
+            enumeration: Some(&enumeration),
+            ..phrase_params("dormitory")
+        });
+        assert_eq!(phrases(&result), vec!["dirty room"]);
+        assert_eq!(result.items[0].score, Some(50));
+    }
+
+    #[test]
+    fn phrase_anagram_respects_limits() {
+        let index = make_index(&["ab", "ba", "a", "b"]);
+        let result = index.query_phrase_anagram(PhraseAnagramParams {
+            limit: 1,
+            ..phrase_params("abab")
+        });
+        assert!(result.truncated);
+        assert_eq!(result.items.len(), 1);
+
+        let result = index.query_phrase_anagram(PhraseAnagramParams {
+            time_limit: Duration::ZERO,
+            ..phrase_params("abab")
+        });
+        assert!(result.truncated);
+        assert!(result.items.is_empty());
+    }
+
+    #[test]
+    fn parses_crossings() {
+        let crossings = parse_crossings("0:c_t:1, 2:__e_:3", 4).unwrap();
+        assert_eq!(crossings.len(), 2);
+        assert_eq!(crossings[1].pos, 2);
+        assert_eq!(crossings[1].pattern.letters[2], Some(b'e'));
+        assert_eq!(crossings[1].cross_pos, 3);
+        assert!(parse_crossings("4:c_t:1", 4).is_err());
+        assert!(parse_crossings("0:c_t:3", 4).is_err());
+        assert!(parse_crossings("0:c_t", 4).is_err());
+    }
+
+    #[test]
+    fn crossing_counts_narrow_a_slot() {
+        let index = make_index(&["cat", "cot", "cut", "arc", "orc"]);
+        let crossing = parse_pattern("_rc").unwrap();
+        let counts = index.letter_counts_at(&crossing, 0, None, None);
+        assert_eq!(counts[0], 1);
+        assert_eq!(counts[(b'o' - b'a') as usize], 1);
+        assert_eq!(counts[(b'u' - b'a') as usize], 0);
+
+        let mut viable = LetterSet::default();
+        for (letter, count) in counts.iter().enumerate() {
+            if *count > 0 {
+                viable.insert(b'a' + letter as u8);
+            }
+        }
+        let mut pattern = parse_pattern("c_t").unwrap();
+        pattern.restrict(1, viable);
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["cat", "cot"]);
+    }
+
+    #[test]
+    fn builds_lists_in_other_alphabets() {
+        let mut file = NamedTempFile::new().expect("temp file");
+        writeln!(file, "café;50\nCŒUR\nnaïf\nzzz9").unwrap();
+        let fr = Arc::new(Alphabet::parse("fr").unwrap());
+        let index = WordIndex::build_from_file_with_alphabet(file.path(), fr).unwrap();
+        assert_eq!(index.word_count(), 3);
+        let query = |pattern: &str| {
+            let pattern = index.alphabet().parse_pattern(pattern).unwrap();
+            index
+                .query(QueryParams {
+                    pattern: &pattern,
+                    must_include: &[],
+                    cannot_include: &[],
+                    enumeration: None,
+                    min_score: None,
+                    sort: SortOrder::Alpha,
+                    page: 1,
+                    page_size: 10,
+                    overlay: None,
+                })
+                .items
+        };
+        let found = query("caf?");
+        assert_eq!(
+            (found[0].word.as_str(), found[0].display.as_str()),
+            ("cafe", "café")
+        );
+        assert_eq!(query("cœ_r")[0].display, "CŒUR");
+
+        let mut file = NamedTempFile::new().expect("temp file");
+        writeln!(file, "ijs\nijzer\nbijl").unwrap();
+        let nl = Arc::new(Alphabet::parse("nl").unwrap());
+        let index = WordIndex::build_from_file_with_alphabet(file.path(), nl).unwrap();
+        let pattern = index.alphabet().parse_pattern("ij???").unwrap();
+        assert_eq!(pattern.len(), 4);
+        let result = index.query(QueryParams {
+            pattern: &pattern,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 10,
+            overlay: None,
+        });
+        assert_eq!(words(&result), vec!["ijzer"]);
+    }
 }
@@ -0,0 +1,241 @@
+//! Anagrams of some letters plus abbreviations of clue words: "ate" with L
+//! for "learner" gives TALE.
+//!
+//! Every choice of one abbreviation per clue word adds its letters to the
+//! bag, and the words using that bag exactly are collected. A word found
+//! with several choices keeps the first, and the matches of every choice are
+//! paged through as one list.
+
+use std::collections::{BTreeMap, HashMap};
+
+use bitvec::prelude::*;
+
+use super::{
+    ALPHABET, BitSet, Fragment, LenIndex, Pattern, SortOrder, WordIndex, WordMatch, merge_buckets,
+};
+
+/// Most choices of abbreviations tried for one query.
+pub const MAX_ABBREV_COMBINATIONS: usize = 64;
+
+#[derive(Debug)]
+pub struct AbbrevAnagramParams<'a> {
+    /// Fixes the length of the answer; without it every length the bag
+    /// and a choice of fragments add up to is tried.
+    pub pattern: Option<&'a Pattern>,
+    pub bag_counts: [u8; ALPHABET],
+    pub blanks: u8,
+    /// Per clue word, the fragments it may stand for. One of each is used.
+    pub choices: &'a [Vec<Fragment>],
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+}
+
+/// A word together with the fragments its letters were completed with.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct AbbrevAnagram {
+    pub word: WordMatch,
+    pub fragments: Vec<Fragment>,
+}
+
+#[derive(Debug, Clone)]
+pub struct AbbrevAnagramResult {
+    pub total: usize,
+    pub items: Vec<AbbrevAnagram>,
+    pub has_more: bool,
+    /// Set when there were more than [`MAX_ABBREV_COMBINATIONS`] choices
+    /// and only the first were tried.
+    pub truncated: bool,
+}
+
+/// Matches of one length bucket over every choice tried.
+struct Found<'a> {
+    len_index: &'a LenIndex,
+    candidates: BitSet,
+    /// The first choice each candidate was found with.
+    choice: HashMap<usize, usize>,
+}
+
+impl WordIndex {
+    pub fn query_abbrev_anagram(&self, params: AbbrevAnagramParams<'_>) -> AbbrevAnagramResult {
+        let combinations = params
+            .choices
+            .iter()
+            .fold(1usize, |n, choices| n.saturating_mul(choices.len()));
+        let truncated = combinations > MAX_ABBREV_COMBINATIONS;
+        let picks: Vec<Vec<&Fragment>> = (0..combinations.min(MAX_ABBREV_COMBINATIONS))
+            .map(|n| pick(params.choices, n))
+            .collect();
+
+        let mut found: BTreeMap<usize, Found<'_>> = BTreeMap::new();
+        for (which, picked) in picks.iter().enumerate() {
+            let mut bag = params.bag_counts;
+            for cell in picked.iter().flat_map(|f| f.cells.bytes()) {
+                let idx = (cell - b'a') as usize;
+                bag[idx] = bag[idx].saturating_add(1);
+            }
+            let tiles = bag.iter().map(|c| *c as usize).sum::<usize>() + params.blanks as usize;
+            let pattern = match params.pattern {
+                Some(pattern) => pattern.clone(),
+                None => Pattern::from(vec![None; tiles]),
+            };
+            // Another choice may still fit the pattern or a bucket.
+            if pattern.len() != tiles {
+                continue;
+            }
+            let Some(len_index) = self.lens.get(tiles).and_then(|o| o.as_ref()) else {
+                continue;
+            };
+
+            let mut candidates = len_index.pattern_candidates(&pattern);
+            if let Some(min_score) = params.min_score {
+                candidates &= &len_index.score_mask(min_score);
+            }
+            len_index.retain_bag(&mut candidates, &bag, params.blanks);
+
+            let entry = found.entry(tiles).or_insert_with(|| Found {
+                len_index,
+                candidates: bitvec![usize, Lsb0; 0; len_index.count()],
+                choice: HashMap::new(),
+            });
+            for idx in candidates.iter_ones() {
+                entry.choice.entry(idx).or_insert(which);
+            }
+            entry.candidates |= &candidates;
+        }
+
+        let total: usize = found.values().map(|f| f.candidates.count_ones()).sum();
+        let buckets: Vec<(&LenIndex, &BitSet)> = found
+            .values()
+            .map(|f| (f.len_index, &f.candidates))
+            .collect();
+        let offset = params
+            .page
+            .saturating_sub(1)
+            .saturating_mul(params.page_size);
+        let items: Vec<AbbrevAnagram> = merge_buckets(&buckets, params.sort)
+            .skip(offset)
+            .take(params.page_size)
+            .map(|(len_index, idx)| {
+                let which = found[&len_index.word(idx).len()].choice[&idx];
+                AbbrevAnagram {
+                    word: len_index.word_match(idx),
+                    fragments: picks[which].iter().map(|f| (*f).clone()).collect(),
+                }
+            })
+            .collect();
+        let has_more = offset + items.len() < total;
+
+        AbbrevAnagramResult {
+            total,
+            items,
+            has_more,
+            truncated,
+        }
+    }
+}
+
+/// The `n`th choice of one fragment per clue word, counting with the last
+/// word's choices changing fastest.
+fn pick(choices: &[Vec<Fragment>], mut n: usize) -> Vec<&Fragment> {
+    let mut picked: Vec<&Fragment> = choices
+        .iter()
+        .rev()
+        .map(|choices| {
+            let fragment = &choices[n % choices.len()];
+            n /= choices.len();
+            fragment
+        })
+        .collect();
+    picked.reverse();
+    picked
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::parse_pattern;
+    use crate::index::tests::make_index;
+
+    fn fragment(letters: &str, source: &str) -> Fragment {
+        Fragment {
+            cells: letters.to_string(),
+            letters: letters.to_string(),
+            source: source.to_string(),
+        }
+    }
+
+    fn bag(letters: &str) -> [u8; ALPHABET] {
+        let mut bag = [0u8; ALPHABET];
+        for cell in letters.bytes() {
+            bag[(cell - b'a') as usize] += 1;
+        }
+        bag
+    }
+
+    fn params<'a>(letters: &str, choices: &'a [Vec<Fragment>]) -> AbbrevAnagramParams<'a> {
+        AbbrevAnagramParams {
+            pattern: None,
+            bag_counts: bag(letters),
+            blanks: 0,
+            choices,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+        }
+    }
+
+    #[test]
+    fn adds_one_fragment_per_clue_word() {
+        let index = make_index(&["tale", "late", "teal", "tear;40", "treat", "tee"]);
+        let choices = [vec![fragment("l", "learner"), fragment("r", "right")]];
+        let result = index.query_abbrev_anagram(params("ate", &choices));
+        assert!(!result.truncated);
+        let found: Vec<(&str, &str)> = result
+            .items
+            .iter()
+            .map(|item| (item.word.word.as_str(), item.fragments[0].source.as_str()))
+            .collect();
+        assert_eq!(
+            found,
+            [
+                ("late", "learner"),
+                ("tale", "learner"),
+                ("teal", "learner"),
+                ("tear", "right"),
+            ]
+        );
+
+        // The pattern fixes the length and letters.
+        let pattern = parse_pattern("t___t").unwrap();
+        let choices = [vec![fragment("tr", "street"), fragment("l", "learner")]];
+        let result = index.query_abbrev_anagram(AbbrevAnagramParams {
+            pattern: Some(&pattern),
+            ..params("eat", &choices)
+        });
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].word.word, "treat");
+        assert_eq!(result.items[0].fragments, [fragment("tr", "street")]);
+    }
+
+    #[test]
+    fn reports_choices_left_untried() {
+        let index = make_index(&["tabcd"]);
+        let word: Vec<Fragment> = ["l", "b", "c", "d"]
+            .iter()
+            .map(|letters| fragment(letters, "clue"))
+            .collect();
+        // 4 * 4 * 4 = 64 choices are all tried; a fourth word makes 256.
+        let choices = vec![word.clone(); 3];
+        let result = index.query_abbrev_anagram(params("ta", &choices));
+        assert!(!result.truncated);
+        assert_eq!(result.total, 1);
+        assert_eq!(result.items[0].word.word, "tabcd");
+
+        let choices = vec![word; 4];
+        let result = index.query_abbrev_anagram(params("a", &choices));
+        assert!(result.truncated);
+    }
+}
@@ -0,0 +1,359 @@
+//! Alphabets: which cells a word list is spelled with and how other
+//! characters fold onto them.
+//!
+//! The index works on cells, not characters. A cell is usually one letter,
+//! but it can be a digraph such as Dutch "ij", which takes a single square in
+//! a Dutch grid. Folding rules map anything else onto cells: `é` onto `e` in
+//! French, `ß` onto `ss` in German. Entries keep their original spelling for
+//! display.
+//!
+//! Cell `i` is stored as the byte `b'a' + i`, so for English the stored form
+//! of a word is the word itself and everything downstream of the parsers
+//! keeps working on plain bytes.
+
+use std::collections::HashMap;
+use std::sync::{Arc, OnceLock};
+
+use thiserror::Error;
+
+use super::{ALPHABET, LetterSet};
+
+/// Presets accepted by [`Alphabet::parse`].
+pub const ALPHABET_PRESETS: [&str; 5] = ["en", "fr", "de", "nl", "es"];
+
+const LATIN: &str = "abcdefghijklmnopqrstuvwxyz";
+
+/// Accented vowels and consonants folded to their base letter by the
+/// presets that treat accents as decoration.
+const ACCENTS: &[(char, &str)] = &[
+    ('à', "a"),
+    ('á', "a"),
+    ('â', "a"),
+    ('ä', "a"),
+    ('ç', "c"),
+    ('è', "e"),
+    ('é', "e"),
+    ('ê', "e"),
+    ('ë', "e"),
+    ('ì', "i"),
+    ('í', "i"),
+    ('î', "i"),
+    ('ï', "i"),
+    ('ñ', "n"),
+    ('ò', "o"),
+    ('ó', "o"),
+    ('ô', "o"),
+    ('ö', "o"),
+    ('ù', "u"),
+    ('ú', "u"),
+    ('û', "u"),
+    ('ü', "u"),
+    ('ÿ', "y"),
+];
+
+/// First characters that make a cell count as a vowel for `@` and `#`.
+const VOWEL_STARTS: &str = "aeiouàáâäåæèéêëìíîïòóôöøœùúûü";
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum AlphabetError {
+    #[error("alphabet has no cells")]
+    Empty,
+    #[error("alphabet may have at most {0} cells")]
+    TooManyCells(usize),
+    #[error("cell {0:?} is listed twice")]
+    DuplicateCell(String),
+    #[error("invalid alphabet rule {0:?}")]
+    InvalidRule(String),
+    #[error("fold target {0:?} is not spelled with the alphabet's cells")]
+    UnknownTarget(String),
+}
+
+/// The cells of a word list and the rules folding other spellings onto them.
+#[derive(Debug, Clone)]
+pub struct Alphabet {
+    /// Preset name, or the spec the alphabet was parsed from.
+    name: String,
+    /// Lowercase spelling of each cell.
+    cells: Vec<String>,
+    vowels: LetterSet,
+    /// Cell codes per input sequence, keyed by first character; longest
+    /// sequences first so "ij" wins over "i".
+    rules: HashMap<char, Vec<(String, Vec<u8>)>>,
+    /// Code of ASCII characters that are a whole cell on their own, 0 when
+    /// the character needs a look at `rules`.
+    ascii: [u8; 128],
+    /// The cells are a–z in order, so stored words are their own spelling.
+    plain: bool,
+}
+
+impl PartialEq for Alphabet {
+    fn eq(&self, other: &Self) -> bool {
+        self.name == other.name
+    }
+}
+
+impl Eq for Alphabet {}
+
+impl Alphabet {
+    /// Plain a–z with no folding.
+    pub fn english() -> Arc<Alphabet> {
+        static ENGLISH: OnceLock<Arc<Alphabet>> = OnceLock::new();
+        Arc::clone(
+            ENGLISH.get_or_init(|| {
+                Arc::new(Alphabet::parse("en").expect("english alphabet is valid"))
+            }),
+        )
+    }
+
+    /// A preset (`en`, `fr`, `de`, `nl`, `es`) or a spec of comma-separated
+    /// cells and `from=to` folds, where `a-z` stands for the 26 Latin
+    /// letters: `a-z,ñ,á=a,é=e` is Spanish without the other accents.
+    pub fn parse(spec: &str) -> Result<Alphabet, AlphabetError> {
+        let spec = spec.trim();
+        let accents = || ACCENTS.iter().map(|(from, to)| (from.to_string(), *to));
+        let (cells, folds): (Vec<String>, Vec<(String, &str)>) = match spec {
+            "en" => (latin(), Vec::new()),
+            "fr" => (
+                latin(),
+                accents()
+                    .chain([("œ".to_string(), "oe"), ("æ".to_string(), "ae")])
+                    .collect(),
+            ),
+            // German grids write umlauts out and ß as "ss".
+            "de" => (
+                latin(),
+                [("ä", "ae"), ("ö", "oe"), ("ü", "ue"), ("ß", "ss")]
+                    .map(|(from, to)| (from.to_string(), to))
+                    .into_iter()
+                    .chain(accents().filter(|(from, _)| !"äöü".contains(from.as_str())))
+                    .collect(),
+            ),
+            "nl" => {
+                let mut cells = latin();
+                cells.push("ij".to_string());
+                (cells, accents().chain([("ĳ".to_string(), "ij")]).collect())
+            }
+            "es" => {
+                let mut cells = latin();
+                cells.push("ñ".to_string());
+                (cells, accents().filter(|(from, _)| from != "ñ").collect())
+            }
+            _ => return Self::from_spec(spec),
+        };
+        let folds = folds.into_iter().map(|(from, to)| (from, to.to_string()));
+        Self::build(spec.to_string(), cells, folds.collect())
+    }
+
+    fn from_spec(spec: &str) -> Result<Alphabet, AlphabetError> {
+        let mut cells = Vec::new();
+        let mut folds = Vec::new();
+        for item in spec.split(',').map(str::trim).filter(|s| !s.is_empty()) {
+            let item = item.to_lowercase();
+            if item == "a-z" {
+                cells.extend(latin());
+            } else if let Some((from, to)) = item.split_once('=') {
+                if from.is_empty() || to.is_empty() {
+                    return Err(AlphabetError::InvalidRule(item));
+                }
+                folds.push((from.to_string(), to.to_string()));
+            } else if item.chars().all(char::is_alphabetic) {
+                cells.push(item);
+            } else {
+                return Err(AlphabetError::InvalidRule(item));
+            }
+        }
+        Self::build(spec.to_string(), cells, folds)
+    }
+
+    fn build(
+        name: String,
+        cells: Vec<String>,
+        folds: Vec<(String, String)>,
+    ) -> Result<Alphabet, AlphabetError> {
+        if cells.is_empty() {
+            return Err(AlphabetError::Empty);
+        }
+        if cells.len() > ALPHABET {
+            return Err(AlphabetError::TooManyCells(ALPHABET));
+        }
+        let mut alphabet = Alphabet {
+            name,
+            cells: Vec::new(),
+            vowels: LetterSet::default(),
+            rules: HashMap::new(),
+            ascii: [0; 128],
+            plain: cells.len() == LATIN.len()
+                && cells
+                    .iter()
+                    .zip(LATIN.chars())
+                    .all(|(cell, c)| *cell == c.to_string()),
+        };
+        for (idx, cell) in cells.iter().enumerate() {
+            if alphabet.cells.contains(cell) {
+                return Err(AlphabetError::DuplicateCell(cell.clone()));
+            }
+            let code = b'a' + idx as u8;
+            if cell.starts_with(|c| VOWEL_STARTS.contains(c)) {
+                alphabet.vowels.insert(code);
+            }
+            alphabet.cells.push(cell.clone());
+            alphabet.add_rule(cell, vec![code]);
+        }
+        for (from, to) in folds {
+            let codes = alphabet
+                .cells_of(&to)
+                .ok_or_else(|| AlphabetError::UnknownTarget(to.clone()))?;
+            alphabet.add_rule(&from, codes);
+        }
+        for (first, rules) in &alphabet.rules {
+            if first.is_ascii()
+                && let [(tail, codes)] = rules.as_slice()
+                && tail.is_empty()
+                && codes.len() == 1
+            {
+                alphabet.ascii[*first as usize] = codes[0];
+            }
+        }
+        Ok(alphabet)
+    }
+
+    fn add_rule(&mut self, from: &str, codes: Vec<u8>) {
+        let mut chars = from.chars();
+        let first = chars.next().expect("rules are not empty");
+        let rules = self.rules.entry(first).or_default();
+        rules.retain(|(tail, _)| tail != chars.as_str());
+        rules.push((chars.as_str().to_string(), codes));
+        rules.sort_by_key(|(tail, _)| std::cmp::Reverse(tail.len()));
+    }
+
+    /// The preset name or spec this alphabet was built from.
+    pub fn name(&self) -> &str {
+        &self.name
+    }
+
+    /// Number of cells.
+    pub fn len(&self) -> usize {
+        self.cells.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.cells.is_empty()
+    }
+
+    /// Whether the cells are exactly a–z, so stored words read as written.
+    pub fn is_plain(&self) -> bool {
+        self.plain
+    }
+
+    /// Every cell as a set.
+    pub fn all(&self) -> LetterSet {
+        let mut set = LetterSet::default();
+        for idx in 0..self.cells.len() {
+            set.insert(b'a' + idx as u8);
+        }
+        set
+    }
+
+    pub fn vowels(&self) -> LetterSet {
+        self.vowels
+    }
+
+    pub fn consonants(&self) -> LetterSet {
+        self.all().intersection(self.vowels.complement())
+    }
+
+    /// Cells at the start of `rest`, which must already be lowercase, and
+    /// the number of bytes they cover.
+    pub(super) fn next_cells<'a>(&'a self, rest: &str) -> Option<(usize, &'a [u8])> {
+        let first = rest.chars().next()?;
+        if first.is_ascii() && self.ascii[first as usize] != 0 {
+            let code = &self.ascii[first as usize];
+            return Some((1, std::slice::from_ref(code)));
+        }
+        let after = &rest[first.len_utf8()..];
+        self.rules
+            .get(&first)?
+            .iter()
+            .find(|(tail, _)| after.starts_with(tail.as_str()))
+            .map(|(tail, codes)| (first.len_utf8() + tail.len(), codes.as_slice()))
+    }
+
+    /// Cell codes spelling all of `text`, or `None` if some part of it is not
+    /// in the alphabet. Case is ignored.
+    pub fn cells_of(&self, text: &str) -> Option<Vec<u8>> {
+        let lower = text.to_lowercase();
+        let mut rest = lower.as_str();
+        let mut codes = Vec::with_capacity(rest.len());
+        while !rest.is_empty() {
+            let (used, found) = self.next_cells(rest)?;
+            codes.extend_from_slice(found);
+            rest = &rest[used..];
+        }
+        Some(codes)
+    }
+
+    /// Spell out stored cell codes, e.g. `b"{and"` as "ñand" in Spanish.
+    pub fn render(&self, codes: &str) -> String {
+        if self.plain {
+            return codes.to_string();
+        }
+        codes
+            .bytes()
+            .map(|code| self.cells[(code - b'a') as usize].as_str())
+            .collect()
+    }
+}
+
+fn latin() -> Vec<String> {
+    LATIN.chars().map(String::from).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn presets_fold_to_cells() {
+        let fr = Alphabet::parse("fr").unwrap();
+        assert_eq!(fr.cells_of("Cœur").unwrap(), b"coeur");
+        assert_eq!(fr.cells_of("été").unwrap(), b"ete");
+
+        let de = Alphabet::parse("de").unwrap();
+        assert_eq!(de.cells_of("Straße").unwrap(), b"strasse");
+        assert_eq!(de.cells_of("Größe").unwrap(), b"groesse");
+
+        let nl = Alphabet::parse("nl").unwrap();
+        let ij = b'a' + 26;
+        assert_eq!(nl.cells_of("IJsje").unwrap(), [ij, b's', b'j', b'e']);
+        assert_eq!(nl.render("{sje"), "ijsje");
+
+        let es = Alphabet::parse("es").unwrap();
+        assert_eq!(es.cells_of("Año").unwrap(), [b'a', b'a' + 26, b'o']);
+        assert!(es.consonants().contains(b'a' + 26));
+
+        let en = Alphabet::english();
+        assert!(en.cells_of("café").is_none());
+        assert_eq!(en.render("cafe"), "cafe");
+    }
+
+    #[test]
+    fn parses_custom_specs() {
+        let custom = Alphabet::parse("a-z, å, ø=o").unwrap();
+        assert_eq!(custom.len(), 27);
+        assert_eq!(custom.cells_of("Sø").unwrap(), b"so");
+        assert!(custom.vowels().contains(b'a' + 26));
+
+        assert_eq!(
+            Alphabet::parse("a-z,a").unwrap_err(),
+            AlphabetError::DuplicateCell("a".into())
+        );
+        assert_eq!(
+            Alphabet::parse("a-z,x=þ").unwrap_err(),
+            AlphabetError::UnknownTarget("þ".into())
+        );
+        assert_eq!(
+            Alphabet::parse("a-z,a1,b2,c3,d4,e5,f6").unwrap_err(),
+            AlphabetError::InvalidRule("a1".into())
+        );
+    }
+}
@@ -0,0 +1,358 @@
+//! Fuzzy pattern matching: words that miss a pattern by a few letters.
+//!
+//! Hamming distance counts the fixed letters a word of the pattern's length
+//! gets wrong, and runs on the bitsets: after each fixed letter, `within[j]`
+//! holds the words with at most `j` wrong letters so far. A word keeps its
+//! count where it has the letter and moves up one where it does not. Letter
+//! classes and repeated-letter variables still have to hold.
+//!
+//! Levenshtein distance also lets letters be added or dropped, so it looks at
+//! every length within reach of the pattern and checks words one at a time,
+//! within a time budget. Every slot may be missed there, classes included.
+//! Repeated-letter variables tie slot positions together, which an edit
+//! shifts, so Levenshtein does not support them.
+
+use std::time::{Duration, Instant};
+
+use bitvec::prelude::*;
+
+use super::{
+    BitSet, Filters, LenIndex, MAX_WORD_LEN, Overlay, Pattern, SortOrder, WordIndex, WordMatch,
+    page_tiers,
+};
+
+/// Most mismatches a fuzzy query may allow.
+pub const MAX_MISMATCHES: usize = 3;
+/// Words checked between deadline checks.
+const DEADLINE_STRIDE: usize = 1024;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Distance {
+    /// Wrong letters at fixed positions; the length is kept.
+    #[default]
+    Hamming,
+    /// Letters substituted, added or dropped.
+    Levenshtein,
+}
+
+/// Parameters for [`WordIndex::query_fuzzy`]; the filters work as in
+/// [`QueryParams`](super::QueryParams).
+#[derive(Debug)]
+pub struct FuzzyParams<'a> {
+    /// For Levenshtein, a pattern without repeated-letter variables.
+    pub pattern: &'a Pattern,
+    pub max_mismatches: usize,
+    pub distance: Distance,
+    pub must_include: &'a [u8],
+    pub cannot_include: &'a [u8],
+    pub enumeration: Option<&'a [usize]>,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+    pub overlay: Option<&'a Overlay>,
+    pub time_limit: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct FuzzyResult {
+    pub total: usize,
+    pub items: Vec<WordMatch>,
+    pub has_more: bool,
+    /// Set when the time budget ran out before every word was checked.
+    pub truncated: bool,
+}
+
+impl WordIndex {
+    /// Words within `max_mismatches` of the pattern: exact matches first,
+    /// then those one letter off, and so on, each group in `sort` order.
+    pub fn query_fuzzy(&self, params: FuzzyParams<'_>) -> FuzzyResult {
+        let deadline = Instant::now() + params.time_limit;
+        let pattern = params.pattern;
+        let max = params.max_mismatches;
+        let filters = Filters {
+            must_include: params.must_include,
+            cannot_include: params.cannot_include,
+            enumeration: params.enumeration,
+            min_score: params.min_score,
+        };
+        let lengths = match params.distance {
+            Distance::Hamming => pattern.len()..=pattern.len(),
+            Distance::Levenshtein => {
+                pattern.len().saturating_sub(max).max(1)..=(pattern.len() + max).min(MAX_WORD_LEN)
+            }
+        };
+
+        let mut hits = Vec::new();
+        for len in lengths {
+            hits.extend(self.select(len, params.overlay, |len_index| {
+                let mut candidates = match params.distance {
+                    Distance::Hamming => len_index.hamming_candidates(pattern, max),
+                    Distance::Levenshtein => len_index.all.to_bitvec(),
+                };
+                len_index.apply_filters(&mut candidates, &filters);
+                candidates
+            }));
+        }
+
+        // Split every bucket by distance; words out of reach drop out here
+        // for Levenshtein.
+        let mut tiers: Vec<Vec<(&LenIndex, BitSet)>> = vec![Vec::new(); max + 1];
+        let mut checked = 0usize;
+        let mut truncated = false;
+        for (len_index, candidates) in &hits {
+            let mut split = vec![bitvec![usize, Lsb0; 0; len_index.count()]; max + 1];
+            for idx in candidates.iter_ones() {
+                if checked.is_multiple_of(DEADLINE_STRIDE) && Instant::now() >= deadline {
+                    truncated = true;
+                    break;
+                }
+                checked += 1;
+                let word = len_index.word(idx).as_bytes();
+                let distance = match params.distance {
+                    Distance::Hamming => hamming(pattern, word),
+                    Distance::Levenshtein => levenshtein(pattern, word, max),
+                };
+                if let Some(tier) = split.get_mut(distance) {
+                    tier.set(idx, true);
+                }
+            }
+            for (tier, found) in tiers.iter_mut().zip(split) {
+                if found.any() {
+                    tier.push((*len_index, found));
+                }
+            }
+            if truncated {
+                break;
+            }
+        }
+
+        let tiers: Vec<Vec<(&LenIndex, &BitSet)>> = tiers
+            .iter()
+            .map(|tier| tier.iter().map(|(l, c)| (*l, c)).collect())
+            .collect();
+        let result = page_tiers(&tiers, params.sort, params.page, params.page_size);
+        FuzzyResult {
+            total: result.total,
+            items: result.items,
+            has_more: result.has_more,
+            truncated,
+        }
+    }
+}
+
+impl LenIndex {
+    /// Words that satisfy the pattern's classes and variables and miss at
+    /// most `max` of its fixed letters.
+    fn hamming_candidates(&self, pattern: &Pattern, max: usize) -> BitSet {
+        let relaxed = Pattern {
+            letters: vec![None; pattern.len()],
+            classes: pattern.classes.clone(),
+            vars: pattern.vars.clone(),
+        };
+        let mut within = vec![self.pattern_candidates(&relaxed); max + 1];
+        for (pos, letter) in pattern
+            .letters
+            .iter()
+            .enumerate()
+            .filter_map(|(pos, letter)| Some((pos, (*letter)?)))
+        {
+            let hit = &*self.pos_letter[pos][(letter - b'a') as usize];
+            // Highest count first, so `within[j - 1]` still holds the counts
+            // from before this letter.
+            for j in (0..=max).rev() {
+                within[j] &= hit;
+                if j > 0 {
+                    let mut missed = within[j - 1].clone();
+                    missed &= &!hit.to_bitvec();
+                    within[j] |= &missed;
+                }
+            }
+        }
+        within.pop().expect("max + 1 tiers")
+    }
+}
+
+/// Whether slot `pos` of the pattern accepts `letter`.
+fn slot_accepts(pattern: &Pattern, pos: usize, letter: u8) -> bool {
+    pattern.letters[pos].is_none_or(|fixed| fixed == letter)
+        && pattern.classes[pos].is_none_or(|class| class.contains(letter))
+}
+
+/// Fixed letters of the pattern that `word` gets wrong.
+fn hamming(pattern: &Pattern, word: &[u8]) -> usize {
+    pattern
+        .letters
+        .iter()
+        .zip(word)
+        .filter(|(fixed, letter)| fixed.is_some_and(|fixed| fixed != **letter))
+        .count()
+}
+
+/// Edit distance between the pattern and `word`, or more than `max` once it
+/// is certain to exceed it.
+fn levenshtein(pattern: &Pattern, word: &[u8], max: usize) -> usize {
+    let slots = pattern.len();
+    let mut prev: Vec<usize> = (0..=slots).collect();
+    for (i, &letter) in word.iter().enumerate() {
+        let mut row = vec![i + 1; slots + 1];
+        for j in 1..=slots {
+            let cost = usize::from(!slot_accepts(pattern, j - 1, letter));
+            row[j] = (prev[j - 1] + cost).min(prev[j] + 1).min(row[j - 1] + 1);
+        }
+        if row.iter().all(|d| *d > max) {
+            return max + 1;
+        }
+        prev = row;
+    }
+    prev[slots]
+}
+
+/// Positions of `word` that disagree with the pattern. For Levenshtein these
+/// are the substituted and the added letters; dropped slots have no position.
+pub fn mismatched_positions(pattern: &Pattern, word: &[u8], distance: Distance) -> Vec<usize> {
+    match distance {
+        Distance::Hamming => pattern
+            .letters
+            .iter()
+            .zip(word)
+            .enumerate()
+            .filter(|(_, (fixed, letter))| fixed.is_some_and(|fixed| fixed != **letter))
+            .map(|(pos, _)| pos)
+            .collect(),
+        Distance::Levenshtein => {
+            let slots = pattern.len();
+            let cost = |i: usize, j: usize| usize::from(!slot_accepts(pattern, j, word[i]));
+            let mut table = vec![vec![0; slots + 1]; word.len() + 1];
+            for (i, row) in table.iter_mut().enumerate() {
+                row[0] = i;
+            }
+            table[0] = (0..=slots).collect();
+            for i in 1..=word.len() {
+                for j in 1..=slots {
+                    table[i][j] = (table[i - 1][j - 1] + cost(i - 1, j - 1))
+                        .min(table[i - 1][j] + 1)
+                        .min(table[i][j - 1] + 1);
+                }
+            }
+
+            let mut positions = Vec::new();
+            let (mut i, mut j) = (word.len(), slots);
+            while i > 0 || j > 0 {
+                if i > 0 && j > 0 && table[i][j] == table[i - 1][j - 1] + cost(i - 1, j - 1) {
+                    if cost(i - 1, j - 1) == 1 {
+                        positions.push(i - 1);
+                    }
+                    i -= 1;
+                    j -= 1;
+                } else if i > 0 && table[i][j] == table[i - 1][j] + 1 {
+                    positions.push(i - 1);
+                    i -= 1;
+                } else {
+                    j -= 1;
+                }
+            }
+            positions.reverse();
+            positions
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::parse_pattern;
+    use crate::index::tests::make_index;
+
+    fn fuzzy(index: &WordIndex, pattern: &str, max: usize, distance: Distance) -> Vec<String> {
+        let pattern = parse_pattern(pattern).unwrap();
+        index
+            .query_fuzzy(FuzzyParams {
+                pattern: &pattern,
+                max_mismatches: max,
+                distance,
+                must_include: &[],
+                cannot_include: &[],
+                enumeration: None,
+                min_score: None,
+                sort: SortOrder::Alpha,
+                page: 1,
+                page_size: 50,
+                overlay: None,
+                time_limit: Duration::from_secs(5),
+            })
+            .items
+            .into_iter()
+            .map(|m| m.word)
+            .collect()
+    }
+
+    #[test]
+    fn hamming_ranks_exact_matches_first() {
+        let index = make_index(&["apple", "ample", "angle", "amble", "addle", "maple"]);
+        assert_eq!(
+            fuzzy(&index, "ap_le", 1, Distance::Hamming),
+            ["apple", "addle", "amble", "ample", "angle"]
+        );
+        assert_eq!(fuzzy(&index, "ap_le", 0, Distance::Hamming), ["apple"]);
+        // Classes stay strict: only the fixed letters may be missed.
+        assert_eq!(
+            fuzzy(&index, "a[nd]_lz", 1, Distance::Hamming),
+            ["addle", "angle"]
+        );
+        assert_eq!(
+            fuzzy(&index, "zzple", 2, Distance::Hamming),
+            ["ample", "apple", "maple"]
+        );
+    }
+
+    #[test]
+    fn levenshtein_crosses_lengths() {
+        let index = make_index(&["apple", "apples", "aple", "ample", "grape"]);
+        assert_eq!(
+            fuzzy(&index, "apple", 1, Distance::Levenshtein),
+            ["apple", "ample", "aple", "apples"]
+        );
+    }
+
+    #[test]
+    fn stops_at_the_time_limit() {
+        let index = make_index(&["apple", "ample"]);
+        let pattern = parse_pattern("apple").unwrap();
+        let result = index.query_fuzzy(FuzzyParams {
+            pattern: &pattern,
+            max_mismatches: 1,
+            distance: Distance::Levenshtein,
+            must_include: &[],
+            cannot_include: &[],
+            enumeration: None,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+            overlay: None,
+            time_limit: Duration::ZERO,
+        });
+        assert!(result.truncated);
+        assert_eq!(result.total, 0);
+    }
+
+    #[test]
+    fn reports_mismatched_positions() {
+        let pattern = parse_pattern("ap_le").unwrap();
+        assert_eq!(
+            mismatched_positions(&pattern, b"amble", Distance::Hamming),
+            [1]
+        );
+        let pattern = parse_pattern("apple").unwrap();
+        assert_eq!(
+            mismatched_positions(&pattern, b"apples", Distance::Levenshtein),
+            [5]
+        );
+        assert_eq!(
+            mismatched_positions(&pattern, b"ample", Distance::Levenshtein),
+            [1]
+        );
+        assert!(mismatched_positions(&pattern, b"aple", Distance::Levenshtein).is_empty());
+    }
+}
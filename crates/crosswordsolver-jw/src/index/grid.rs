@@ -0,0 +1,522 @@
+//! Whole-grid filling on top of [`WordIndex`].
+//!
+//! The filler is a plain backtracking search: each open slot's domain is the
+//! set of words its current letters allow (straight from the positional
+//! bitsets), the slot with the smallest domain is filled next, and words are
+//! tried best score first. After every placement the crossing slots must
+//! still have at least one candidate, otherwise the word is undone.
+
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{Duration, Instant};
+
+use thiserror::Error;
+
+use super::{Alphabet, BitSet, LenIndex, MAX_WORD_LEN, Pattern, SortOrder, WordIndex, WordMatch};
+
+/// Largest supported grid width or height.
+pub const MAX_GRID_SIZE: usize = 25;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Cell {
+    Block,
+    Empty,
+    /// A cell code of the grid's alphabet.
+    Letter(u8),
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+/// A run of two or more open cells, numbered the usual crossword way.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub number: usize,
+    pub direction: Direction,
+    pub row: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Grid {
+    width: usize,
+    height: usize,
+    cells: Vec<Cell>,
+    /// Spells the letters; fill from an index with the same alphabet.
+    alphabet: Arc<Alphabet>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum GridError {
+    #[error("grid must have between 1 and {0} rows and columns")]
+    InvalidSize(usize),
+    #[error("grid rows must all have the same width")]
+    RaggedRows,
+    #[error("invalid character in grid: {0}")]
+    InvalidChar(char),
+}
+
+impl Grid {
+    /// Parse a grid from rows where `#` is a block, `.`, `_` or `?` an empty
+    /// cell and a letter a pre-filled cell.
+    pub fn parse<S: AsRef<str>>(rows: &[S]) -> Result<Self, GridError> {
+        Self::parse_with_alphabet(rows, Alphabet::english())
+    }
+
+    /// [`Grid::parse`] with letters read as cells of `alphabet`, so a Dutch
+    /// "ij" takes one square and "é" folds onto "e" in French. A letter
+    /// folding onto several cells, such as German "ß", would need as many
+    /// squares and is rejected.
+    pub fn parse_with_alphabet<S: AsRef<str>>(
+        rows: &[S],
+        alphabet: Arc<Alphabet>,
+    ) -> Result<Self, GridError> {
+        let height = rows.len();
+        if height == 0 || height > MAX_GRID_SIZE {
+            return Err(GridError::InvalidSize(MAX_GRID_SIZE));
+        }
+        let mut width = 0;
+        let mut cells = Vec::new();
+        for (i, row) in rows.iter().enumerate() {
+            let row = parse_row(row.as_ref(), &alphabet)?;
+            if i == 0 {
+                width = row.len();
+                if width == 0 {
+                    return Err(GridError::InvalidSize(MAX_GRID_SIZE));
+                }
+            } else if row.len() != width {
+                return Err(GridError::RaggedRows);
+            }
+            cells.extend(row);
+        }
+        Ok(Self {
+            width,
+            height,
+            cells,
+            alphabet,
+        })
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> Cell {
+        self.cells[row * self.width + col]
+    }
+
+    /// Rows in the same notation [`Grid::parse`] accepts.
+    pub fn to_rows(&self) -> Vec<String> {
+        self.cells
+            .chunks(self.width)
+            .map(|row| {
+                row.iter()
+                    .map(|cell| match cell {
+                        Cell::Block => "#".to_string(),
+                        Cell::Empty => ".".to_string(),
+                        Cell::Letter(b) => self.alphabet.render(&(*b as char).to_string()),
+                    })
+                    .collect()
+            })
+            .collect()
+    }
+
+    /// Every across and down slot in clue-number order, across before down.
+    pub fn slots(&self) -> Vec<Slot> {
+        let open = |row: usize, col: usize| self.cell(row, col) != Cell::Block;
+        let mut slots = Vec::new();
+        let mut number = 0;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if !open(row, col) {
+                    continue;
+                }
+                let across_len = if col == 0 || !open(row, col - 1) {
+                    (col..self.width).take_while(|c| open(row, *c)).count()
+                } else {
+                    0
+                };
+                let down_len = if row == 0 || !open(row - 1, col) {
+                    (row..self.height).take_while(|r| open(*r, col)).count()
+                } else {
+                    0
+                };
+                if across_len < 2 && down_len < 2 {
+                    continue;
+                }
+                number += 1;
+                for (direction, len) in
+                    [(Direction::Across, across_len), (Direction::Down, down_len)]
+                {
+                    if len >= 2 {
+                        slots.push(Slot {
+                            number,
+                            direction,
+                            row,
+                            col,
+                            len,
+                        });
+                    }
+                }
+            }
+        }
+        slots
+    }
+
+    /// Cell indices covered by `slot`.
+    fn slot_cells(&self, slot: &Slot) -> Vec<usize> {
+        let start = slot.row * self.width + slot.col;
+        let step = match slot.direction {
+            Direction::Across => 1,
+            Direction::Down => self.width,
+        };
+        (0..slot.len).map(|i| start + i * step).collect()
+    }
+
+    /// The slot's current letters as a pattern for [`WordIndex::query`].
+    pub fn slot_pattern(&self, slot: &Slot) -> Pattern {
+        Pattern::from(
+            self.slot_cells(slot)
+                .into_iter()
+                .map(|i| match self.cells[i] {
+                    Cell::Letter(b) => Some(b),
+                    _ => None,
+                })
+                .collect::<Vec<_>>(),
+        )
+    }
+}
+
+/// The cells of one row, at most [`MAX_GRID_SIZE`] of them.
+fn parse_row(row: &str, alphabet: &Alphabet) -> Result<Vec<Cell>, GridError> {
+    let lower = row.to_lowercase();
+    let mut rest = lower.as_str();
+    let mut cells = Vec::new();
+    while let Some(c) = rest.chars().next() {
+        if cells.len() == MAX_GRID_SIZE {
+            return Err(GridError::InvalidSize(MAX_GRID_SIZE));
+        }
+        let (used, cell) = match c {
+            '#' => (1, Cell::Block),
+            '.' | '_' | '?' => (1, Cell::Empty),
+            _ => match alphabet.next_cells(rest) {
+                Some((used, &[code])) => (used, Cell::Letter(code)),
+                _ => return Err(GridError::InvalidChar(c)),
+            },
+        };
+        cells.push(cell);
+        rest = &rest[used..];
+    }
+    Ok(cells)
+}
+
+#[derive(Debug, Clone)]
+pub struct FillParams {
+    pub min_score: Option<u8>,
+    /// Give up once this much time has passed.
+    pub time_limit: Duration,
+}
+
+/// A slot together with the word placed in it.
+#[derive(Debug, Clone, PartialEq)]
+pub struct FilledSlot {
+    pub slot: Slot,
+    pub word: WordMatch,
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct Fill {
+    pub grid: Grid,
+    pub entries: Vec<FilledSlot>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum FillError {
+    #[error("slot {0} is longer than {MAX_WORD_LEN} letters")]
+    SlotTooLong(usize),
+    #[error("no consistent fill exists for this grid")]
+    NoFill,
+    #[error("no fill found within the time limit")]
+    TimedOut,
+}
+
+impl WordIndex {
+    /// Fill every slot of `grid` with distinct words, keeping pre-filled
+    /// letters and preferring higher-scoring words.
+    pub fn fill_grid(&self, grid: &Grid, params: &FillParams) -> Result<Fill, FillError> {
+        let slots = grid.slots();
+        if let Some(slot) = slots.iter().find(|s| s.len > MAX_WORD_LEN) {
+            return Err(FillError::SlotTooLong(slot.number));
+        }
+
+        let slot_cells: Vec<Vec<usize>> = slots.iter().map(|s| grid.slot_cells(s)).collect();
+        let mut by_cell: Vec<Vec<usize>> = vec![Vec::new(); grid.cells.len()];
+        for (slot, cells) in slot_cells.iter().enumerate() {
+            for cell in cells {
+                by_cell[*cell].push(slot);
+            }
+        }
+        let crossings = slot_cells
+            .iter()
+            .enumerate()
+            .map(|(slot, cells)| {
+                cells
+                    .iter()
+                    .flat_map(|cell| by_cell[*cell].iter().copied())
+                    .filter(|other| *other != slot)
+                    .collect()
+            })
+            .collect();
+        let score_masks = (0..=MAX_WORD_LEN)
+            .map(|len| {
+                let bucket = self.lens.get(len)?.as_ref()?;
+                params.min_score.map(|min| bucket.score_mask(min))
+            })
+            .collect();
+
+        let mut filler = Filler {
+            index: self,
+            grid: grid.clone(),
+            assigned: vec![None; slots.len()],
+            slots,
+            slot_cells,
+            crossings,
+            score_masks,
+            used: HashSet::new(),
+            deadline: Instant::now() + params.time_limit,
+            timed_out: false,
+        };
+        if !filler.solve() {
+            return Err(if filler.timed_out {
+                FillError::TimedOut
+            } else {
+                FillError::NoFill
+            });
+        }
+
+        let entries = filler
+            .slots
+            .iter()
+            .zip(&filler.assigned)
+            .map(|(slot, idx)| FilledSlot {
+                slot: slot.clone(),
+                word: self
+                    .bucket(slot.len)
+                    .word_match(idx.expect("every slot is filled")),
+            })
+            .collect();
+        Ok(Fill {
+            grid: filler.grid,
+            entries,
+        })
+    }
+}
+
+struct Filler<'a> {
+    index: &'a WordIndex,
+    grid: Grid,
+    slots: Vec<Slot>,
+    slot_cells: Vec<Vec<usize>>,
+    crossings: Vec<Vec<usize>>,
+    /// `min_score` mask per word length, when a minimum is set.
+    score_masks: Vec<Option<BitSet>>,
+    /// Word index into its length bucket for each filled slot.
+    assigned: Vec<Option<usize>>,
+    /// Cells of every placed word, so "hot dog" and "hotdog" count as one.
+    used: HashSet<String>,
+    deadline: Instant,
+    timed_out: bool,
+}
+
+impl<'a> Filler<'a> {
+    fn solve(&mut self) -> bool {
+        if Instant::now() >= self.deadline {
+            self.timed_out = true;
+            return false;
+        }
+
+        // Fill the most constrained slot next.
+        let mut best: Option<(usize, &'a LenIndex, BitSet, usize)> = None;
+        for slot in 0..self.slots.len() {
+            if self.assigned[slot].is_some() {
+                continue;
+            }
+            let Some((bucket, domain)) = self.domain(slot) else {
+                return false;
+            };
+            let count = domain.count_ones();
+            if count == 0 {
+                return false;
+            }
+            if best.as_ref().is_none_or(|b| count < b.3) {
+                best = Some((slot, bucket, domain, count));
+            }
+        }
+        let Some((slot, bucket, domain, _)) = best else {
+            return true;
+        };
+
+        for idx in bucket.ordered(&domain, SortOrder::Score) {
+            let placed = self.place(slot, bucket, idx);
+            let viable = self.crossings[slot].iter().all(|other| {
+                self.assigned[*other].is_some() || self.domain(*other).is_some_and(|(_, d)| d.any())
+            });
+            if viable && self.solve() {
+                return true;
+            }
+            self.assigned[slot] = None;
+            self.used.remove(bucket.word(idx));
+            for cell in placed {
+                self.grid.cells[cell] = Cell::Empty;
+            }
+            if self.timed_out {
+                return false;
+            }
+        }
+        false
+    }
+
+    /// Candidates for `slot` given the letters already in the grid.
+    fn domain(&self, slot: usize) -> Option<(&'a LenIndex, BitSet)> {
+        let len = self.slots[slot].len;
+        let bucket = self.index.lens.get(len)?.as_ref()?;
+        let mut domain = bucket.pattern_candidates(&self.grid.slot_pattern(&self.slots[slot]));
+        if let Some(mask) = &self.score_masks[len] {
+            domain &= mask;
+        }
+        for word in self.used.iter().filter(|w| w.len() == len) {
+            for idx in bucket.find(word) {
+                domain.set(idx, false);
+            }
+        }
+        Some((bucket, domain))
+    }
+
+    /// Write a word into the grid, returning the cells that were empty.
+    fn place(&mut self, slot: usize, bucket: &LenIndex, idx: usize) -> Vec<usize> {
+        let mut placed = Vec::new();
+        for (cell, letter) in self.slot_cells[slot].iter().zip(bucket.word(idx).bytes()) {
+            if self.grid.cells[*cell] == Cell::Empty {
+                self.grid.cells[*cell] = Cell::Letter(letter);
+                placed.push(*cell);
+            }
+        }
+        self.assigned[slot] = Some(idx);
+        self.used.insert(bucket.word(idx).to_string());
+        placed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::tests::make_index;
+
+    fn params() -> FillParams {
+        FillParams {
+            min_score: None,
+            time_limit: Duration::from_secs(5),
+        }
+    }
+
+    #[test]
+    fn numbers_slots_like_a_crossword() {
+        let grid = Grid::parse(&["...", ".#.", "..."]).unwrap();
+        let slots: Vec<(usize, Direction, usize)> = grid
+            .slots()
+            .into_iter()
+            .map(|s| (s.number, s.direction, s.len))
+            .collect();
+        assert_eq!(
+            slots,
+            vec![
+                (1, Direction::Across, 3),
+                (1, Direction::Down, 3),
+                (2, Direction::Down, 3),
+                (3, Direction::Across, 3),
+            ]
+        );
+        assert_eq!(Grid::parse(&["ab", "a"]), Err(GridError::RaggedRows));
+        assert_eq!(Grid::parse(&["a!"]), Err(GridError::InvalidChar('!')));
+    }
+
+    #[test]
+    fn fills_grid_backtracking_from_best_words() {
+        let index = make_index(&[
+            "bet;60", "bat;50", "ore;40", "ode;30", "bo", "ar", "te", "at",
+        ]);
+        let grid = Grid::parse(&["b..", "..."]).unwrap();
+        let fill = index.fill_grid(&grid, &params()).unwrap();
+        // "bet" scores higher but leaves no word for the second down slot.
+        assert_eq!(fill.grid.to_rows(), vec!["bat", "ore"]);
+        let entries: Vec<(usize, Direction, &str)> = fill
+            .entries
+            .iter()
+            .map(|e| (e.slot.number, e.slot.direction, e.word.word.as_str()))
+            .collect();
+        assert_eq!(
+            entries,
+            vec![
+                (1, Direction::Across, "bat"),
+                (1, Direction::Down, "bo"),
+                (2, Direction::Down, "ar"),
+                (3, Direction::Down, "te"),
+                (4, Direction::Across, "ore"),
+            ]
+        );
+    }
+
+    #[test]
+    fn never_repeats_a_word() {
+        let index = make_index(&["bat", "ate", "tea"]);
+        let grid = Grid::parse(&["b..", "...", "..."]).unwrap();
+        assert_eq!(index.fill_grid(&grid, &params()), Err(FillError::NoFill));
+
+        // Entries with the same letters are the same word in a grid.
+        let index = make_index(&["hotdog", "hot dog"]);
+        let grid = Grid::parse(&["......", "######", "......"]).unwrap();
+        assert_eq!(index.fill_grid(&grid, &params()), Err(FillError::NoFill));
+    }
+
+    #[test]
+    fn reads_letters_with_the_alphabet() {
+        let dutch = Arc::new(Alphabet::parse("nl").unwrap());
+        let grid = Grid::parse_with_alphabet(&["IJs.", "#.."], Arc::clone(&dutch)).unwrap();
+        assert_eq!(grid.width(), 3);
+        assert_eq!(grid.to_rows(), vec!["ijs.", "#.."]);
+        let french = Arc::new(Alphabet::parse("fr").unwrap());
+        let grid = Grid::parse_with_alphabet(&["été"], french).unwrap();
+        assert_eq!(grid.to_rows(), vec!["ete"]);
+        let german = Arc::new(Alphabet::parse("de").unwrap());
+        assert_eq!(
+            Grid::parse_with_alphabet(&["fuß"], german),
+            Err(GridError::InvalidChar('ß'))
+        );
+        assert_eq!(Grid::parse(&["é"]), Err(GridError::InvalidChar('é')));
+    }
+
+    #[test]
+    fn reports_when_no_fill_exists() {
+        let index = make_index(&["bat", "ate", "tea"]);
+        let grid = Grid::parse(&["x..", "...", "..."]).unwrap();
+        assert_eq!(index.fill_grid(&grid, &params()), Err(FillError::NoFill));
+
+        let grid = Grid::parse(&["...", "...", "..."]).unwrap();
+        let timed = index.fill_grid(
+            &grid,
+            &FillParams {
+                time_limit: Duration::ZERO,
+                ..params()
+            },
+        );
+        assert_eq!(timed.err(), Some(FillError::TimedOut));
+    }
+}
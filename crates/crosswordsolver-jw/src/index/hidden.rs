@@ -0,0 +1,180 @@
+//! Hidden words: answers spelled out by a run of letters in the clue text,
+//! across word breaks and punctuation, read forwards or backwards.
+//!
+//! The clue is folded onto the list's cells once, remembering which clue
+//! characters each cell came from. Every window of the pattern's length is
+//! then looked up in the length bucket and checked against the pattern's
+//! candidates.
+
+use std::ops::Range;
+
+use thiserror::Error;
+
+use super::{Alphabet, Pattern, WordIndex, WordMatch};
+
+/// Longest clue accepted, in characters.
+pub const MAX_CLUE_LEN: usize = 300;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum HiddenError {
+    #[error("clue must be at most {0} characters")]
+    TooLong(usize),
+}
+
+/// A word found inside the clue.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct HiddenWord {
+    pub word: WordMatch,
+    /// Characters of the clue the word is hidden in: `start` inclusive,
+    /// `end` exclusive, counted in chars.
+    pub start: usize,
+    pub end: usize,
+    /// The clue text between `start` and `end`.
+    pub text: String,
+    /// Set when the word reads right to left.
+    pub reversed: bool,
+}
+
+impl WordIndex {
+    /// Entries fitting `pattern` that appear as a run of letters in `clue`,
+    /// forwards or reversed, in order of where they start.
+    pub fn hidden_words(
+        &self,
+        clue: &str,
+        pattern: &Pattern,
+        min_score: Option<u8>,
+    ) -> Result<Vec<HiddenWord>, HiddenError> {
+        if clue.chars().count() > MAX_CLUE_LEN {
+            return Err(HiddenError::TooLong(MAX_CLUE_LEN));
+        }
+        let len = pattern.len();
+        let Some(len_index) = self.lens.get(len).and_then(Option::as_ref) else {
+            return Ok(Vec::new());
+        };
+        let mut candidates = len_index.pattern_candidates(pattern);
+        if let Some(min_score) = min_score {
+            candidates &= &len_index.score_mask(min_score);
+        }
+
+        let cells = clue_cells(&self.alphabet, clue);
+        let chars: Vec<char> = clue.chars().collect();
+        let mut found = Vec::new();
+        for window in cells.windows(len) {
+            let forwards: String = window.iter().map(|(code, _)| *code as char).collect();
+            let backwards: String = forwards.chars().rev().collect();
+            let start = window[0].1.start;
+            let end = window[len - 1].1.end;
+            // Palindromes read the same both ways; report them once.
+            let readings = if forwards == backwards {
+                vec![(forwards, false)]
+            } else {
+                vec![(forwards, false), (backwards, true)]
+            };
+            for (spelled, reversed) in readings {
+                for idx in len_index.find(&spelled).filter(|idx| candidates[*idx]) {
+                    found.push(HiddenWord {
+                        word: len_index.word_match(idx),
+                        start,
+                        end,
+                        text: chars[start..end].iter().collect(),
+                        reversed,
+                    });
+                }
+            }
+        }
+        Ok(found)
+    }
+}
+
+/// The clue's letters as cell codes, each with the clue characters it was
+/// read from. Anything outside the alphabet is skipped.
+fn clue_cells(alphabet: &Alphabet, clue: &str) -> Vec<(u8, Range<usize>)> {
+    // Lowercasing can change byte lengths, so map every byte of the
+    // lowercase text back to its clue character.
+    let mut lower = String::with_capacity(clue.len());
+    let mut char_at = Vec::with_capacity(clue.len());
+    for (pos, c) in clue.chars().enumerate() {
+        for lowered in c.to_lowercase() {
+            lower.push(lowered);
+            char_at.extend(std::iter::repeat_n(pos, lowered.len_utf8()));
+        }
+    }
+
+    let mut cells = Vec::new();
+    let mut offset = 0;
+    while let Some(c) = lower[offset..].chars().next() {
+        match alphabet.next_cells(&lower[offset..]) {
+            Some((used, codes)) => {
+                let chars = char_at[offset]..char_at[offset + used - 1] + 1;
+                cells.extend(codes.iter().map(|code| (*code, chars.clone())));
+                offset += used;
+            }
+            None => offset += c.len_utf8(),
+        }
+    }
+    cells
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::parse_pattern;
+    use crate::index::tests::make_index;
+
+    fn hidden(index: &WordIndex, clue: &str, pattern: &str) -> Vec<(String, usize, bool)> {
+        let pattern = parse_pattern(pattern).unwrap();
+        index
+            .hidden_words(clue, &pattern, None)
+            .unwrap()
+            .into_iter()
+            .map(|h| (h.word.word, h.start, h.reversed))
+            .collect()
+    }
+
+    #[test]
+    fn finds_words_across_breaks_and_reversed() {
+        let index = make_index(&["feller", "rock", "tar", "rat", "level", "hot dog"]);
+        assert_eq!(
+            hidden(&index, "Some Rockefeller crowd", "______"),
+            [("feller".to_string(), 10, false)]
+        );
+        assert_eq!(
+            hidden(&index, "Some Rockefeller crowd", "r___"),
+            [("rock".to_string(), 5, false)]
+        );
+        assert_eq!(
+            hidden(&index, "Star, rat!", "___"),
+            [
+                ("tar".to_string(), 1, false),
+                ("rat".to_string(), 1, true),
+                ("rat".to_string(), 6, false),
+                ("tar".to_string(), 6, true),
+            ]
+        );
+        assert!(hidden(&index, "Nothing to see", "_____").is_empty());
+        assert_eq!(
+            hidden(&index, "shot, dogged", "______"),
+            [("hotdog".to_string(), 1, false)]
+        );
+    }
+
+    #[test]
+    fn reports_clue_spans() {
+        let index = make_index(&["level", "tar"]);
+        let pattern = parse_pattern("_____").unwrap();
+        let found = index
+            .hidden_words("Real EVE, Lurking", &pattern, None)
+            .unwrap();
+        assert_eq!(found.len(), 1);
+        assert_eq!((found[0].start, found[0].end), (3, 11));
+        assert_eq!(found[0].text, "l EVE, L");
+        assert!(!found[0].reversed);
+
+        assert_eq!(
+            index
+                .hidden_words(&"a".repeat(MAX_CLUE_LEN + 1), &pattern, None)
+                .unwrap_err(),
+            HiddenError::TooLong(MAX_CLUE_LEN)
+        );
+    }
+}
@@ -0,0 +1,279 @@
+//! Word ladders (doublets): the shortest chains from one word to another of
+//! the same length, changing one letter per step.
+//!
+//! The length bucket is the graph. A word's neighbours differing at position
+//! `p` are the words that share its letters at every other position, i.e. the
+//! AND of the positional bitsets for those letters; prefix and suffix ANDs
+//! give all positions in one pass. The search is a breadth-first walk one
+//! level at a time, keeping every parent on the level before so that all
+//! shortest paths can be read back.
+
+use std::collections::HashMap;
+use std::time::{Duration, Instant};
+
+use bitvec::prelude::*;
+use thiserror::Error;
+
+use super::{BitSet, LenIndex, MAX_WORD_LEN, WordIndex, WordMatch};
+
+/// Most steps a ladder may take.
+pub const MAX_LADDER_STEPS: usize = 30;
+
+#[derive(Debug)]
+pub struct LadderParams {
+    pub max_steps: usize,
+    /// Shortest paths to return at most.
+    pub max_paths: usize,
+    /// Only step through words scoring at least this; the two ends are
+    /// exempt.
+    pub min_score: Option<u8>,
+    pub time_limit: Duration,
+}
+
+#[derive(Debug, Clone)]
+pub struct LadderResult {
+    /// Shortest paths, each starting with `from` and ending with `to`.
+    pub paths: Vec<Vec<WordMatch>>,
+    /// Set when the time budget ran out before the search finished.
+    pub truncated: bool,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum LadderError {
+    #[error("{0:?} is not in the word list")]
+    UnknownWord(String),
+    #[error("both words must have the same length")]
+    LengthMismatch,
+}
+
+impl WordIndex {
+    /// Shortest ladders from `from` to `to` through single words of the list.
+    pub fn word_ladder(
+        &self,
+        from: &str,
+        to: &str,
+        params: &LadderParams,
+    ) -> Result<LadderResult, LadderError> {
+        let cells = |word: &str| {
+            self.alphabet
+                .cells_of(word.trim())
+                .filter(|cells| !cells.is_empty() && cells.len() <= MAX_WORD_LEN)
+                .and_then(|cells| String::from_utf8(cells).ok())
+                .ok_or_else(|| LadderError::UnknownWord(word.to_string()))
+        };
+        let (start_word, end_word) = (cells(from)?, cells(to)?);
+        if start_word.len() != end_word.len() {
+            return Err(LadderError::LengthMismatch);
+        }
+        let Some(len_index) = self.lens.get(start_word.len()).and_then(Option::as_ref) else {
+            return Err(LadderError::UnknownWord(from.to_string()));
+        };
+        let start = len_index
+            .single_word(&start_word)
+            .ok_or_else(|| LadderError::UnknownWord(from.to_string()))?;
+        let end = len_index
+            .single_word(&end_word)
+            .ok_or_else(|| LadderError::UnknownWord(to.to_string()))?;
+
+        let mut allowed = !len_index.phrase.to_bitvec();
+        if let Some(min_score) = params.min_score {
+            allowed &= &len_index.score_mask(min_score);
+        }
+        allowed.set(end, true);
+
+        let deadline = Instant::now() + params.time_limit;
+        let mut visited = bitvec![usize, Lsb0; 0; len_index.count()];
+        visited.set(start, true);
+        let mut frontier = vec![start];
+        let mut parents: HashMap<usize, Vec<usize>> = HashMap::new();
+        let mut truncated = false;
+        let mut steps = 0;
+        'levels: while start != end && !parents.contains_key(&end) && steps < params.max_steps {
+            let mut open = allowed.clone();
+            open &= &!visited.clone();
+            let mut next = bitvec![usize, Lsb0; 0; len_index.count()];
+            for (expanded, &word) in frontier.iter().enumerate() {
+                if expanded % 64 == 0 && Instant::now() >= deadline {
+                    truncated = true;
+                    break 'levels;
+                }
+                let mut neighbours = len_index.neighbours(word);
+                neighbours &= &open;
+                for found in neighbours.iter_ones() {
+                    parents.entry(found).or_default().push(word);
+                }
+                next |= &neighbours;
+            }
+            if next.not_any() {
+                break;
+            }
+            visited |= &next;
+            frontier = next.iter_ones().collect();
+            steps += 1;
+        }
+
+        let mut paths = Vec::new();
+        if start == end {
+            paths.push(vec![start]);
+        } else if parents.contains_key(&end) {
+            let mut path = vec![end];
+            collect_paths(&parents, start, &mut path, &mut paths, params.max_paths);
+        }
+        Ok(LadderResult {
+            paths: paths
+                .into_iter()
+                .map(|path| {
+                    path.into_iter()
+                        .map(|idx| len_index.word_match(idx))
+                        .collect()
+                })
+                .collect(),
+            truncated,
+        })
+    }
+}
+
+/// Walk the parent links from the end of `path` back to `start`, recording up
+/// to `limit` paths in start-to-end order.
+fn collect_paths(
+    parents: &HashMap<usize, Vec<usize>>,
+    start: usize,
+    path: &mut Vec<usize>,
+    paths: &mut Vec<Vec<usize>>,
+    limit: usize,
+) {
+    let last = *path.last().expect("path is never empty");
+    if last == start {
+        paths.push(path.iter().rev().copied().collect());
+        return;
+    }
+    for &parent in &parents[&last] {
+        if paths.len() >= limit {
+            return;
+        }
+        path.push(parent);
+        collect_paths(parents, start, path, paths, limit);
+        path.pop();
+    }
+}
+
+impl LenIndex {
+    /// The entry spelled `word` that is a single word, not a phrase.
+    fn single_word(&self, word: &str) -> Option<usize> {
+        self.find(word).find(|idx| !self.phrase[*idx])
+    }
+
+    /// Entries that differ from entry `idx` in exactly one position.
+    fn neighbours(&self, idx: usize) -> BitSet {
+        let letters: Vec<usize> = self
+            .word(idx)
+            .bytes()
+            .map(|letter| (letter - b'a') as usize)
+            .collect();
+        let len = letters.len();
+        // suffix[p]: words sharing the letters from position p onwards.
+        let mut suffix = vec![self.all.to_bitvec(); len + 1];
+        for pos in (0..len).rev() {
+            let mut shared = suffix[pos + 1].clone();
+            shared &= &*self.pos_letter[pos][letters[pos]];
+            suffix[pos] = shared;
+        }
+
+        let mut neighbours = bitvec![usize, Lsb0; 0; self.count()];
+        let mut prefix = self.all.to_bitvec();
+        for pos in 0..len {
+            let mut differ_here = prefix.clone();
+            differ_here &= &suffix[pos + 1];
+            differ_here &= &!self.pos_letter[pos][letters[pos]].to_bitvec();
+            neighbours |= &differ_here;
+            prefix &= &*self.pos_letter[pos][letters[pos]];
+        }
+        neighbours
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::tests::make_index;
+
+    fn params(max_steps: usize, max_paths: usize) -> LadderParams {
+        LadderParams {
+            max_steps,
+            max_paths,
+            min_score: None,
+            time_limit: Duration::from_secs(5),
+        }
+    }
+
+    fn spelled(result: &LadderResult) -> Vec<Vec<&str>> {
+        result
+            .paths
+            .iter()
+            .map(|path| path.iter().map(|m| m.word.as_str()).collect())
+            .collect()
+    }
+
+    #[test]
+    fn finds_every_shortest_ladder() {
+        let index = make_index(&[
+            "cold", "cord", "card", "ward", "warm", "word", "worm", "corm", "wold", "bold",
+        ]);
+        let result = index.word_ladder("cold", "warm", &params(10, 10)).unwrap();
+        assert_eq!(
+            spelled(&result),
+            [
+                ["cold", "cord", "card", "ward", "warm"],
+                ["cold", "cord", "word", "ward", "warm"],
+                ["cold", "wold", "word", "ward", "warm"],
+                ["cold", "cord", "corm", "worm", "warm"],
+                ["cold", "cord", "word", "worm", "warm"],
+                ["cold", "wold", "word", "worm", "warm"],
+            ]
+        );
+
+        let result = index.word_ladder("cold", "warm", &params(10, 2)).unwrap();
+        assert_eq!(result.paths.len(), 2);
+        assert!(
+            index
+                .word_ladder("cold", "warm", &params(3, 5))
+                .unwrap()
+                .paths
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn restricts_steps_to_scored_words() {
+        let index = make_index(&["cat;50", "cot;10", "cut;50", "cog;50", "dog;50", "cug;50"]);
+        let mut strict = params(10, 5);
+        strict.min_score = Some(40);
+        let result = index.word_ladder("cat", "dog", &strict).unwrap();
+        assert_eq!(spelled(&result), [["cat", "cut", "cug", "cog", "dog"]]);
+        let result = index.word_ladder("cat", "dog", &params(10, 5)).unwrap();
+        assert_eq!(spelled(&result), [["cat", "cot", "cog", "dog"]]);
+    }
+
+    #[test]
+    fn rejects_unknown_or_mismatched_words() {
+        let index = make_index(&["cat", "cot", "hot dog"]);
+        assert_eq!(
+            index.word_ladder("cat", "zzz", &params(5, 1)).unwrap_err(),
+            LadderError::UnknownWord("zzz".into())
+        );
+        assert_eq!(
+            index
+                .word_ladder("cat", "hotdog", &params(5, 1))
+                .unwrap_err(),
+            LadderError::LengthMismatch
+        );
+        assert_eq!(
+            index
+                .word_ladder("cot", "cot", &params(5, 1))
+                .unwrap()
+                .paths
+                .len(),
+            1
+        );
+    }
+}
@@ -0,0 +1,228 @@
+//! Per-query word overlays: extra entries to allow and words to deny on top
+//! of a [`WordIndex`], without rebuilding it.
+//!
+//! Allowed entries are indexed into a small index of their own, and queries
+//! page through both indexes together. Denied words are removed from both, and
+//! a base entry that is also allowed is hidden so the overlay's copy (and its
+//! score) is the only one returned.
+
+use std::collections::HashSet;
+use std::ops::Range;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use super::{Alphabet, BitSet, LenIndex, MAX_WORD_LEN, WordIndex, normalize_word, parse_entry};
+
+/// Most entries accepted in each of the allow and deny lists.
+pub const MAX_OVERLAY_WORDS: usize = 5000;
+
+#[derive(Debug, Clone)]
+pub struct Overlay {
+    extra: WordIndex,
+    /// Normalized letters of every denied word.
+    deny: HashSet<String>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum OverlayError {
+    #[error("overlay lists may hold at most {0} words each")]
+    TooManyWords(usize),
+    #[error("invalid overlay word: {0:?}")]
+    InvalidWord(String),
+}
+
+impl Overlay {
+    /// Build an overlay for an index spelled with `alphabet`. Allowed
+    /// entries use word-list syntax, so `theme;60` carries a score and
+    /// "hot dog" keeps its word break. Denied words match on letters only,
+    /// so denying "hotdog" also removes "hot dog".
+    pub fn new<A, D>(alphabet: &Arc<Alphabet>, allow: A, deny: D) -> Result<Self, OverlayError>
+    where
+        A: IntoIterator,
+        A::Item: AsRef<str>,
+        D: IntoIterator,
+        D::Item: AsRef<str>,
+    {
+        let mut entries = Vec::new();
+        for raw in allow {
+            if entries.len() == MAX_OVERLAY_WORDS {
+                return Err(OverlayError::TooManyWords(MAX_OVERLAY_WORDS));
+            }
+            let raw = raw.as_ref();
+            let entry =
+                parse_entry(raw, alphabet).ok_or_else(|| OverlayError::InvalidWord(raw.into()))?;
+            entries.push(entry);
+        }
+        let mut denied = HashSet::new();
+        for (count, raw) in deny.into_iter().enumerate() {
+            if count == MAX_OVERLAY_WORDS {
+                return Err(OverlayError::TooManyWords(MAX_OVERLAY_WORDS));
+            }
+            let raw = raw.as_ref();
+            let (word, _) = normalize_word(raw.trim(), alphabet)
+                .ok_or_else(|| OverlayError::InvalidWord(raw.into()))?;
+            denied.insert(word);
+        }
+
+        let mut buckets = vec![Vec::new(); MAX_WORD_LEN + 1];
+        for entry in entries {
+            buckets[entry.word.len()].push(entry);
+        }
+        Ok(Self {
+            extra: WordIndex::from_buckets(buckets, Arc::clone(alphabet)),
+            deny: denied,
+        })
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.deny.is_empty() && self.extra.word_count() == 0
+    }
+
+    /// Allowed entries of length `len`.
+    pub(super) fn extra(&self, len: usize) -> Option<&LenIndex> {
+        self.extra.lens.get(len).and_then(Option::as_ref)
+    }
+
+    /// Drop denied words, and base entries the overlay replaces, from
+    /// `candidates` of the base bucket `base`.
+    pub(super) fn mask_base(&self, base: &LenIndex, candidates: &mut BitSet) {
+        self.mask_denied(base, candidates);
+        let Some(extra) = self.extra(base.pos_letter.len()) else {
+            return;
+        };
+        for idx in 0..extra.count() {
+            for found in base.find(extra.word(idx)) {
+                if base.display[found] == extra.display[idx] {
+                    candidates.set(found, false);
+                }
+            }
+        }
+    }
+
+    /// Drop denied words from `candidates` of the overlay's own bucket.
+    pub(super) fn mask_extra(&self, extra: &LenIndex, candidates: &mut BitSet) {
+        self.mask_denied(extra, candidates);
+    }
+
+    fn mask_denied(&self, bucket: &LenIndex, candidates: &mut BitSet) {
+        for word in self
+            .deny
+            .iter()
+            .filter(|w| w.len() == bucket.pos_letter.len())
+        {
+            for idx in bucket.find(word) {
+                candidates.set(idx, false);
+            }
+        }
+    }
+}
+
+impl LenIndex {
+    /// Indices of the entries spelled `word`; buckets are alphabetical, so
+    /// they form one run.
+    pub(super) fn find(&self, word: &str) -> Range<usize> {
+        let bound = |inclusive: bool| {
+            let (mut lo, mut hi) = (0, self.count());
+            while lo < hi {
+                let mid = (lo + hi) / 2;
+                let current = self.word(mid);
+                if current < word || (inclusive && current == word) {
+                    lo = mid + 1;
+                } else {
+                    hi = mid;
+                }
+            }
+            lo
+        };
+        bound(false)..bound(true)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::tests::make_index;
+    use crate::index::{
+        QueryParams, SortOrder, WildcardParams, parse_pattern, parse_wildcard_pattern,
+    };
+
+    fn words(index: &WordIndex, pattern: &str, overlay: Option<&Overlay>) -> Vec<String> {
+        let pattern = parse_pattern(pattern).unwrap();
+        index
+            .query(QueryParams {
+                pattern: &pattern,
+                must_include: &[],
+                cannot_include: &[],
+                enumeration: None,
+                min_score: None,
+                sort: SortOrder::Score,
+                page: 1,
+                page_size: 50,
+                overlay,
+            })
+            .items
+            .into_iter()
+            .map(|m| m.display)
+            .collect()
+    }
+
+    #[test]
+    fn overlay_adds_denies_and_rescores() {
+        let index = make_index(&["apple;50", "angle;40", "ankle;30", "hot dog;50"]);
+        let overlay = Overlay::new(
+            &Alphabet::english(),
+            ["ankle;90", "aspic", "hotrod;45"],
+            ["ANGLE"],
+        )
+        .unwrap();
+
+        assert_eq!(words(&index, "a____", None), ["apple", "angle", "ankle"]);
+        assert_eq!(
+            words(&index, "a____", Some(&overlay)),
+            ["ankle", "apple", "aspic"]
+        );
+        assert_eq!(
+            words(&index, "ho____", Some(&overlay)),
+            ["hot dog", "hotrod"]
+        );
+
+        let deny_phrase =
+            Overlay::new(&Alphabet::english(), Vec::<&str>::new(), ["hotdog"]).unwrap();
+        assert!(words(&index, "ho____", Some(&deny_phrase)).is_empty());
+    }
+
+    #[test]
+    fn overlay_applies_to_wildcards_and_lengths_missing_from_base() {
+        let index = make_index(&["apple", "angle"]);
+        let overlay = Overlay::new(&Alphabet::english(), ["ant", "aspics"], ["apple"]).unwrap();
+        let pattern = parse_wildcard_pattern("a*").unwrap();
+        let result = index.query_wildcard(WildcardParams {
+            pattern: &pattern,
+            min_len: 1,
+            max_len: MAX_WORD_LEN,
+            must_include: &[],
+            cannot_include: &[],
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+            overlay: Some(&overlay),
+        });
+        let found: Vec<_> = result.items.into_iter().map(|m| m.word).collect();
+        assert_eq!(found, ["angle", "ant", "aspics"]);
+    }
+
+    #[test]
+    fn overlay_rejects_bad_words() {
+        assert_eq!(
+            Overlay::new(&Alphabet::english(), ["ok", "n0pe"], Vec::<&str>::new()).unwrap_err(),
+            OverlayError::InvalidWord("n0pe".into())
+        );
+        let many = vec!["word"; MAX_OVERLAY_WORDS + 1];
+        assert_eq!(
+            Overlay::new(&Alphabet::english(), Vec::<&str>::new(), many).unwrap_err(),
+            OverlayError::TooManyWords(MAX_OVERLAY_WORDS)
+        );
+    }
+}
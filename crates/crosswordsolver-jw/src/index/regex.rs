@@ -0,0 +1,328 @@
+//! Regular-expression search over the length buckets.
+//!
+//! Patterns run against the letters of each entry (no spaces or hyphens)
+//! with fancy-regex, so backreferences such as `(.)\1` work. Where
+//! regex-syntax can read the pattern, its length bounds pick the buckets,
+//! and an anchored literal prefix or suffix and the letters every match
+//! needs are applied with the positional and containment bitsets. Only the
+//! words left after that are run through the regex.
+
+use std::time::{Duration, Instant};
+
+use bitvec::prelude::*;
+use fancy_regex::{Regex, RegexBuilder};
+use regex_syntax::hir::literal::{ExtractKind, Extractor};
+use regex_syntax::hir::{Hir, HirKind, Look};
+use thiserror::Error;
+
+use super::{
+    Alphabet, BitSet, LenIndex, LetterSet, MAX_WORD_LEN, QueryResult, SortOrder, WordIndex,
+    page_buckets,
+};
+
+/// Longest pattern accepted, in characters.
+pub const MAX_REGEX_LEN: usize = 200;
+/// Size allowed for the compiled automata, in bytes.
+const REGEX_SIZE_LIMIT: usize = 1 << 20;
+/// Backtracking steps allowed per word, for patterns with backreferences
+/// or look-around.
+const BACKTRACK_LIMIT: usize = 100_000;
+/// Words scanned between deadline checks. Patterns that need backtracking
+/// check every word, since one word can take `BACKTRACK_LIMIT` steps.
+const DEADLINE_STRIDE: usize = 1024;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum RegexError {
+    #[error("regex must be at most {0} characters")]
+    TooLong(usize),
+    #[error("invalid regex: {0}")]
+    Invalid(String),
+    #[error("regex search took too long; narrow the pattern or the lengths")]
+    TooSlow,
+}
+
+/// A compiled pattern with the hints used to narrow candidates.
+#[derive(Debug)]
+pub struct WordRegex {
+    regex: Regex,
+    min_len: usize,
+    max_len: usize,
+    /// Letters every matching word starts with.
+    prefix: Vec<u8>,
+    /// Letters every matching word ends with.
+    suffix: Vec<u8>,
+    /// Letters every matching word contains.
+    required: LetterSet,
+    /// Words scanned between deadline checks.
+    stride: usize,
+}
+
+#[derive(Debug)]
+pub struct RegexParams<'a> {
+    pub regex: &'a WordRegex,
+    pub min_len: usize,
+    pub max_len: usize,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+    pub time_limit: Duration,
+}
+
+impl WordRegex {
+    /// Compile `pattern` for a list spelled with `alphabet`. Hints are only
+    /// taken for a–z alphabets, where pattern bytes and stored cells agree.
+    pub fn new(pattern: &str, alphabet: &Alphabet) -> Result<Self, RegexError> {
+        if pattern.chars().count() > MAX_REGEX_LEN {
+            return Err(RegexError::TooLong(MAX_REGEX_LEN));
+        }
+        let regex = RegexBuilder::new(pattern)
+            .backtrack_limit(BACKTRACK_LIMIT)
+            .delegate_size_limit(REGEX_SIZE_LIMIT)
+            .delegate_dfa_size_limit(REGEX_SIZE_LIMIT)
+            .build()
+            .map_err(|e| RegexError::Invalid(e.to_string()))?;
+        let mut word_regex = Self {
+            regex,
+            min_len: 1,
+            max_len: MAX_WORD_LEN,
+            prefix: Vec::new(),
+            suffix: Vec::new(),
+            required: LetterSet::default(),
+            stride: DEADLINE_STRIDE,
+        };
+        // regex-syntax has no backreferences or look-around; those patterns
+        // backtrack and are scanned without hints.
+        match regex_syntax::Parser::new().parse(pattern) {
+            Ok(hir) if alphabet.is_plain() => word_regex.add_hints(&hir),
+            Ok(_) => {}
+            Err(_) => word_regex.stride = 1,
+        }
+        Ok(word_regex)
+    }
+
+    fn add_hints(&mut self, hir: &Hir) {
+        let props = hir.properties();
+        // No minimum means nothing can match.
+        self.min_len = props.minimum_len().unwrap_or(usize::MAX).max(1);
+        let starts = props.look_set_prefix().contains(Look::Start);
+        let ends = props.look_set_suffix().contains(Look::End);
+        if starts && ends {
+            self.max_len = props.maximum_len().unwrap_or(MAX_WORD_LEN);
+        }
+        if starts {
+            let seq = Extractor::new().kind(ExtractKind::Prefix).extract(hir);
+            self.prefix = letters(seq.longest_common_prefix().unwrap_or_default(), false);
+        }
+        if ends {
+            let seq = Extractor::new().kind(ExtractKind::Suffix).extract(hir);
+            self.suffix = letters(seq.longest_common_suffix().unwrap_or_default(), true);
+        }
+        self.required = required_letters(hir);
+    }
+
+    fn is_match(&self, text: &str) -> Result<bool, RegexError> {
+        self.regex.is_match(text).map_err(|_| RegexError::TooSlow)
+    }
+}
+
+/// The lowercase letters at the start (or end) of `literal`, up to the first
+/// other byte.
+fn letters(literal: &[u8], from_end: bool) -> Vec<u8> {
+    let lowercase = |b: &&u8| b.is_ascii_lowercase();
+    if from_end {
+        let mut tail: Vec<u8> = literal
+            .iter()
+            .rev()
+            .take_while(lowercase)
+            .copied()
+            .collect();
+        tail.reverse();
+        tail
+    } else {
+        literal.iter().take_while(lowercase).copied().collect()
+    }
+}
+
+/// Letters that appear in every string `hir` matches.
+fn required_letters(hir: &Hir) -> LetterSet {
+    match hir.kind() {
+        HirKind::Literal(literal) => {
+            let mut set = LetterSet::default();
+            for letter in literal.0.iter().filter(|b| b.is_ascii_lowercase()) {
+                set.insert(*letter);
+            }
+            set
+        }
+        HirKind::Capture(capture) => required_letters(&capture.sub),
+        HirKind::Repetition(repetition) if repetition.min > 0 => required_letters(&repetition.sub),
+        HirKind::Concat(subs) => subs.iter().fold(LetterSet::default(), |set, sub| {
+            set.union(required_letters(sub))
+        }),
+        HirKind::Alternation(subs) => subs
+            .iter()
+            .map(required_letters)
+            .reduce(LetterSet::intersection)
+            .unwrap_or_default(),
+        _ => LetterSet::default(),
+    }
+}
+
+impl WordIndex {
+    /// Words the regex matches somewhere in their letters; anchor with `^`
+    /// and `$` to match whole words. Fails once `time_limit` is spent.
+    pub fn query_regex(&self, params: RegexParams<'_>) -> Result<QueryResult, RegexError> {
+        let regex = params.regex;
+        let deadline = Instant::now() + params.time_limit;
+        let min_len = params.min_len.max(regex.min_len).max(1);
+        let max_len = params.max_len.min(regex.max_len).min(MAX_WORD_LEN);
+
+        let mut hits = Vec::new();
+        for len in min_len..=max_len {
+            let Some(len_index) = self.lens.get(len).and_then(Option::as_ref) else {
+                continue;
+            };
+            let mut candidates = len_index.regex_candidates(regex);
+            if let Some(min_score) = params.min_score {
+                candidates &= &len_index.score_mask(min_score);
+            }
+            let survivors: Vec<usize> = candidates.iter_ones().collect();
+            for (scanned, idx) in survivors.into_iter().enumerate() {
+                if scanned % regex.stride == 0 && Instant::now() >= deadline {
+                    return Err(RegexError::TooSlow);
+                }
+                let word = len_index.word(idx);
+                let matched = if self.alphabet.is_plain() {
+                    regex.is_match(word)?
+                } else {
+                    regex.is_match(&self.alphabet.render(word))?
+                };
+                if !matched {
+                    candidates.set(idx, false);
+                }
+            }
+            if candidates.any() {
+                hits.push((len_index, candidates));
+            }
+        }
+
+        let buckets: Vec<(&LenIndex, &BitSet)> = hits.iter().map(|(l, c)| (*l, c)).collect();
+        Ok(page_buckets(
+            &buckets,
+            params.sort,
+            params.page,
+            params.page_size,
+        ))
+    }
+}
+
+impl LenIndex {
+    /// Words that agree with the regex's fixed prefix, suffix and required
+    /// letters.
+    fn regex_candidates(&self, regex: &WordRegex) -> BitSet {
+        let len = self.pos_letter.len();
+        if regex.prefix.len() > len || regex.suffix.len() > len {
+            return bitvec![usize, Lsb0; 0; self.count()];
+        }
+        let suffix_start = len - regex.suffix.len();
+        let fixed = regex.prefix.iter().enumerate().chain(
+            regex
+                .suffix
+                .iter()
+                .enumerate()
+                .map(|(i, l)| (suffix_start + i, l)),
+        );
+
+        let mut candidates = self.all.to_bitvec();
+        for (pos, letter) in fixed {
+            candidates &= &*self.pos_letter[pos][(letter - b'a') as usize];
+        }
+        for letter in regex.required.iter() {
+            candidates &= &*self.contains[(letter - b'a') as usize];
+        }
+        candidates
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::tests::make_index;
+
+    fn search(index: &WordIndex, pattern: &str) -> Result<Vec<String>, RegexError> {
+        let regex = WordRegex::new(pattern, index.alphabet())?;
+        let result = index.query_regex(RegexParams {
+            regex: &regex,
+            min_len: 1,
+            max_len: MAX_WORD_LEN,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+            time_limit: Duration::from_secs(5),
+        })?;
+        Ok(result.items.into_iter().map(|m| m.word).collect())
+    }
+
+    #[test]
+    fn extracts_hints_from_literals() {
+        let english = Alphabet::english();
+        let regex = WordRegex::new("^re.*(ing|ed)$", &english).unwrap();
+        assert_eq!(regex.prefix, b"re");
+        assert!(regex.suffix.is_empty());
+        assert_eq!(regex.min_len, 4);
+        assert_eq!(regex.max_len, MAX_WORD_LEN);
+        assert_eq!(regex.required.iter().collect::<Vec<_>>(), b"er");
+
+        let regex = WordRegex::new("^c(a|o)t$", &english).unwrap();
+        assert_eq!((regex.min_len, regex.max_len), (3, 3));
+        assert_eq!(
+            (regex.prefix.as_slice(), regex.suffix.as_slice()),
+            (&b"c"[..], &b"t"[..])
+        );
+
+        // Backreferences are left to the scan.
+        let regex = WordRegex::new(r"(.)\1", &english).unwrap();
+        assert_eq!((regex.min_len, regex.max_len), (1, MAX_WORD_LEN));
+    }
+
+    #[test]
+    fn searches_with_backreferences_and_anchors() {
+        let index = make_index(&["redding", "reed", "ring", "tested", "hot dog", "apple"]);
+        assert_eq!(
+            search(&index, "^re.*(ing|ed)$").unwrap(),
+            ["redding", "reed"]
+        );
+        assert_eq!(
+            search(&index, r"(.)\1").unwrap(),
+            ["apple", "redding", "reed"]
+        );
+        assert_eq!(search(&index, "^hotdog$").unwrap(), ["hotdog"]);
+        assert_eq!(search(&index, "ng$").unwrap(), ["redding", "ring"]);
+        assert!(search(&index, "^ABC").unwrap().is_empty());
+
+        // Backtracking patterns check the deadline after every word; the
+        // rest run on the linear-time engine.
+        let english = Alphabet::english();
+        assert_eq!(WordRegex::new(r"(.)\1", &english).unwrap().stride, 1);
+        let regex = WordRegex::new("(a|aa)*b", &english).unwrap();
+        assert_eq!(regex.stride, DEADLINE_STRIDE);
+    }
+
+    #[test]
+    fn rejects_long_or_invalid_patterns() {
+        let english = Alphabet::english();
+        assert_eq!(
+            WordRegex::new(&"a".repeat(MAX_REGEX_LEN + 1), &english).unwrap_err(),
+            RegexError::TooLong(MAX_REGEX_LEN)
+        );
+        assert!(matches!(
+            WordRegex::new("(ab", &english),
+            Err(RegexError::Invalid(_))
+        ));
+        assert!(matches!(
+            WordRegex::new(r"\w{100}\w{100}", &english),
+            Err(RegexError::Invalid(_))
+        ));
+    }
+}
@@ -0,0 +1,522 @@
+//! Versioned on-disk snapshot of a [`WordIndex`].
+//!
+//! Building the index normalizes every word-list line and fills one
+//! positional bitset per cell and position in each length bucket. A snapshot stores the finished
+//! columns and bitsets so startup can map the file and use them in place;
+//! only the sparse phrase spellings and the scores are copied out.
+//!
+//! Layout, in the byte order of the machine that wrote it:
+//!
+//! ```text
+//! header   magic "CWSWIDX\0", version u32, endian marker u32, usize width u32,
+//!          reserved u32, source checksum u64, payload checksum u64,
+//!          payload length u64
+//! payload  bucket count u32, reserved u32, the alphabet name as a section,
+//!          then per bucket: word length u32, word count u32 and its
+//!          sections; each section is a u64 byte length followed by the
+//!          bytes, starting on an 8-byte boundary
+//! ```
+//!
+//! The source checksum covers the word list the snapshot was built from, so
+//! editing the list makes the snapshot stale.
+
+use std::fs::{self, File};
+use std::io;
+use std::mem::{align_of, size_of, size_of_val};
+use std::ops::Deref;
+use std::path::Path;
+use std::sync::Arc;
+
+use bitvec::prelude::*;
+use memmap2::Mmap;
+use thiserror::Error;
+use xxhash_rust::xxh3::xxh3_64;
+
+use super::{ALPHABET, Alphabet, BitSet, LenIndex, MAX_WORD_LEN, WordIndex};
+
+/// Bumped whenever the layout changes; snapshots with another version are
+/// rejected so the caller rebuilds.
+pub const SNAPSHOT_VERSION: u32 = 2;
+const MAGIC: &[u8; 8] = b"CWSWIDX\0";
+const ENDIAN_MARKER: u32 = 0x0102_0304;
+const HEADER_LEN: usize = 48;
+const NO_SCORE: u16 = u16::MAX;
+
+#[derive(Debug, Error)]
+pub enum SnapshotError {
+    #[error("snapshot i/o failed: {0}")]
+    Io(#[from] io::Error),
+    #[error("not an index snapshot")]
+    BadMagic,
+    #[error("snapshot version {found} does not match {expected}")]
+    Version { found: u32, expected: u32 },
+    #[error("snapshot was written with a different word size or byte order")]
+    Platform,
+    #[error("snapshot is stale: the word list changed since it was built")]
+    Stale,
+    #[error("snapshot was built for alphabet {0:?}")]
+    Alphabet(String),
+    #[error("snapshot is corrupt: {0}")]
+    Corrupt(&'static str),
+}
+
+/// Element types that can be viewed straight from mapped bytes.
+///
+/// # Safety
+/// Implementors must have no padding and be valid for every bit pattern.
+pub(super) unsafe trait Pod: Copy {}
+
+unsafe impl Pod for u8 {}
+unsafe impl Pod for u16 {}
+unsafe impl Pod for u32 {}
+unsafe impl Pod for usize {}
+unsafe impl Pod for [u8; ALPHABET] {}
+
+/// A read-only array, either owned or borrowed from a snapshot mapping.
+#[derive(Debug, Clone)]
+pub(super) enum Column<T> {
+    Owned(Vec<T>),
+    Mapped {
+        map: Arc<Mmap>,
+        offset: usize,
+        len: usize,
+    },
+}
+
+impl<T: Pod> Deref for Column<T> {
+    type Target = [T];
+
+    fn deref(&self) -> &[T] {
+        match self {
+            Column::Owned(values) => values,
+            Column::Mapped { map, offset, len } => {
+                let bytes = &map[*offset..*offset + len * size_of::<T>()];
+                // SAFETY: `Reader::column` checked the bounds and alignment
+                // when the snapshot was opened, and `T: Pod` accepts any bytes.
+                unsafe { std::slice::from_raw_parts(bytes.as_ptr().cast::<T>(), *len) }
+            }
+        }
+    }
+}
+
+impl<T> From<Vec<T>> for Column<T> {
+    fn from(values: Vec<T>) -> Self {
+        Column::Owned(values)
+    }
+}
+
+/// A read-only bitset stored as a [`Column`] of words.
+#[derive(Debug, Clone)]
+pub(super) struct Bits {
+    words: Column<usize>,
+    len: usize,
+}
+
+impl Deref for Bits {
+    type Target = BitSlice<usize, Lsb0>;
+
+    fn deref(&self) -> &Self::Target {
+        &self.words.view_bits::<Lsb0>()[..self.len]
+    }
+}
+
+impl From<BitSet> for Bits {
+    fn from(bits: BitSet) -> Self {
+        let len = bits.len();
+        Self {
+            words: Column::Owned(bits.into_vec()),
+            len,
+        }
+    }
+}
+
+impl WordIndex {
+    /// Write the index to `path` as a snapshot of the word list at
+    /// `wordlist`. The file is written next to `path` and renamed into
+    /// place, so a running server never maps a half-written snapshot.
+    pub fn write_snapshot(
+        &self,
+        path: impl AsRef<Path>,
+        wordlist: impl AsRef<Path>,
+    ) -> Result<(), SnapshotError> {
+        let path = path.as_ref();
+        let source = list_checksum(wordlist.as_ref())?;
+
+        let buckets: Vec<(usize, &LenIndex)> = self
+            .lens
+            .iter()
+            .enumerate()
+            .filter_map(|(len, bucket)| Some((len, bucket.as_ref()?)))
+            .collect();
+        let mut payload = Writer::default();
+        payload.u32(buckets.len() as u32);
+        payload.u32(0);
+        payload.column(self.alphabet.name().as_bytes());
+        for (len, bucket) in buckets {
+            payload.u32(len as u32);
+            payload.u32(bucket.count() as u32);
+            payload.column(&bucket.words);
+            payload.column(&bucket.breaks);
+            payload.column(&bucket.by_score);
+            payload.column(&bucket.letter_counts);
+            let scores: Vec<u16> = bucket
+                .scores
+                .iter()
+                .map(|score| score.map_or(NO_SCORE, u16::from))
+                .collect();
+            payload.column(&scores);
+            let mut display = Vec::new();
+            for spelling in &bucket.display {
+                let bytes = spelling.as_deref().unwrap_or_default().as_bytes();
+                display.extend_from_slice(&(bytes.len() as u16).to_ne_bytes());
+                display.extend_from_slice(bytes);
+            }
+            payload.column(&display);
+            payload.column(&bucket.phrase.words);
+            payload.column(&bucket.all.words);
+            for bits in bucket.pos_letter.iter().flatten() {
+                payload.column(&bits.words);
+            }
+            for bits in &bucket.contains {
+                payload.column(&bits.words);
+            }
+        }
+
+        let payload = payload.buf;
+        let mut out = Vec::with_capacity(HEADER_LEN + payload.len());
+        out.extend_from_slice(MAGIC);
+        out.extend_from_slice(&SNAPSHOT_VERSION.to_ne_bytes());
+        out.extend_from_slice(&ENDIAN_MARKER.to_ne_bytes());
+        out.extend_from_slice(&(size_of::<usize>() as u32).to_ne_bytes());
+        out.extend_from_slice(&0u32.to_ne_bytes());
+        out.extend_from_slice(&source.to_ne_bytes());
+        out.extend_from_slice(&xxh3_64(&payload).to_ne_bytes());
+        out.extend_from_slice(&(payload.len() as u64).to_ne_bytes());
+        out.extend_from_slice(&payload);
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, &out)?;
+        fs::rename(&tmp, path)?;
+        Ok(())
+    }
+
+    /// Map a snapshot written by [`WordIndex::write_snapshot`]. Fails with
+    /// [`SnapshotError::Stale`] when `wordlist` no longer matches the list
+    /// the snapshot was built from, and with [`SnapshotError::Alphabet`] when
+    /// it was built with another alphabet; callers then fall back to
+    /// [`WordIndex::build_from_file_with_alphabet`].
+    pub fn load_snapshot(
+        path: impl AsRef<Path>,
+        wordlist: impl AsRef<Path>,
+        alphabet: &Arc<Alphabet>,
+    ) -> Result<Arc<Self>, SnapshotError> {
+        let file = File::open(path.as_ref())?;
+        // SAFETY: snapshots are replaced by rename, never rewritten in place.
+        let map = Arc::new(unsafe { Mmap::map(&file)? });
+        check_header(&map, list_checksum(wordlist.as_ref())?)?;
+
+        let mut reader = Reader {
+            map: &map,
+            pos: HEADER_LEN,
+        };
+        let count = reader.u32()?;
+        reader.u32()?;
+        let built_with = reader.section()?;
+        if built_with != alphabet.name().as_bytes() {
+            return Err(SnapshotError::Alphabet(
+                String::from_utf8_lossy(built_with).into_owned(),
+            ));
+        }
+        let cells = alphabet.len();
+        let mut lens = vec![None; MAX_WORD_LEN + 1];
+        for _ in 0..count {
+            let len = reader.u32()? as usize;
+            let n = reader.u32()? as usize;
+            if !(1..=MAX_WORD_LEN).contains(&len) || n == 0 || lens[len].is_some() {
+                return Err(SnapshotError::Corrupt("bad bucket header"));
+            }
+            let words = reader.column(n * len)?;
+            let breaks = reader.column(n)?;
+            let by_score = reader.column(n)?;
+            let letter_counts = reader.column(n)?;
+            let scores = reader
+                .column::<u16>(n)?
+                .iter()
+                .map(|score| u8::try_from(*score).ok())
+                .collect();
+            let display = decode_display(reader.section()?, n)?;
+            let phrase = reader.bits(n)?;
+            let all = reader.bits(n)?;
+            let pos_letter = (0..len)
+                .map(|_| (0..cells).map(|_| reader.bits(n)).collect())
+                .collect::<Result<Vec<_>, _>>()?;
+            let contains = (0..cells)
+                .map(|_| reader.bits(n))
+                .collect::<Result<_, _>>()?;
+            lens[len] = Some(LenIndex {
+                alphabet: Arc::clone(alphabet),
+                words,
+                display,
+                breaks,
+                phrase,
+                scores,
+                by_score,
+                all,
+                pos_letter,
+                contains,
+                letter_counts,
+            });
+        }
+        Ok(Arc::new(Self {
+            lens,
+            alphabet: Arc::clone(alphabet),
+        }))
+    }
+}
+
+/// Checksum of a word list's bytes; snapshots record it to detect edits.
+pub fn list_checksum(wordlist: &Path) -> io::Result<u64> {
+    Ok(xxh3_64(&fs::read(wordlist)?))
+}
+
+fn check_header(map: &[u8], source: u64) -> Result<(), SnapshotError> {
+    if map.len() < HEADER_LEN || &map[..MAGIC.len()] != MAGIC {
+        return Err(SnapshotError::BadMagic);
+    }
+    let word = |at: usize| u32::from_ne_bytes(map[at..at + 4].try_into().expect("4 bytes"));
+    let wide = |at: usize| u64::from_ne_bytes(map[at..at + 8].try_into().expect("8 bytes"));
+    if word(12) != ENDIAN_MARKER || word(16) != size_of::<usize>() as u32 {
+        return Err(SnapshotError::Platform);
+    }
+    if word(8) != SNAPSHOT_VERSION {
+        return Err(SnapshotError::Version {
+            found: word(8),
+            expected: SNAPSHOT_VERSION,
+        });
+    }
+    if wide(24) != source {
+        return Err(SnapshotError::Stale);
+    }
+    let payload = &map[HEADER_LEN..];
+    if wide(40) != payload.len() as u64 {
+        return Err(SnapshotError::Corrupt("payload length mismatch"));
+    }
+    if xxh3_64(payload) != wide(32) {
+        return Err(SnapshotError::Corrupt("payload checksum mismatch"));
+    }
+    Ok(())
+}
+
+fn decode_display(mut bytes: &[u8], n: usize) -> Result<Vec<Option<String>>, SnapshotError> {
+    let mut display = Vec::with_capacity(n);
+    for _ in 0..n {
+        let (len, rest) = bytes
+            .split_first_chunk::<2>()
+            .ok_or(SnapshotError::Corrupt("truncated display section"))?;
+        let len = u16::from_ne_bytes(*len) as usize;
+        let (text, rest) = rest
+            .split_at_checked(len)
+            .ok_or(SnapshotError::Corrupt("truncated display section"))?;
+        let text = std::str::from_utf8(text)
+            .map_err(|_| SnapshotError::Corrupt("display text is not utf-8"))?;
+        display.push((len > 0).then(|| text.to_string()));
+        bytes = rest;
+    }
+    Ok(display)
+}
+
+#[derive(Default)]
+struct Writer {
+    buf: Vec<u8>,
+}
+
+impl Writer {
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_ne_bytes());
+    }
+
+    fn column<T: Pod>(&mut self, values: &[T]) {
+        self.buf.resize(self.buf.len().next_multiple_of(8), 0);
+        // SAFETY: `T: Pod` has no padding, so every byte is initialized.
+        let bytes = unsafe {
+            std::slice::from_raw_parts(values.as_ptr().cast::<u8>(), size_of_val(values))
+        };
+        self.buf
+            .extend_from_slice(&(bytes.len() as u64).to_ne_bytes());
+        self.buf.extend_from_slice(bytes);
+    }
+}
+
+struct Reader<'a> {
+    map: &'a Arc<Mmap>,
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], SnapshotError> {
+        let map: &'a [u8] = self.map;
+        let bytes = self
+            .pos
+            .checked_add(len)
+            .and_then(|end| map.get(self.pos..end))
+            .ok_or(SnapshotError::Corrupt("truncated payload"))?;
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    fn u32(&mut self) -> Result<u32, SnapshotError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_ne_bytes(bytes.try_into().expect("4 bytes")))
+    }
+
+    /// The next section's bytes, whatever its length.
+    fn section(&mut self) -> Result<&'a [u8], SnapshotError> {
+        self.pos = self.pos.next_multiple_of(8);
+        let len = self.take(8)?;
+        let len = u64::from_ne_bytes(len.try_into().expect("8 bytes"));
+        let len = usize::try_from(len).map_err(|_| SnapshotError::Corrupt("section too long"))?;
+        self.take(len)
+    }
+
+    /// The next section viewed in place as `len` values of `T`.
+    fn column<T: Pod>(&mut self, len: usize) -> Result<Column<T>, SnapshotError> {
+        let bytes = self.section()?;
+        if bytes.len() != len * size_of::<T>() {
+            return Err(SnapshotError::Corrupt("section length mismatch"));
+        }
+        if !(bytes.as_ptr() as usize).is_multiple_of(align_of::<T>()) {
+            return Err(SnapshotError::Corrupt("misaligned section"));
+        }
+        Ok(Column::Mapped {
+            map: Arc::clone(self.map),
+            offset: bytes.as_ptr() as usize - self.map.as_ptr() as usize,
+            len,
+        })
+    }
+
+    fn bits(&mut self, len: usize) -> Result<Bits, SnapshotError> {
+        Ok(Bits {
+            words: self.column(len.div_ceil(usize::BITS as usize))?,
+            len,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::io::Write;
+
+    use tempfile::{NamedTempFile, TempDir};
+
+    use super::*;
+    use crate::index::{AnagramParams, QueryParams, SortOrder, parse_letter_bag, parse_pattern};
+
+    const WORDS: &[&str] = &[
+        "apple;50",
+        "angle;40",
+        "hot dog;60",
+        "hotdog",
+        "ankle",
+        "cat;10",
+    ];
+
+    fn wordlist(lines: &[&str]) -> NamedTempFile {
+        let mut file = NamedTempFile::new().expect("temp file");
+        for line in lines {
+            writeln!(file, "{line}").unwrap();
+        }
+        file
+    }
+
+    fn matches(index: &WordIndex, raw: &str) -> Vec<(String, String, Option<u8>)> {
+        let pattern = parse_pattern(raw).unwrap();
+        index
+            .query(QueryParams {
+                pattern: &pattern,
+                must_include: &[],
+                cannot_include: &[],
+                enumeration: None,
+                min_score: None,
+                sort: SortOrder::Score,
+                page: 1,
+                page_size: 10,
+                overlay: None,
+            })
+            .items
+            .into_iter()
+            .map(|m| (m.word, m.display, m.score))
+            .collect()
+    }
+
+    #[test]
+    fn round_trips_through_a_mapped_snapshot() {
+        let list = wordlist(WORDS);
+        let built = WordIndex::build_from_file(list.path()).unwrap();
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("words.snap");
+        built.write_snapshot(&path, list.path()).unwrap();
+
+        let loaded = WordIndex::load_snapshot(&path, list.path(), &Alphabet::english()).unwrap();
+        let bucket = loaded.lens[5].as_ref().unwrap();
+        assert!(matches!(bucket.words, Column::Mapped { .. }));
+        for pattern in ["a___e", "h_____", "___"] {
+            assert_eq!(matches(&loaded, pattern), matches(&built, pattern));
+        }
+        assert_eq!(matches(&loaded, "h_____")[0].1, "hot dog");
+
+        let anagram = |index: &WordIndex| {
+            let pattern = parse_pattern("_____").unwrap();
+            index
+                .query_anagram(AnagramParams {
+                    pattern: &pattern,
+                    bag_counts: parse_letter_bag("glean", 5).unwrap(),
+                    blanks: 0,
+                    min_score: None,
+                    sort: SortOrder::Alpha,
+                    page: 1,
+                    page_size: 10,
+                })
+                .items
+                .len()
+        };
+        assert_eq!(anagram(&loaded), 1);
+    }
+
+    #[test]
+    fn rejects_stale_and_damaged_snapshots() {
+        let list = wordlist(WORDS);
+        let dir = TempDir::new().unwrap();
+        let path = dir.path().join("words.snap");
+        WordIndex::build_from_file(list.path())
+            .unwrap()
+            .write_snapshot(&path, list.path())
+            .unwrap();
+
+        let edited = wordlist(&["apple;50"]);
+        assert!(matches!(
+            WordIndex::load_snapshot(&path, edited.path(), &Alphabet::english()),
+            Err(SnapshotError::Stale)
+        ));
+        let french = Arc::new(Alphabet::parse("fr").unwrap());
+        assert!(matches!(
+            WordIndex::load_snapshot(&path, list.path(), &french),
+            Err(SnapshotError::Alphabet(name)) if name == "en"
+        ));
+
+        let mut bytes = fs::read(&path).unwrap();
+        let last = bytes.len() - 1;
+        bytes[last] ^= 0xff;
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            WordIndex::load_snapshot(&path, list.path(), &Alphabet::english()),
+            Err(SnapshotError::Corrupt(_))
+        ));
+
+        bytes[8..12].copy_from_slice(&(SNAPSHOT_VERSION + 1).to_ne_bytes());
+        fs::write(&path, &bytes).unwrap();
+        assert!(matches!(
+            WordIndex::load_snapshot(&path, list.path(), &Alphabet::english()),
+            Err(SnapshotError::Version { .. })
+        ));
+    }
+}
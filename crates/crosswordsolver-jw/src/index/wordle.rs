@@ -0,0 +1,447 @@
+//! Wordle-style solving: narrow a length bucket with the feedback from
+//! earlier guesses and rank next guesses by expected information.
+//!
+//! Green letters pin positions with the positional bitsets, and yellow and
+//! grey letters rule positions out. Each guess also bounds how often a letter
+//! occurs: its green and yellow marks are a lower bound, and a grey mark on
+//! the same letter makes that bound exact. Bounds of zero or one are applied
+//! with the `contains` bitsets; higher ones are checked against each word's
+//! letter counts.
+
+use std::cmp::Ordering;
+use std::collections::HashMap;
+
+use thiserror::Error;
+
+use super::{
+    ALPHABET, Alphabet, BitSet, LenIndex, LetterSet, MAX_WORD_LEN, QueryResult, SortOrder,
+    WordIndex, WordMatch,
+};
+
+/// Candidates the next-guess ranking is scored against; beyond this the
+/// best-scoring ones stand in for the rest.
+const MAX_ANSWER_SAMPLE: usize = 1000;
+/// Remaining candidates tried as next guesses.
+const MAX_CANDIDATE_GUESSES: usize = 300;
+/// Other words of the bucket tried as probes that cannot be the answer but
+/// may split the candidates better.
+const MAX_PROBE_GUESSES: usize = 200;
+
+/// Feedback for one letter of a guess.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Mark {
+    /// Right letter, right place.
+    Green,
+    /// In the word, elsewhere.
+    Yellow,
+    /// Not in the word, or not as many times as guessed.
+    Grey,
+}
+
+/// A guess as cell codes together with its feedback.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Guess {
+    pub letters: Vec<u8>,
+    pub marks: Vec<Mark>,
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum WordleError {
+    #[error("expected guess:feedback, got {0:?}")]
+    Malformed(String),
+    #[error("invalid guess {0:?}")]
+    InvalidGuess(String),
+    #[error("feedback {0:?} must use G, Y and B, one per letter")]
+    InvalidFeedback(String),
+    #[error("guesses must all have {expected} letters, got {found}")]
+    LengthMismatch { expected: usize, found: usize },
+    #[error("word length must be between 1 and {0}")]
+    InvalidLength(usize),
+}
+
+/// What the feedback so far says about the answer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct WordleConstraints {
+    len: usize,
+    /// Positions whose letter is known.
+    greens: Vec<(usize, u8)>,
+    /// Letters ruled out at each position.
+    excluded: Vec<LetterSet>,
+    min_count: [u8; ALPHABET],
+    max_count: [u8; ALPHABET],
+}
+
+#[derive(Debug)]
+pub struct WordleParams<'a> {
+    pub constraints: &'a WordleConstraints,
+    pub min_score: Option<u8>,
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+    /// Next guesses to suggest.
+    pub suggestions: usize,
+}
+
+#[derive(Debug, Clone)]
+pub struct WordleResult {
+    pub candidates: QueryResult,
+    pub suggestions: Vec<GuessSuggestion>,
+}
+
+/// A ranked next guess.
+#[derive(Debug, Clone)]
+pub struct GuessSuggestion {
+    pub word: WordMatch,
+    /// Expected bits of information from its feedback.
+    pub expected_info: f64,
+    /// Expected number of candidates left after it.
+    pub expected_remaining: f64,
+    /// Whether the guess could itself be the answer.
+    pub candidate: bool,
+}
+
+impl Alphabet {
+    /// Parse a guess history such as `crane:BYBBG,slate:BBGBB`. Feedback is
+    /// case-insensitive.
+    pub fn parse_guesses(&self, raw: &str) -> Result<Vec<Guess>, WordleError> {
+        raw.split(',')
+            .map(str::trim)
+            .filter(|part| !part.is_empty())
+            .map(|part| {
+                let (word, feedback) = part
+                    .split_once(':')
+                    .ok_or_else(|| WordleError::Malformed(part.to_string()))?;
+                let letters = self
+                    .cells_of(word.trim())
+                    .filter(|cells| !cells.is_empty() && cells.len() <= MAX_WORD_LEN)
+                    .ok_or_else(|| WordleError::InvalidGuess(word.to_string()))?;
+                let marks = feedback
+                    .trim()
+                    .chars()
+                    .map(|c| match c.to_ascii_uppercase() {
+                        'G' => Some(Mark::Green),
+                        'Y' => Some(Mark::Yellow),
+                        'B' => Some(Mark::Grey),
+                        _ => None,
+                    })
+                    .collect::<Option<Vec<_>>>()
+                    .filter(|marks| marks.len() == letters.len())
+                    .ok_or_else(|| WordleError::InvalidFeedback(feedback.to_string()))?;
+                Ok(Guess { letters, marks })
+            })
+            .collect()
+    }
+}
+
+impl WordleConstraints {
+    /// Combine the feedback of every guess for answers of `len` letters.
+    pub fn new(len: usize, guesses: &[Guess]) -> Result<Self, WordleError> {
+        if len == 0 || len > MAX_WORD_LEN {
+            return Err(WordleError::InvalidLength(MAX_WORD_LEN));
+        }
+        let mut constraints = Self {
+            len,
+            greens: Vec::new(),
+            excluded: vec![LetterSet::default(); len],
+            min_count: [0; ALPHABET],
+            max_count: [u8::MAX; ALPHABET],
+        };
+        for guess in guesses {
+            if guess.letters.len() != len {
+                return Err(WordleError::LengthMismatch {
+                    expected: len,
+                    found: guess.letters.len(),
+                });
+            }
+            let mut marked = [0u8; ALPHABET];
+            let mut greyed = LetterSet::default();
+            for (pos, (&letter, &mark)) in guess.letters.iter().zip(&guess.marks).enumerate() {
+                let cell = (letter - b'a') as usize;
+                match mark {
+                    Mark::Green => {
+                        constraints.greens.push((pos, letter));
+                        marked[cell] += 1;
+                    }
+                    Mark::Yellow => {
+                        constraints.excluded[pos].insert(letter);
+                        marked[cell] += 1;
+                    }
+                    Mark::Grey => {
+                        constraints.excluded[pos].insert(letter);
+                        greyed.insert(letter);
+                    }
+                }
+            }
+            for (min, count) in constraints.min_count.iter_mut().zip(marked) {
+                *min = (*min).max(count);
+            }
+            for letter in greyed.iter() {
+                let cell = (letter - b'a') as usize;
+                constraints.max_count[cell] = constraints.max_count[cell].min(marked[cell]);
+            }
+        }
+        Ok(constraints)
+    }
+
+    /// Whether the letter counts of a word fall within the bounds.
+    fn counts_fit(&self, counts: &[u8; ALPHABET]) -> bool {
+        counts
+            .iter()
+            .zip(self.min_count.iter().zip(&self.max_count))
+            .all(|(count, (min, max))| count >= min && count <= max)
+    }
+}
+
+/// Feedback for `guess` against `answer` as a base-3 number, one digit per
+/// letter. Repeated letters are marked yellow only as often as the answer has
+/// them spare after the greens.
+fn feedback_code(guess: &[u8], answer: &[u8]) -> u64 {
+    let mut spare = [0u8; ALPHABET];
+    for (g, a) in guess.iter().zip(answer) {
+        if g != a {
+            spare[(a - b'a') as usize] += 1;
+        }
+    }
+    guess.iter().zip(answer).fold(0, |code, (g, a)| {
+        let digit = if g == a {
+            2
+        } else if spare[(g - b'a') as usize] > 0 {
+            spare[(g - b'a') as usize] -= 1;
+            1
+        } else {
+            0
+        };
+        code * 3 + digit
+    })
+}
+
+impl WordIndex {
+    /// Single words that agree with every guess so far, plus the next
+    /// guesses that split them best.
+    pub fn query_wordle(&self, params: WordleParams<'_>) -> WordleResult {
+        let constraints = params.constraints;
+        let Some(len_index) = self.lens.get(constraints.len).and_then(Option::as_ref) else {
+            return WordleResult {
+                candidates: QueryResult {
+                    total: 0,
+                    items: Vec::new(),
+                    has_more: false,
+                },
+                suggestions: Vec::new(),
+            };
+        };
+        let mut candidates = len_index.wordle_candidates(constraints);
+        if let Some(min_score) = params.min_score {
+            candidates &= &len_index.score_mask(min_score);
+        }
+        let suggestions =
+            len_index.suggest_guesses(&candidates, params.min_score, params.suggestions);
+        WordleResult {
+            candidates: len_index.page(&candidates, params.sort, params.page, params.page_size),
+            suggestions,
+        }
+    }
+}
+
+impl LenIndex {
+    fn wordle_candidates(&self, constraints: &WordleConstraints) -> BitSet {
+        let mut candidates = !self.phrase.to_bitvec();
+        for (pos, letter) in &constraints.greens {
+            candidates &= &*self.pos_letter[*pos][(letter - b'a') as usize];
+        }
+        for (pos, excluded) in constraints.excluded.iter().enumerate() {
+            for letter in excluded.iter() {
+                candidates &= &!self.pos_letter[pos][(letter - b'a') as usize].to_bitvec();
+            }
+        }
+        let mut exact_counts = false;
+        for cell in 0..self.contains.len() {
+            let (min, max) = (constraints.min_count[cell], constraints.max_count[cell]);
+            if max == 0 {
+                candidates &= &!self.contains[cell].to_bitvec();
+            } else if min > 0 {
+                candidates &= &*self.contains[cell];
+            }
+            exact_counts |= min > 1 || (max > 0 && max < u8::MAX);
+        }
+
+        if exact_counts {
+            let survivors: Vec<usize> = candidates.iter_ones().collect();
+            for idx in survivors {
+                if !constraints.counts_fit(&self.letter_counts[idx]) {
+                    candidates.set(idx, false);
+                }
+            }
+        }
+        candidates
+    }
+
+    /// Rank guesses by the entropy of the feedback they would get across the
+    /// candidates. Ties go to guesses that could be the answer, then to
+    /// higher-scoring words.
+    fn suggest_guesses(
+        &self,
+        candidates: &BitSet,
+        min_score: Option<u8>,
+        limit: usize,
+    ) -> Vec<GuessSuggestion> {
+        let answers: Vec<usize> = self
+            .ordered(candidates, SortOrder::Score)
+            .take(MAX_ANSWER_SAMPLE)
+            .collect();
+        if answers.is_empty() || limit == 0 {
+            return Vec::new();
+        }
+
+        let mut probes = !self.phrase.to_bitvec();
+        probes &= &!candidates.clone();
+        if let Some(min_score) = min_score {
+            probes &= &self.score_mask(min_score);
+        }
+        let pool = answers
+            .iter()
+            .take(MAX_CANDIDATE_GUESSES)
+            .map(|idx| (*idx, true))
+            .chain(
+                self.ordered(&probes, SortOrder::Score)
+                    .take(MAX_PROBE_GUESSES)
+                    .map(|idx| (idx, false)),
+            );
+
+        let total = answers.len() as f64;
+        let mut ranked: Vec<(usize, bool, f64, f64)> = pool
+            .map(|(guess, candidate)| {
+                let guess_letters = self.word(guess).as_bytes();
+                let mut groups: HashMap<u64, u32> = HashMap::new();
+                for answer in &answers {
+                    let code = feedback_code(guess_letters, self.word(*answer).as_bytes());
+                    *groups.entry(code).or_default() += 1;
+                }
+                let (info, remaining) = groups.values().fold((0.0, 0.0), |(info, rem), n| {
+                    let p = f64::from(*n) / total;
+                    (info - p * p.log2(), rem + p * f64::from(*n))
+                });
+                (guess, candidate, info, remaining)
+            })
+            .collect();
+        ranked.sort_by(|a, b| {
+            b.2.partial_cmp(&a.2)
+                .unwrap_or(Ordering::Equal)
+                .then(b.1.cmp(&a.1))
+                .then_with(|| {
+                    self.sort_key(a.0, SortOrder::Score)
+                        .cmp(&self.sort_key(b.0, SortOrder::Score))
+                })
+        });
+        ranked
+            .into_iter()
+            .take(limit)
+            .map(
+                |(idx, candidate, expected_info, expected_remaining)| GuessSuggestion {
+                    word: self.word_match(idx),
+                    expected_info,
+                    expected_remaining,
+                    candidate,
+                },
+            )
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::tests::make_index;
+
+    fn solve(index: &WordIndex, history: &str) -> WordleResult {
+        let guesses = Alphabet::english().parse_guesses(history).unwrap();
+        let constraints = WordleConstraints::new(5, &guesses).unwrap();
+        index.query_wordle(WordleParams {
+            constraints: &constraints,
+            min_score: None,
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+            suggestions: 3,
+        })
+    }
+
+    fn words(result: &WordleResult) -> Vec<&str> {
+        result
+            .candidates
+            .items
+            .iter()
+            .map(|m| m.word.as_str())
+            .collect()
+    }
+
+    #[test]
+    fn feedback_marks_repeated_letters_once() {
+        let code = |guess: &str, answer: &str| feedback_code(guess.as_bytes(), answer.as_bytes());
+        let digits = |marks: &str| {
+            marks
+                .bytes()
+                .fold(0, |code, m| code * 3 + u64::from(m - b'0'))
+        };
+        // "abide" has one e to spare, so only the first e of "speed" is yellow.
+        assert_eq!(code("speed", "abide"), digits("00101"));
+        assert_eq!(code("crane", "crane"), digits("22222"));
+    }
+
+    #[test]
+    fn narrows_with_greens_yellows_and_counts() {
+        let index = make_index(&[
+            "crane", "react", "trace", "cater", "carte", "eerie", "geese", "hot dog", "caret",
+        ]);
+        // c r a n e against "trace": c y, r g, a g, n b, e g.
+        let result = solve(&index, "crane:YGGBG");
+        assert_eq!(words(&result), ["trace"]);
+
+        // e e r i e against "geese": first e y, second e g, r b, i b, e g.
+        let result = solve(&index, "eerie:YGBBG");
+        assert_eq!(words(&result), ["geese"]);
+
+        // A grey e after a yellow one caps e at one.
+        let result = solve(&index, "geese:BYBBB");
+        assert_eq!(words(&result), ["caret", "cater"]);
+    }
+
+    #[test]
+    fn suggests_guesses_that_split_candidates() {
+        let index = make_index(&["cater", "later", "water", "hater", "lower", "cwhlm"]);
+        let result = solve(&index, "tuber:YBBGG");
+        assert_eq!(words(&result), ["cater", "hater", "later", "water"]);
+        let best = &result.suggestions[0];
+        // Only the probe separates all four candidates.
+        assert_eq!(best.word.word, "cwhlm");
+        assert!(!best.candidate);
+        assert!((best.expected_info - 2.0).abs() < 1e-9);
+        assert!((best.expected_remaining - 1.0).abs() < 1e-9);
+
+        let result = solve(&index, "tuber:YBBGG,cwhlm:BYBBB");
+        assert_eq!(words(&result), ["water"]);
+        assert_eq!(result.suggestions[0].word.word, "water");
+        assert!(result.suggestions[0].candidate);
+    }
+
+    #[test]
+    fn rejects_bad_histories() {
+        let english = Alphabet::english();
+        assert_eq!(
+            english.parse_guesses("crane").unwrap_err(),
+            WordleError::Malformed("crane".into())
+        );
+        assert_eq!(
+            english.parse_guesses("crane:GGX").unwrap_err(),
+            WordleError::InvalidFeedback("GGX".into())
+        );
+        let guesses = english.parse_guesses("crane:bbbbb,cat:bbb").unwrap();
+        assert_eq!(
+            WordleConstraints::new(5, &guesses).unwrap_err(),
+            WordleError::LengthMismatch {
+                expected: 5,
+                found: 3
+            }
+        );
+    }
+}
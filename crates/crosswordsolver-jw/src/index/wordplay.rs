@@ -0,0 +1,323 @@
+//! Charade and container wordplay: answers built from other entries.
+//!
+//! A charade writes words one after another (CAR + PET = CARPET); a
+//! container writes one word inside another (SE in PLEAD = PLEA(SE)D). For
+//! every answer fitting the pattern, each way of cutting it into two or
+//! three pieces, or into an inner run and the outer letters around it, is
+//! looked up in the length buckets. Fragments, such as the conventional
+//! abbreviations of clue words, may stand in for any piece.
+
+use std::time::{Duration, Instant};
+
+use super::{LenIndex, Pattern, SortOrder, WordIndex, WordMatch};
+
+/// Answers checked between deadline checks.
+const DEADLINE_STRIDE: usize = 256;
+
+#[derive(Debug)]
+pub struct WordplayParams<'a> {
+    pub pattern: &'a Pattern,
+    /// Shortest component word.
+    pub min_part_len: usize,
+    /// Applies to the answer and every component.
+    pub min_score: Option<u8>,
+    pub charades: bool,
+    pub containers: bool,
+    /// Extra pieces that need not be entries; exempt from `min_part_len`
+    /// and `min_score`.
+    pub fragments: &'a [Fragment],
+    pub sort: SortOrder,
+    pub page: usize,
+    pub page_size: usize,
+    pub time_limit: Duration,
+}
+
+/// Letters a clue word can stand for, e.g. "ab" for "sailor".
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Fragment {
+    /// The letters as cell codes.
+    pub cells: String,
+    /// The letters as written.
+    pub letters: String,
+    /// What the letters stand for.
+    pub source: String,
+}
+
+/// How an answer is put together.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Wordplay {
+    /// A component used as it is.
+    Word(WordMatch),
+    /// A fragment used as a component.
+    Fragment(Fragment),
+    /// Components written one after another.
+    Charade(Vec<Wordplay>),
+    /// `inner` written into `outer` after its first `at` letters.
+    Container {
+        outer: Box<Wordplay>,
+        inner: Box<Wordplay>,
+        at: usize,
+    },
+}
+
+/// An answer with every construction found for it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Construction {
+    pub answer: WordMatch,
+    pub parses: Vec<Wordplay>,
+}
+
+#[derive(Debug, Clone)]
+pub struct WordplayResult {
+    /// Answers with at least one construction.
+    pub total: usize,
+    pub has_more: bool,
+    pub items: Vec<Construction>,
+    /// Set when the time budget ran out before every answer was checked.
+    pub truncated: bool,
+}
+
+impl WordIndex {
+    /// Answers fitting the pattern that split into two or three entries, or
+    /// into one entry inside another, with the ways they do.
+    pub fn query_wordplay(&self, params: WordplayParams<'_>) -> WordplayResult {
+        let empty = WordplayResult {
+            total: 0,
+            has_more: false,
+            items: Vec::new(),
+            truncated: false,
+        };
+        let Some(len_index) = self.lens.get(params.pattern.len()).and_then(Option::as_ref) else {
+            return empty;
+        };
+        let mut candidates = len_index.pattern_candidates(params.pattern);
+        if let Some(min_score) = params.min_score {
+            candidates &= &len_index.score_mask(min_score);
+        }
+
+        let deadline = Instant::now() + params.time_limit;
+        let offset = params
+            .page
+            .saturating_sub(1)
+            .saturating_mul(params.page_size);
+        let mut result = empty;
+        for (checked, idx) in len_index.ordered(&candidates, params.sort).enumerate() {
+            if checked % DEADLINE_STRIDE == 0 && Instant::now() >= deadline {
+                result.truncated = true;
+                break;
+            }
+            let parses = self.constructions(len_index.word(idx), &params);
+            if parses.is_empty() {
+                continue;
+            }
+            if result.total >= offset && result.items.len() < params.page_size {
+                result.items.push(Construction {
+                    answer: len_index.word_match(idx),
+                    parses,
+                });
+            }
+            result.total += 1;
+        }
+        result.has_more = offset.saturating_add(result.items.len()) < result.total;
+        result
+    }
+
+    fn constructions(&self, answer: &str, params: &WordplayParams<'_>) -> Vec<Wordplay> {
+        let len = answer.len();
+        let min = params.min_part_len.max(1);
+        let piece = |cells: &str| -> Option<Wordplay> {
+            if let Some(fragment) = params.fragments.iter().find(|f| f.cells == cells) {
+                return Some(Wordplay::Fragment(fragment.clone()));
+            }
+            if cells.len() < min {
+                return None;
+            }
+            self.component(cells, params.min_score).map(Wordplay::Word)
+        };
+        let mut parses = Vec::new();
+
+        if params.charades {
+            for i in 1..len {
+                let Some(head) = piece(&answer[..i]) else {
+                    continue;
+                };
+                if let Some(tail) = piece(&answer[i..]) {
+                    parses.push(Wordplay::Charade(vec![head.clone(), tail]));
+                }
+                for j in i + 1..len {
+                    if let (Some(middle), Some(tail)) = (piece(&answer[i..j]), piece(&answer[j..]))
+                    {
+                        parses.push(Wordplay::Charade(vec![head.clone(), middle, tail]));
+                    }
+                }
+            }
+        }
+
+        if params.containers {
+            // The inner piece starts after at least one outer letter and ends
+            // before at least one more.
+            for i in 1..len {
+                for j in i + 1..len {
+                    let Some(inner) = piece(&answer[i..j]) else {
+                        continue;
+                    };
+                    let outer = format!("{}{}", &answer[..i], &answer[j..]);
+                    if let Some(outer) = piece(&outer) {
+                        parses.push(Wordplay::Container {
+                            outer: Box::new(outer),
+                            inner: Box::new(inner),
+                            at: i,
+                        });
+                    }
+                }
+            }
+        }
+        parses
+    }
+
+    /// The entry spelled `cells`, if any scores at least `min_score`.
+    fn component(&self, cells: &str, min_score: Option<u8>) -> Option<WordMatch> {
+        let len_index: &LenIndex = self.lens.get(cells.len())?.as_ref()?;
+        len_index
+            .find(cells)
+            .find(|idx| {
+                min_score.is_none_or(|min| len_index.scores[*idx].is_some_and(|s| s >= min))
+            })
+            .map(|idx| len_index.word_match(idx))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::parse_pattern;
+    use crate::index::tests::make_index;
+
+    fn params(pattern: &Pattern) -> WordplayParams<'_> {
+        WordplayParams {
+            pattern,
+            min_part_len: 2,
+            min_score: None,
+            charades: true,
+            containers: true,
+            fragments: &[],
+            sort: SortOrder::Alpha,
+            page: 1,
+            page_size: 50,
+            time_limit: Duration::from_secs(5),
+        }
+    }
+
+    fn spelled(play: &Wordplay) -> String {
+        match play {
+            Wordplay::Word(word) => word.word.clone(),
+            Wordplay::Fragment(fragment) => fragment.letters.to_uppercase(),
+            Wordplay::Charade(parts) => parts.iter().map(spelled).collect::<Vec<_>>().join("+"),
+            Wordplay::Container { outer, inner, at } => {
+                let outer = spelled(outer);
+                format!("{}({}){}", &outer[..*at], spelled(inner), &outer[*at..])
+            }
+        }
+    }
+
+    fn parses(result: &WordplayResult) -> Vec<(String, Vec<String>)> {
+        result
+            .items
+            .iter()
+            .map(|c| {
+                (
+                    c.answer.word.clone(),
+                    c.parses.iter().map(spelled).collect(),
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn finds_charades_and_containers() {
+        let index = make_index(&[
+            "carpet;50",
+            "car;50",
+            "pet;50",
+            "pleased;50",
+            "plead;50",
+            "se;10",
+            "anthems;50",
+            "ant;50",
+            "he;50",
+            "ms;50",
+            "pastime;50",
+        ]);
+        let pattern = parse_pattern("______").unwrap();
+        let result = index.query_wordplay(params(&pattern));
+        assert_eq!(
+            parses(&result),
+            [("carpet".to_string(), vec!["car+pet".to_string()])]
+        );
+
+        let pattern = parse_pattern("_______").unwrap();
+        let result = index.query_wordplay(params(&pattern));
+        assert_eq!(
+            parses(&result),
+            [
+                ("anthems".to_string(), vec!["ant+he+ms".to_string()]),
+                ("pleased".to_string(), vec!["plea(se)d".to_string()]),
+            ]
+        );
+
+        // Components must pass min_score too.
+        let mut strict = params(&pattern);
+        strict.min_score = Some(20);
+        let result = index.query_wordplay(strict);
+        assert_eq!(parses(&result).len(), 1);
+        let mut charades = params(&pattern);
+        charades.containers = false;
+        assert_eq!(index.query_wordplay(charades).total, 1);
+    }
+
+    #[test]
+    fn pages_through_answers() {
+        let index = make_index(&[
+            "carpet", "car", "pet", "carpal", "pal", "capon", "cap", "on",
+        ]);
+        let pattern = parse_pattern("ca____").unwrap();
+        let mut first = params(&pattern);
+        first.page_size = 1;
+        let result = index.query_wordplay(first);
+        assert_eq!((result.total, result.has_more), (2, true));
+        assert_eq!(result.items[0].answer.word, "carpal");
+        let mut second = params(&pattern);
+        second.page = 2;
+        second.page_size = 1;
+        let result = index.query_wordplay(second);
+        assert_eq!(result.items[0].answer.word, "carpet");
+        assert!(!result.has_more);
+    }
+
+    #[test]
+    fn uses_fragments_as_pieces() {
+        let index = make_index(&["tarmac", "mac", "bleat", "beat"]);
+        let fragment = |cells: &str, source: &str| Fragment {
+            cells: cells.to_string(),
+            letters: cells.to_string(),
+            source: source.to_string(),
+        };
+        let fragments = [fragment("tar", "sailor"), fragment("l", "learner")];
+        let pattern = parse_pattern("_____").unwrap();
+        let mut with_fragments = params(&pattern);
+        with_fragments.fragments = &fragments;
+        assert_eq!(
+            parses(&index.query_wordplay(with_fragments)),
+            [("bleat".to_string(), vec!["b(L)eat".to_string()])]
+        );
+        assert_eq!(index.query_wordplay(params(&pattern)).total, 0);
+
+        let pattern = parse_pattern("______").unwrap();
+        let mut with_fragments = params(&pattern);
+        with_fragments.fragments = &fragments;
+        assert_eq!(
+            parses(&index.query_wordplay(with_fragments)),
+            [("tarmac".to_string(), vec!["TAR+mac".to_string()])]
+        );
+    }
+}
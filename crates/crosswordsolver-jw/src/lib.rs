@@ -1,9 +1,23 @@
+pub mod clue;
+pub mod cryptic;
 pub mod handlers;
 pub mod index;
+pub mod lists;
 pub mod rate_limit;
+pub mod reload;
+pub mod reverse;
+pub mod thesaurus;
 
 pub use handlers::{AppState, router};
 pub use index::{
-    AnagramParams, MAX_WORD_LEN, QueryParams, WordIndex, parse_letter_bag, parse_letters,
-    parse_pattern,
+    ALPHABET_PRESETS, Alphabet, AlphabetError, AnagramParams, Cell, Construction, Crossing,
+    Direction, Distance, Fill, FillError, FillParams, FilledSlot, FuzzyParams, Grid, GridError,
+    GroupedResult, LadderError, LadderParams, LadderResult, LengthGroup, LetterSet, MAX_GRID_SIZE,
+    MAX_LADDER_STEPS, MAX_MISMATCHES, MAX_OVERLAY_WORDS, MAX_REGEX_LEN, MAX_WILDCARDS,
+    MAX_WORD_LEN, Overlay, OverlayError, Pattern, PhraseAnagramParams, PhraseMatch, PhraseResult,
+    QueryParams, RegexError, RegexParams, Slot, SnapshotError, SortOrder, Spelled,
+    SubAnagramParams, WildcardParams, WildcardPattern, WordIndex, WordMatch, WordRegex,
+    WordleConstraints, WordleError, WordleParams, WordleResult, Wordplay, WordplayParams,
+    WordplayResult, mismatched_positions, parse_crossings, parse_enumeration, parse_isomorph,
+    parse_letter_bag, parse_letters, parse_pattern, parse_tile_bag, parse_wildcard_pattern,
 };
@@ -0,0 +1,151 @@
+//! Named word lists served side by side.
+//!
+//! Each list has its own [`IndexStore`], so lists reload independently.
+//! Requests pick one with `list=`; without it they use the default list.
+
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+use thiserror::Error;
+
+use crate::reload::IndexStore;
+
+/// Name used when only `WORDLIST_PATH` is configured.
+pub const DEFAULT_LIST: &str = "default";
+
+#[derive(Debug, Error)]
+pub enum ListsError {
+    #[error("invalid list name {0:?}; use letters, digits, '-' and '_'")]
+    InvalidName(String),
+    #[error("list {0:?} is configured twice")]
+    Duplicate(String),
+    #[error("default list {0:?} is not configured")]
+    MissingDefault(String),
+    #[error("expected name=path, got {0:?}")]
+    Malformed(String),
+}
+
+pub struct WordLists {
+    default: String,
+    lists: BTreeMap<String, Arc<IndexStore>>,
+}
+
+impl WordLists {
+    /// A server with one list, named [`DEFAULT_LIST`].
+    pub fn single(store: IndexStore) -> Self {
+        Self {
+            default: DEFAULT_LIST.to_string(),
+            lists: BTreeMap::from([(DEFAULT_LIST.to_string(), Arc::new(store))]),
+        }
+    }
+
+    pub fn new(
+        default: impl Into<String>,
+        lists: impl IntoIterator<Item = (String, IndexStore)>,
+    ) -> Result<Self, ListsError> {
+        let default = default.into();
+        let mut map = BTreeMap::new();
+        for (name, store) in lists {
+            validate_name(&name)?;
+            if map.insert(name.clone(), Arc::new(store)).is_some() {
+                return Err(ListsError::Duplicate(name));
+            }
+        }
+        if !map.contains_key(&default) {
+            return Err(ListsError::MissingDefault(default));
+        }
+        Ok(Self {
+            default,
+            lists: map,
+        })
+    }
+
+    pub fn default_name(&self) -> &str {
+        &self.default
+    }
+
+    /// The named list, or the default one when `name` is `None`.
+    pub fn get(&self, name: Option<&str>) -> Option<&Arc<IndexStore>> {
+        self.lists.get(name.unwrap_or(&self.default))
+    }
+
+    pub fn default_list(&self) -> &Arc<IndexStore> {
+        &self.lists[&self.default]
+    }
+
+    /// All lists in name order.
+    pub fn iter(&self) -> impl Iterator<Item = (&str, &Arc<IndexStore>)> {
+        self.lists
+            .iter()
+            .map(|(name, store)| (name.as_str(), store))
+    }
+}
+
+fn validate_name(name: &str) -> Result<(), ListsError> {
+    let valid = !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if valid {
+        Ok(())
+    } else {
+        Err(ListsError::InvalidName(name.to_string()))
+    }
+}
+
+/// Parse `name=path` pairs separated by commas, e.g.
+/// `daily=/data/daily.txt,scrabble=/data/sowpods.txt`.
+pub fn parse_list_spec(raw: &str) -> Result<Vec<(String, String)>, ListsError> {
+    raw.split(',')
+        .map(str::trim)
+        .filter(|part| !part.is_empty())
+        .map(|part| {
+            let (name, path) = part
+                .split_once('=')
+                .ok_or_else(|| ListsError::Malformed(part.to_string()))?;
+            let (name, path) = (name.trim(), path.trim());
+            validate_name(name)?;
+            if path.is_empty() {
+                return Err(ListsError::Malformed(part.to_string()));
+            }
+            Ok((name.to_string(), path.to_string()))
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::index::WordIndex;
+
+    #[test]
+    fn parses_list_specs() {
+        let lists = parse_list_spec("daily=/a/daily.txt, scrabble = b.txt,").unwrap();
+        assert_eq!(
+            lists,
+            vec![
+                ("daily".to_string(), "/a/daily.txt".to_string()),
+                ("scrabble".to_string(), "b.txt".to_string()),
+            ]
+        );
+        assert!(parse_list_spec("daily").is_err());
+        assert!(parse_list_spec("da ily=x").is_err());
+    }
+
+    #[test]
+    fn picks_named_or_default_list() {
+        let store = || IndexStore::new(Arc::new(WordIndex::empty()), "v", None);
+        let lists = WordLists::new(
+            "daily",
+            [("daily".into(), store()), ("theme".into(), store())],
+        )
+        .unwrap();
+        assert!(Arc::ptr_eq(
+            lists.get(None).unwrap(),
+            lists.get(Some("daily")).unwrap()
+        ));
+        assert!(lists.get(Some("theme")).is_some());
+        assert!(lists.get(Some("nope")).is_none());
+        assert!(WordLists::new("other", [("daily".into(), store())]).is_err());
+    }
+}
@@ -1,35 +1,50 @@
+use std::collections::HashMap;
 use std::env;
+use std::fs;
 use std::net::SocketAddr;
-use std::path::PathBuf;
-use std::sync::Arc;
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, OnceLock};
 use std::time::Instant;
 
 use tokio::net::TcpListener;
 use tower_http::trace::TraceLayer;
-use tracing::{Level, info};
+use tracing::{Level, info, warn};
 use tracing_subscriber::EnvFilter;
 use wordnet_db::{LoadMode, WordNet};
 use wordnet_morphy::Morphy;
 
+use crosswordsolver_jw::cryptic::CrypticDb;
+use crosswordsolver_jw::lists::{DEFAULT_LIST, WordLists, parse_list_spec};
 use crosswordsolver_jw::rate_limit::RateLimiterLayer;
-use crosswordsolver_jw::{AppState, WordIndex, router};
+use crosswordsolver_jw::reload::{IndexStore, list_version};
+use crosswordsolver_jw::{Alphabet, AppState, WordIndex, router};
 
 const DEFAULT_PORT: u16 = 8080;
 const DEFAULT_HOST: &str = "0.0.0.0";
-const DEFAULT_WORDLIST: &str = "words.txt";
+const DEFAULT_WORDLIST: &str = "spreadthewordlist.dict";
 const DEFAULT_WORDNET_PATH: &str = "open_english_wordnet_2024/oewn2024";
 const DEFAULT_WORDNET_IMAGE_PATH: &str = "/app/wordnet";
 const MAX_PAGE_SIZE: usize = 500;
 const DEFAULT_RATE_LIMIT_RPS: u32 = 5;
 const DEFAULT_RATE_LIMIT_BURST: u32 = 10;
+const WORDNET_SNAPSHOT: &str = "wordnet.snap";
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
     init_tracing();
 
-    let config = load_config();
+    let config = load_config()?;
+    if env::args().nth(1).as_deref() == Some("build-snapshot") {
+        return build_snapshot(&config);
+    }
     info!("binding to {}:{}", config.host, config.port);
-    info!("using wordlist at {}", config.wordlist_path.display());
+    for (name, path) in &config.wordlists {
+        info!(
+            "using wordlist '{name}' at {} (alphabet {})",
+            path.display(),
+            config.alphabet(name).name()
+        );
+    }
     info!(
         "using wordnet at {} (mode: {:?})",
         config.wordnet_path.display(),
@@ -38,30 +53,53 @@ async fn main() -> anyhow::Result<()> {
     if config.disable_cache {
         info!("cache headers disabled");
     }
+    if config.admin_token.is_none() {
+        info!("ADMIN_TOKEN not set; /admin/reload disabled");
+    }
     info!(
         "rate limit: {} req/s (burst {})",
         config.rate_limit_rps, config.rate_limit_burst
     );
 
-    let start = Instant::now();
-    let index = WordIndex::build_from_file(&config.wordlist_path)?;
-    let elapsed = start.elapsed();
-    info!("index built in {} ms", elapsed.as_millis());
+    let mut stores = Vec::with_capacity(config.wordlists.len());
+    for (name, path) in &config.wordlists {
+        let start = Instant::now();
+        let index = load_index(&config, name, path)?;
+        let version = list_version(path)?;
+        info!(
+            "index '{name}' ready in {} ms (list {version})",
+            start.elapsed().as_millis()
+        );
+        stores.push((
+            name.clone(),
+            IndexStore::new(index, version, Some(path.clone())),
+        ));
+    }
+    let lists = Arc::new(WordLists::new(config.default_list.clone(), stores)?);
+    spawn_reload_on_sighup(Arc::clone(&lists));
 
     let wn_start = Instant::now();
-    let wordnet = Arc::new(WordNet::load_with_mode(
-        &config.wordnet_path,
-        config.wordnet_mode,
-    )?);
+    let wordnet = Arc::new(load_wordnet(&config)?);
     let morphy = Arc::new(Morphy::load(&config.wordnet_path)?);
     info!("wordnet loaded in {} ms", wn_start.elapsed().as_millis());
 
+    let cryptic = match &config.cryptic_db {
+        Some(path) => {
+            info!("using cryptic table at {}", path.display());
+            CrypticDb::load(path)?
+        }
+        None => CrypticDb::builtin(),
+    };
+
     let state = AppState {
-        index: Arc::clone(&index),
+        lists,
         wordnet,
         morphy,
         max_page_size: MAX_PAGE_SIZE,
         disable_cache: config.disable_cache,
+        admin_token: config.admin_token.clone(),
+        cryptic: Arc::new(cryptic),
+        reverse: Arc::new(OnceLock::new()),
     };
 
     let rate_limiter = RateLimiterLayer::new(config.rate_limit_rps, config.rate_limit_burst);
@@ -81,18 +119,28 @@ async fn main() -> anyhow::Result<()> {
 struct Config {
     host: String,
     port: u16,
-    wordlist_path: PathBuf,
+    /// Named word lists in configuration order.
+    wordlists: Vec<(String, PathBuf)>,
+    default_list: String,
+    /// Alphabet for lists without an entry in `list_alphabets`.
+    alphabet: Arc<Alphabet>,
+    list_alphabets: HashMap<String, Arc<Alphabet>>,
     wordnet_path: PathBuf,
     wordnet_mode: LoadMode,
+    snapshot_dir: Option<PathBuf>,
     disable_cache: bool,
+    admin_token: Option<String>,
+    /// Abbreviation and indicator table; the built-in one when unset.
+    cryptic_db: Option<PathBuf>,
     rate_limit_rps: u32,
     rate_limit_burst: u32,
 }
 
-fn load_config() -> Config {
+fn load_config() -> anyhow::Result<Config> {
     let mut disable_cache = false;
     let mut cli_wordnet_dir: Option<PathBuf> = None;
     let mut cli_wordnet_mode: Option<LoadMode> = None;
+    let mut cli_snapshot_dir: Option<PathBuf> = None;
     let mut args = env::args().skip(1).peekable();
     while let Some(arg) = args.next() {
         match arg.as_str() {
@@ -107,6 +155,8 @@ fn load_config() -> Config {
                     cli_wordnet_dir = Some(PathBuf::from(path));
                 } else if let Some(mode) = arg.strip_prefix("--wordnet-mode=") {
                     cli_wordnet_mode = parse_load_mode(mode);
+                } else if let Some(path) = arg.strip_prefix("--snapshot-dir=") {
+                    cli_snapshot_dir = Some(PathBuf::from(path));
                 }
             }
         }
@@ -117,9 +167,39 @@ fn load_config() -> Config {
         .ok()
         .and_then(|p| p.parse::<u16>().ok())
         .unwrap_or(DEFAULT_PORT);
-    let wordlist_path = env::var("WORDLIST_PATH")
-        .map(PathBuf::from)
-        .unwrap_or_else(|_| PathBuf::from(DEFAULT_WORDLIST));
+    let wordlists = match env::var("WORDLISTS").ok().filter(|v| !v.trim().is_empty()) {
+        Some(spec) => parse_list_spec(&spec)?
+            .into_iter()
+            .map(|(name, path)| (name, PathBuf::from(path)))
+            .collect(),
+        None => {
+            let path = env::var("WORDLIST_PATH")
+                .map(PathBuf::from)
+                .unwrap_or_else(|_| PathBuf::from(DEFAULT_WORDLIST));
+            vec![(DEFAULT_LIST.to_string(), path)]
+        }
+    };
+    let default_list = env::var("DEFAULT_LIST")
+        .ok()
+        .filter(|v| !v.is_empty())
+        .unwrap_or_else(|| wordlists[0].0.clone());
+    let alphabet = match env::var("ALPHABET").ok().filter(|v| !v.trim().is_empty()) {
+        Some(spec) => Arc::new(Alphabet::parse(&spec)?),
+        None => Alphabet::english(),
+    };
+    let list_alphabets = match env::var("LIST_ALPHABETS").ok() {
+        Some(spec) => parse_list_spec(&spec)?
+            .into_iter()
+            .map(|(name, spec)| Ok((name, Arc::new(Alphabet::parse(&spec)?))))
+            .collect::<anyhow::Result<_>>()?,
+        None => HashMap::new(),
+    };
+    if let Some(name) = list_alphabets
+        .keys()
+        .find(|name| !wordlists.iter().any(|(list, _)| list == *name))
+    {
+        anyhow::bail!("LIST_ALPHABETS names unknown list '{name}'");
+    }
     let wordnet_path = cli_wordnet_dir
         .or_else(|| env::var("WORDNET_DIR").ok().map(PathBuf::from))
         .unwrap_or_else(default_wordnet_path);
@@ -131,6 +211,13 @@ fn load_config() -> Config {
                 .and_then(parse_load_mode)
         })
         .unwrap_or(LoadMode::Mmap);
+    let snapshot_dir =
+        cli_snapshot_dir.or_else(|| env::var("SNAPSHOT_DIR").ok().map(PathBuf::from));
+    let admin_token = env::var("ADMIN_TOKEN").ok().filter(|t| !t.is_empty());
+    let cryptic_db = env::var("CRYPTIC_DB")
+        .ok()
+        .filter(|p| !p.trim().is_empty())
+        .map(PathBuf::from);
     let rate_limit_rps = env::var("RATE_LIMIT_RPS")
         .ok()
         .and_then(|v| v.parse::<u32>().ok())
@@ -142,16 +229,136 @@ fn load_config() -> Config {
         .filter(|v| *v > 0)
         .unwrap_or(DEFAULT_RATE_LIMIT_BURST);
 
-    Config {
+    Ok(Config {
         host,
         port,
-        wordlist_path,
+        wordlists,
+        default_list,
+        alphabet,
+        list_alphabets,
         wordnet_path,
         wordnet_mode,
+        snapshot_dir,
         disable_cache,
+        admin_token,
+        cryptic_db,
         rate_limit_rps,
         rate_limit_burst,
+    })
+}
+
+impl Config {
+    /// The alphabet the list called `name` is spelled with.
+    fn alphabet(&self, name: &str) -> &Arc<Alphabet> {
+        self.list_alphabets.get(name).unwrap_or(&self.alphabet)
+    }
+}
+
+/// Snapshot file for the word list called `name`.
+fn index_snapshot(dir: &Path, name: &str) -> PathBuf {
+    dir.join(format!("words-{name}.snap"))
+}
+
+/// Map the list's snapshot when one is configured and current; otherwise
+/// build the index from the word list.
+fn load_index(config: &Config, name: &str, wordlist: &Path) -> anyhow::Result<Arc<WordIndex>> {
+    let alphabet = config.alphabet(name);
+    if let Some(dir) = &config.snapshot_dir {
+        let path = index_snapshot(dir, name);
+        match WordIndex::load_snapshot(&path, wordlist, alphabet) {
+            Ok(index) => {
+                info!("index loaded from snapshot {}", path.display());
+                return Ok(index);
+            }
+            Err(err) => warn!(
+                "index snapshot {} unusable ({err}); rebuilding",
+                path.display()
+            ),
+        }
     }
+    Ok(WordIndex::build_from_file_with_alphabet(
+        wordlist,
+        Arc::clone(alphabet),
+    )?)
+}
+
+/// Load WordNet from its snapshot when one is configured and current;
+/// otherwise parse the dictionary files.
+fn load_wordnet(config: &Config) -> anyhow::Result<WordNet> {
+    if let Some(dir) = &config.snapshot_dir {
+        let path = dir.join(WORDNET_SNAPSHOT);
+        match WordNet::load_with_snapshot(&config.wordnet_path, &path, config.wordnet_mode) {
+            Ok(wordnet) => {
+                info!("wordnet loaded from snapshot {}", path.display());
+                return Ok(wordnet);
+            }
+            Err(err) => warn!(
+                "wordnet snapshot {} unusable ({err:#}); parsing",
+                path.display()
+            ),
+        }
+    }
+    WordNet::load_with_mode(&config.wordnet_path, config.wordnet_mode)
+}
+
+/// Rebuild every word list whenever the process gets SIGHUP.
+#[cfg(unix)]
+fn spawn_reload_on_sighup(lists: Arc<WordLists>) {
+    use tokio::signal::unix::{SignalKind, signal};
+
+    let mut hangups = match signal(SignalKind::hangup()) {
+        Ok(stream) => stream,
+        Err(err) => {
+            warn!("cannot listen for SIGHUP ({err}); reload via /admin/reload only");
+            return;
+        }
+    };
+    tokio::spawn(async move {
+        while hangups.recv().await.is_some() {
+            info!("SIGHUP received; reloading word lists");
+            for (name, store) in lists.iter() {
+                if let Err(err) = store.reload().await {
+                    warn!("reload of word list '{name}' failed: {err}");
+                }
+            }
+        }
+    });
+}
+
+#[cfg(not(unix))]
+fn spawn_reload_on_sighup(_lists: Arc<WordLists>) {}
+
+/// `crosswordsolver build-snapshot`: write both snapshots into the snapshot
+/// directory and exit.
+fn build_snapshot(config: &Config) -> anyhow::Result<()> {
+    let Some(dir) = &config.snapshot_dir else {
+        anyhow::bail!("build-snapshot needs --snapshot-dir=<path> or SNAPSHOT_DIR");
+    };
+    fs::create_dir_all(dir)?;
+
+    for (name, wordlist) in &config.wordlists {
+        let start = Instant::now();
+        let index =
+            WordIndex::build_from_file_with_alphabet(wordlist, Arc::clone(config.alphabet(name)))?;
+        let path = index_snapshot(dir, name);
+        index.write_snapshot(&path, wordlist)?;
+        info!(
+            "wrote {} in {} ms",
+            path.display(),
+            start.elapsed().as_millis()
+        );
+    }
+
+    let start = Instant::now();
+    let wordnet = WordNet::load_with_mode(&config.wordnet_path, LoadMode::Owned)?;
+    let path = dir.join(WORDNET_SNAPSHOT);
+    wordnet.write_snapshot(&path)?;
+    info!(
+        "wrote {} in {} ms",
+        path.display(),
+        start.elapsed().as_millis()
+    );
+    Ok(())
 }
 
 fn default_wordnet_path() -> PathBuf {
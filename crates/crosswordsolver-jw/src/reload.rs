@@ -0,0 +1,148 @@
+//! The live word index and its hot reload.
+//!
+//! Handlers take the current index with [`IndexStore::load`] and keep that
+//! `Arc` for the rest of the request, so a reload never changes the list
+//! underneath a request that is already running. A reload rebuilds the index
+//! on the blocking pool and swaps the pointer once the new one is complete.
+
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, RwLock};
+
+use thiserror::Error;
+use tokio::sync::Mutex;
+use tracing::info;
+
+use crate::index::{IndexError, WordIndex, list_checksum};
+
+/// An index together with the list it was built from.
+#[derive(Debug)]
+pub struct ActiveIndex {
+    pub index: Arc<WordIndex>,
+    /// Checksum of the word list, as 16 hex digits.
+    pub version: String,
+    /// Starts at 1 and goes up by one with every successful reload.
+    pub generation: u64,
+}
+
+#[derive(Debug, Error)]
+pub enum ReloadError {
+    #[error("no word list path configured")]
+    NoSource,
+    #[error("a reload is already running")]
+    InProgress,
+    #[error("the new word list has no usable words")]
+    Empty,
+    #[error(transparent)]
+    Index(#[from] IndexError),
+    #[error("reload task failed: {0}")]
+    Task(#[from] tokio::task::JoinError),
+}
+
+pub struct IndexStore {
+    current: RwLock<Arc<ActiveIndex>>,
+    wordlist: Option<PathBuf>,
+    reloading: Mutex<()>,
+}
+
+impl IndexStore {
+    /// Wrap an already built index. Without a `wordlist` path the store
+    /// cannot reload.
+    pub fn new(
+        index: Arc<WordIndex>,
+        version: impl Into<String>,
+        wordlist: Option<PathBuf>,
+    ) -> Self {
+        Self {
+            current: RwLock::new(Arc::new(ActiveIndex {
+                index,
+                version: version.into(),
+                generation: 1,
+            })),
+            wordlist,
+            reloading: Mutex::new(()),
+        }
+    }
+
+    /// The index to use for one request.
+    pub fn load(&self) -> Arc<WordIndex> {
+        Arc::clone(&self.active().index)
+    }
+
+    pub fn active(&self) -> Arc<ActiveIndex> {
+        Arc::clone(&self.current.read().expect("index lock poisoned"))
+    }
+
+    /// The word list this store reloads from.
+    pub fn source(&self) -> Option<&Path> {
+        self.wordlist.as_deref()
+    }
+
+    /// Rebuild the index from the word list and swap it in. Only one reload
+    /// runs at a time; a second caller gets [`ReloadError::InProgress`]. A
+    /// list that yields no words is rejected and the current index stays.
+    pub async fn reload(&self) -> Result<Arc<ActiveIndex>, ReloadError> {
+        let path = self.wordlist.clone().ok_or(ReloadError::NoSource)?;
+        let _guard = self
+            .reloading
+            .try_lock()
+            .map_err(|_| ReloadError::InProgress)?;
+
+        // A reload keeps the alphabet the list was first built with.
+        let alphabet = Arc::clone(self.active().index.alphabet());
+        let (index, version) = tokio::task::spawn_blocking(move || {
+            let version = list_version(&path)?;
+            let index = WordIndex::build_from_file_with_alphabet(&path, alphabet)?;
+            Ok::<_, IndexError>((index, version))
+        })
+        .await??;
+        if index.word_count() == 0 {
+            return Err(ReloadError::Empty);
+        }
+
+        let mut current = self.current.write().expect("index lock poisoned");
+        let next = Arc::new(ActiveIndex {
+            index,
+            version,
+            generation: current.generation + 1,
+        });
+        *current = Arc::clone(&next);
+        info!(
+            "swapped in word list {} (generation {}, {} words)",
+            next.version,
+            next.generation,
+            next.index.word_count()
+        );
+        Ok(next)
+    }
+}
+
+/// Version string for the word list at `path`.
+pub fn list_version(path: &Path) -> Result<String, IndexError> {
+    Ok(format!("{:016x}", list_checksum(path)?))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[tokio::test]
+    async fn reload_swaps_index_and_keeps_old_handles() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("words.txt");
+        std::fs::write(&path, "apple\n").unwrap();
+        let index = WordIndex::build_from_file(&path).unwrap();
+        let store = IndexStore::new(index, list_version(&path).unwrap(), Some(path.clone()));
+        let before = store.active();
+
+        std::fs::write(&path, "apple\nangle\n").unwrap();
+        let after = store.reload().await.unwrap();
+        assert_eq!(after.generation, 2);
+        assert_ne!(after.version, before.version);
+        assert_eq!(store.load().word_count(), 2);
+        assert_eq!(before.index.word_count(), 1);
+
+        std::fs::write(&path, "not a word!\n").unwrap();
+        assert!(matches!(store.reload().await, Err(ReloadError::Empty)));
+        assert_eq!(store.active().generation, 2);
+    }
+}
@@ -0,0 +1,230 @@
+//! Reverse dictionary: synsets ranked by how well their text matches a
+//! description.
+//!
+//! Every synset's lemmas and whole gloss are one document in an inverted
+//! index, built on the first lookup rather than at startup. A query scores
+//! documents with BM25 over the terms it shares with them. Terms are
+//! lowercase words with plural endings trimmed, so "rivers" finds "river".
+
+use std::collections::HashMap;
+
+use thiserror::Error;
+use wordnet_db::WordNet;
+use wordnet_types::{Pos, SynsetId};
+
+use crate::clue::STOPWORDS;
+
+/// Longest description accepted, in characters.
+pub const MAX_QUERY_LEN: usize = 200;
+/// Best-scoring synsets kept per query.
+pub const MAX_HITS: usize = 1000;
+/// BM25 term-frequency saturation.
+const K1: f32 = 1.2;
+/// BM25 length normalization.
+const B: f32 = 0.75;
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ReverseError {
+    #[error("q must contain at least one word that is not a stopword")]
+    NoTerms,
+    #[error("q must be at most {0} characters")]
+    TooLong(usize),
+}
+
+/// Term postings over WordNet synsets.
+#[derive(Debug, Default)]
+pub struct ReverseIndex {
+    synsets: Vec<SynsetId>,
+    /// Terms per synset.
+    lens: Vec<u32>,
+    avg_len: f32,
+    /// Per term: synset positions with the times the term occurs there.
+    postings: HashMap<String, Vec<(u32, u32)>>,
+}
+
+/// A synset matching the description.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ReverseHit {
+    pub synset: SynsetId,
+    pub score: f32,
+    /// Words of the query found in the synset, in query order.
+    pub matched: Vec<String>,
+}
+
+impl ReverseIndex {
+    pub fn build(wordnet: &WordNet) -> Self {
+        let mut index = Self::default();
+        let mut total = 0u64;
+        for synset in wordnet.iter_synsets() {
+            let mut counts: HashMap<String, u32> = HashMap::new();
+            // The raw gloss holds the definition, anything after its first
+            // `;`, and the examples.
+            let lemmas = synset.words.iter().map(|w| w.text);
+            for part in lemmas.chain([synset.gloss.raw]) {
+                for term in terms(part) {
+                    *counts.entry(term).or_default() += 1;
+                }
+            }
+            let doc = index.synsets.len() as u32;
+            let len: u32 = counts.values().sum();
+            for (term, count) in counts {
+                index.postings.entry(term).or_default().push((doc, count));
+            }
+            index.synsets.push(synset.id);
+            index.lens.push(len);
+            total += u64::from(len);
+        }
+        index.avg_len = total as f32 / index.synsets.len().max(1) as f32;
+        index
+    }
+
+    pub fn synset_count(&self) -> usize {
+        self.synsets.len()
+    }
+
+    /// Synsets in the parts of speech `pos` sharing terms with `query`, best
+    /// first, at most [`MAX_HITS`].
+    pub fn search(&self, query: &str, pos: &[Pos]) -> Result<Vec<ReverseHit>, ReverseError> {
+        if query.chars().count() > MAX_QUERY_LEN {
+            return Err(ReverseError::TooLong(MAX_QUERY_LEN));
+        }
+        // Each term once, with the query word it came from.
+        let mut words: Vec<(String, String)> = Vec::new();
+        for word in words_of(query) {
+            let term = stem(&word);
+            if !words.iter().any(|(t, _)| *t == term) {
+                words.push((term, word));
+            }
+        }
+        if words.is_empty() {
+            return Err(ReverseError::NoTerms);
+        }
+
+        let docs = self.synsets.len() as f32;
+        let mut scores: HashMap<u32, (f32, Vec<usize>)> = HashMap::new();
+        for (which, (term, _)) in words.iter().enumerate() {
+            let Some(postings) = self.postings.get(term) else {
+                continue;
+            };
+            let df = postings.len() as f32;
+            let idf = (1.0 + (docs - df + 0.5) / (df + 0.5)).ln();
+            for &(doc, count) in postings {
+                if !pos.contains(&self.synsets[doc as usize].pos) {
+                    continue;
+                }
+                let tf = count as f32;
+                let norm = 1.0 - B + B * self.lens[doc as usize] as f32 / self.avg_len;
+                let entry = scores.entry(doc).or_default();
+                entry.0 += idf * tf * (K1 + 1.0) / (tf + K1 * norm);
+                entry.1.push(which);
+            }
+        }
+
+        let mut hits: Vec<ReverseHit> = scores
+            .into_iter()
+            .map(|(doc, (score, matched))| ReverseHit {
+                synset: self.synsets[doc as usize],
+                score,
+                matched: matched.into_iter().map(|i| words[i].1.clone()).collect(),
+            })
+            .collect();
+        hits.sort_by(|a, b| {
+            b.score
+                .total_cmp(&a.score)
+                .then_with(|| a.synset.offset.cmp(&b.synset.offset))
+        });
+        hits.truncate(MAX_HITS);
+        Ok(hits)
+    }
+}
+
+/// Lowercase words of `text` that are not stopwords. Lemmas join words
+/// with underscores, which split here too.
+fn words_of(text: &str) -> impl Iterator<Item = String> + '_ {
+    text.split(|c: char| !c.is_alphanumeric() && c != '\'')
+        .map(|w| w.trim_matches('\'').to_lowercase())
+        .map(|w| w.strip_suffix("'s").map(str::to_string).unwrap_or(w))
+        .filter(|w| !w.is_empty() && !STOPWORDS.contains(&w.as_str()))
+}
+
+fn terms(text: &str) -> impl Iterator<Item = String> + '_ {
+    words_of(text).map(|w| stem(&w))
+}
+
+/// Trim a plural ending: "ponies" to "pony", "rivers" to "river". Words
+/// ending in "ss", "us" or "is" keep their "s".
+fn stem(word: &str) -> String {
+    if word.len() > 4
+        && let Some(base) = word.strip_suffix("ies")
+    {
+        return format!("{base}y");
+    }
+    if word.len() > 3
+        && word.ends_with('s')
+        && !["ss", "us", "is"].iter().any(|end| word.ends_with(end))
+    {
+        return word[..word.len() - 1].to_string();
+    }
+    word.to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordnet_db::LoadMode;
+
+    fn fixture() -> WordNet {
+        let dir = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../wordnet-db/tests/fixtures/wn"
+        );
+        WordNet::load_with_mode(dir, LoadMode::Owned).unwrap()
+    }
+
+    const ALL: [Pos; 4] = [Pos::Noun, Pos::Verb, Pos::Adj, Pos::Adv];
+
+    #[test]
+    fn ranks_synsets_by_shared_terms() {
+        let index = ReverseIndex::build(&fixture());
+        assert_eq!(index.synset_count(), 6);
+
+        let hits = index.search("A domestic mammal", &ALL).unwrap();
+        assert_eq!(hits.len(), 1);
+        assert_eq!(hits[0].synset.offset, 1740);
+        assert_eq!(hits[0].matched, ["domestic", "mammal"]);
+
+        // Examples count, and plurals meet singulars.
+        let hits = index.search("campers", &ALL).unwrap();
+        assert_eq!(hits[0].synset.offset, 4000);
+
+        let hits = index.search("move swiftly", &ALL).unwrap();
+        assert_eq!(hits[0].synset.offset, 2500);
+        assert_eq!(hits[0].matched, ["move", "swiftly"]);
+        assert_eq!(hits[1].synset.offset, 3000);
+        assert!(
+            index
+                .search("move swiftly", &[Pos::Noun])
+                .unwrap()
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rejects_empty_and_long_queries() {
+        let index = ReverseIndex::build(&fixture());
+        assert_eq!(index.search("of the", &ALL), Err(ReverseError::NoTerms));
+        assert_eq!(
+            index.search(&"a".repeat(MAX_QUERY_LEN + 1), &ALL),
+            Err(ReverseError::TooLong(MAX_QUERY_LEN))
+        );
+    }
+
+    #[test]
+    fn trims_plurals() {
+        assert_eq!(stem("ponies"), "pony");
+        assert_eq!(stem("rivers"), "river");
+        assert_eq!(stem("glass"), "glass");
+        assert_eq!(stem("bus"), "bus");
+        assert_eq!(stem("gas"), "gas");
+    }
+}
@@ -0,0 +1,281 @@
+//! Words related to a word in WordNet, a few relation steps out.
+//!
+//! The word's own synsets are step 0 and give its synonyms. Each further
+//! step follows the chosen relations (similar-to, hypernyms, ...) from the
+//! synsets reached so far; every synset keeps the shortest path to it. The
+//! lemmas of all reached synsets are the results.
+
+use std::collections::{HashMap, HashSet, VecDeque};
+
+use thiserror::Error;
+use wordnet_db::WordNet;
+use wordnet_morphy::Morphy;
+use wordnet_types::{Pos, SynsetId};
+
+/// Most relation steps a walk may take.
+pub const MAX_DEPTH: usize = 3;
+/// Synsets visited before a walk stops early.
+const MAX_SYNSETS: usize = 20_000;
+
+/// A WordNet relation the walk can follow, named as in
+/// `/v1/wordnet/related`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Relation {
+    pub name: &'static str,
+    symbols: &'static [&'static str],
+}
+
+pub const RELATIONS: [Relation; 12] = [
+    Relation::new("similar_to", &["&"]),
+    Relation::new("also_see", &["^"]),
+    Relation::new("verb_group", &["$"]),
+    Relation::new("hypernyms", &["@", "@i"]),
+    Relation::new("hyponyms", &["~", "~i"]),
+    Relation::new("derivations", &["+"]),
+    Relation::new("pertainyms", &["\\"]),
+    Relation::new("participle", &["<"]),
+    Relation::new("attributes", &["="]),
+    Relation::new("entails", &["*"]),
+    Relation::new("causes", &[">"]),
+    Relation::new("antonyms", &["!"]),
+];
+
+/// Followed when a query names no relations.
+pub const DEFAULT_RELATIONS: [&str; 5] = [
+    "similar_to",
+    "also_see",
+    "verb_group",
+    "hypernyms",
+    "hyponyms",
+];
+
+impl Relation {
+    const fn new(name: &'static str, symbols: &'static [&'static str]) -> Self {
+        Self { name, symbols }
+    }
+
+    pub fn by_name(name: &str) -> Option<Relation> {
+        RELATIONS.into_iter().find(|r| r.name == name)
+    }
+}
+
+#[derive(Debug, Error, PartialEq, Eq)]
+pub enum ThesaurusError {
+    #[error("unknown relation {0:?}; expected one of {1}")]
+    UnknownRelation(String, String),
+}
+
+/// Parse comma-separated relation names, or the defaults when `raw` is
+/// empty.
+pub fn parse_relations(raw: Option<&str>) -> Result<Vec<Relation>, ThesaurusError> {
+    let names: Vec<&str> = raw
+        .unwrap_or_default()
+        .split(',')
+        .map(str::trim)
+        .filter(|n| !n.is_empty())
+        .collect();
+    if names.is_empty() {
+        return Ok(DEFAULT_RELATIONS
+            .iter()
+            .filter_map(|name| Relation::by_name(name))
+            .collect());
+    }
+    names
+        .into_iter()
+        .map(|name| {
+            Relation::by_name(&name.to_ascii_lowercase()).ok_or_else(|| {
+                let known: Vec<&str> = RELATIONS.iter().map(|r| r.name).collect();
+                ThesaurusError::UnknownRelation(name.to_string(), known.join("|"))
+            })
+        })
+        .collect()
+}
+
+/// A lemma reached from the word.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RelatedLemma {
+    /// The lemma with spaces for underscores, e.g. "ice cream".
+    pub lemma: String,
+    pub pos: Pos,
+    pub synset: SynsetId,
+    /// Relations followed to reach it; empty for synonyms.
+    pub path: Vec<&'static str>,
+    /// Tagged sense count of the lemma in that synset, 0 when untagged.
+    pub sense_count: u32,
+}
+
+#[derive(Debug, Clone)]
+pub struct RelatedLemmas {
+    /// The word's own lemmas, which are left out of `items`.
+    pub lemmas: Vec<String>,
+    /// Closest first, then by sense count, then alphabetically. Each lemma
+    /// appears once, with its closest synset.
+    pub items: Vec<RelatedLemma>,
+    /// Set when the walk stopped at the synset limit.
+    pub truncated: bool,
+}
+
+/// Lemmas within `depth` steps of `word` over `relations`, in the parts of
+/// speech `pos`.
+pub fn related_lemmas(
+    wordnet: &WordNet,
+    morphy: &Morphy,
+    word: &str,
+    pos: &[Pos],
+    relations: &[Relation],
+    depth: usize,
+) -> RelatedLemmas {
+    let mut lemmas = Vec::new();
+    let mut paths: HashMap<SynsetId, Vec<&'static str>> = HashMap::new();
+    let mut queue = VecDeque::new();
+    for &pos in pos {
+        let found = morphy.lemmas_for(pos, word, |p, lemma| wordnet.lemma_exists(p, lemma));
+        for candidate in found {
+            let lemma = candidate.lemma.replace('_', " ");
+            if !lemmas.contains(&lemma) {
+                lemmas.push(lemma);
+            }
+            for id in wordnet.synsets_for_lemma(pos, &candidate.lemma) {
+                if paths.insert(*id, Vec::new()).is_none() {
+                    queue.push_back(*id);
+                }
+            }
+        }
+    }
+
+    // Breadth first, so the first path found to a synset is a shortest one.
+    let mut truncated = false;
+    while let Some(id) = queue.pop_front() {
+        let path = paths[&id].clone();
+        if path.len() >= depth {
+            continue;
+        }
+        let Some(synset) = wordnet.get_synset(id) else {
+            continue;
+        };
+        for pointer in &synset.pointers {
+            let Some(relation) = relations
+                .iter()
+                .find(|r| r.symbols.contains(&pointer.symbol))
+            else {
+                continue;
+            };
+            if paths.contains_key(&pointer.target) {
+                continue;
+            }
+            if paths.len() >= MAX_SYNSETS {
+                truncated = true;
+                break;
+            }
+            let mut next = path.clone();
+            next.push(relation.name);
+            paths.insert(pointer.target, next);
+            queue.push_back(pointer.target);
+        }
+    }
+
+    let own: HashSet<String> = lemmas.iter().map(|l| l.to_lowercase()).collect();
+    let mut best: HashMap<String, RelatedLemma> = HashMap::new();
+    for (id, path) in paths {
+        let Some(synset) = wordnet.get_synset(id) else {
+            continue;
+        };
+        for word in &synset.words {
+            let lemma = word.text.replace('_', " ");
+            let key = lemma.to_lowercase();
+            if own.contains(&key) {
+                continue;
+            }
+            let found = RelatedLemma {
+                sense_count: wordnet
+                    .sense_count(id.pos, word.text, id.offset)
+                    .unwrap_or(0),
+                lemma,
+                pos: id.pos,
+                synset: id,
+                path: path.clone(),
+            };
+            let closer = best
+                .get(&key)
+                .is_none_or(|existing| rank(&found, existing).is_lt());
+            if closer {
+                best.insert(key, found);
+            }
+        }
+    }
+
+    let mut items: Vec<RelatedLemma> = best.into_values().collect();
+    items.sort_by(rank);
+    RelatedLemmas {
+        lemmas,
+        items,
+        truncated,
+    }
+}
+
+fn rank(a: &RelatedLemma, b: &RelatedLemma) -> std::cmp::Ordering {
+    a.path
+        .len()
+        .cmp(&b.path.len())
+        .then_with(|| b.sense_count.cmp(&a.sense_count))
+        .then_with(|| a.lemma.cmp(&b.lemma))
+        .then_with(|| a.synset.offset.cmp(&b.synset.offset))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use wordnet_db::LoadMode;
+
+    fn fixture() -> (WordNet, Morphy) {
+        let dir = concat!(
+            env!("CARGO_MANIFEST_DIR"),
+            "/../wordnet-db/tests/fixtures/wn"
+        );
+        (
+            WordNet::load_with_mode(dir, LoadMode::Owned).unwrap(),
+            Morphy::load(dir).unwrap(),
+        )
+    }
+
+    fn lemmas(found: &RelatedLemmas) -> Vec<(&str, Vec<&str>)> {
+        found
+            .items
+            .iter()
+            .map(|item| (item.lemma.as_str(), item.path.clone()))
+            .collect()
+    }
+
+    #[test]
+    fn walks_relations_to_depth() {
+        let (wordnet, morphy) = fixture();
+        let relations = parse_relations(None).unwrap();
+        let found = related_lemmas(&wordnet, &morphy, "dogs", &[Pos::Noun], &relations, 0);
+        assert_eq!(found.lemmas, ["dog"]);
+        assert_eq!(lemmas(&found), [("cat", vec![])]);
+
+        let found = related_lemmas(&wordnet, &morphy, "dog", &[Pos::Noun], &relations, 1);
+        assert_eq!(
+            lemmas(&found),
+            [("cat", vec![]), ("animal", vec!["hypernyms"])]
+        );
+
+        // Only the relations asked for are followed.
+        let relations = parse_relations(Some("similar_to")).unwrap();
+        let found = related_lemmas(&wordnet, &morphy, "dog", &[Pos::Noun], &relations, 2);
+        assert_eq!(lemmas(&found), [("cat", vec![])]);
+    }
+
+    #[test]
+    fn ranks_by_closeness_then_sense_count() {
+        let (wordnet, morphy) = fixture();
+        let relations = parse_relations(Some("hypernyms")).unwrap();
+        let found = related_lemmas(&wordnet, &morphy, "cat", &[Pos::Noun], &relations, 1);
+        assert_eq!(found.items[0].lemma, "dog");
+        assert_eq!(found.items[0].sense_count, 5);
+        assert!(matches!(
+            parse_relations(Some("hypernyms,cousins")),
+            Err(ThesaurusError::UnknownRelation(name, _)) if name == "cousins"
+        ));
+    }
+}
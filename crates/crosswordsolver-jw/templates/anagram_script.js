@@ -325,10 +325,18 @@ async function fetchPage() {
     if (data.items.length === 0) {
       resultsList.innerHTML = '<li class="list-group-item text-muted">No matches found.</li>';
     } else {
-      data.items.forEach(word => {
+      data.items.forEach(item => {
+        const word = item.word;
         const li = document.createElement('li');
-        li.className = 'list-group-item fs-5 word-item';
-        li.textContent = word;
+        li.className = 'list-group-item fs-5 word-item d-flex justify-content-between align-items-center';
+        li.textContent = item.display || word;
+        if (item.score != null) {
+          const badge = document.createElement('span');
+          badge.className = 'badge text-bg-light fw-normal';
+          badge.textContent = item.score;
+          badge.title = 'Word list score';
+          li.appendChild(badge);
+        }
         li.dataset.word = word;
         li.setAttribute('role', 'button');
         li.tabIndex = 0;
@@ -353,8 +361,8 @@ async function runSolve() {
     statusEl.textContent = 'Letters are required.';
     return;
   }
-  if (!/^[a-z]+$/.test(letters)) {
-    statusEl.textContent = 'Letters must be A-Z only.';
+  if (!/^[a-z?]+$/.test(letters)) {
+    statusEl.textContent = 'Letters must be A-Z, or ? for a blank tile.';
     return;
   }
   if (letters.length > maxLen) {
@@ -1,6 +1,7 @@
 const maxLen = __MAX_LEN__;
 const lengthSelect = document.getElementById('lengthSelect');
 const charRow = document.getElementById('charRow');
+const enumInput = document.getElementById('enumInput');
 const solveBtn = document.getElementById('solveBtn');
 const resetBtn = document.getElementById('resetBtn');
 const statusEl = document.getElementById('status');
@@ -26,6 +27,7 @@ async function readError(resp) {
 
 const state = {
   pattern: '',
+  enumeration: '',
   page: 1,
   pageSize: 50,
   hasMore: false,
@@ -191,7 +193,7 @@ function handleInput(e) {
   const val = target.value;
   if (!val) return;
   const ch = val.slice(-1);
-  if (/^[A-Za-z]$/.test(ch)) {
+  if (/^[A-Za-z0-9@#]$/.test(ch)) {
     target.value = ch.toLowerCase();
   } else {
     target.value = '';
@@ -225,6 +227,7 @@ async function runSolve() {
   const pattern = patternFromInputs();
   if (!pattern) return;
   state.pattern = pattern;
+  state.enumeration = enumInput.value.trim();
   state.page = 1;
   state.hasMore = false;
   state.total = 0;
@@ -238,9 +241,11 @@ function resetAll() {
   lengthSelect.value = 7;
   const len = 7;
   buildInputs(len);
+  enumInput.value = '';
   statusEl.textContent = 'Enter a pattern to search.';
   clearResults();
   state.pattern = '';
+  state.enumeration = '';
   state.page = 1;
   state.hasMore = false;
   state.total = 0;
@@ -292,9 +297,11 @@ function loadPattern(pattern) {
   lengthSelect.value = len;
   buildInputs(len);
   Array.from(charRow.querySelectorAll('input')).forEach((inp, idx) => {
-    inp.value = pattern[idx] && /[a-z]/i.test(pattern[idx]) ? pattern[idx] : '';
+    inp.value = pattern[idx] && /[a-z0-9@#]/i.test(pattern[idx]) ? pattern[idx] : '';
   });
+  enumInput.value = '';
   state.pattern = pattern;
+  state.enumeration = '';
   state.page = 1;
   state.hasMore = false;
   state.total = 0;
@@ -326,7 +333,8 @@ async function fetchPage() {
   statusEl.textContent = `Loading page ${state.page}...`;
   resultsList.innerHTML = '';
   try {
-    const resp = await fetch(`/v1/matches?pattern=${encodeURIComponent(state.pattern)}&page=${state.page}&page_size=${state.pageSize}`);
+    const enumParam = state.enumeration ? `&enum=${encodeURIComponent(state.enumeration)}` : '';
+    const resp = await fetch(`/v1/matches?pattern=${encodeURIComponent(state.pattern)}${enumParam}&page=${state.page}&page_size=${state.pageSize}`);
     if (!resp.ok) {
       const msg = await readError(resp);
       throw new Error(msg);
@@ -339,10 +347,18 @@ async function fetchPage() {
     if (data.items.length === 0) {
       resultsList.innerHTML = '<li class="list-group-item text-muted">No matches found.</li>';
     } else {
-      data.items.forEach(word => {
+      data.items.forEach(item => {
+        const word = item.word;
         const li = document.createElement('li');
-        li.className = 'list-group-item fs-5 word-item';
-        li.textContent = word;
+        li.className = 'list-group-item fs-5 word-item d-flex justify-content-between align-items-center';
+        li.textContent = item.display || word;
+        if (item.score != null) {
+          const badge = document.createElement('span');
+          badge.className = 'badge text-bg-light fw-normal';
+          badge.textContent = item.score;
+          badge.title = 'Word list score';
+          li.appendChild(badge);
+        }
         li.dataset.word = word;
         li.setAttribute('role', 'button');
         li.tabIndex = 0;
@@ -367,6 +383,12 @@ lengthSelect.addEventListener('change', (e) => {
 });
 charRow.addEventListener('input', handleInput);
 charRow.addEventListener('keydown', handleKeydown);
+enumInput.addEventListener('keydown', (e) => {
+  if (e.key === 'Enter') {
+    e.preventDefault();
+    runSolve();
+  }
+});
 solveBtn.addEventListener('click', runSolve);
 resetBtn.addEventListener('click', resetAll);
 resultsList.addEventListener('click', handleResultClick);
@@ -1,27 +1,49 @@
-use std::sync::Arc;
+use std::sync::{Arc, OnceLock};
 
 use axum::body::{Body, to_bytes};
 use axum::http::{Request, StatusCode};
 use tower::util::ServiceExt;
 
+use crosswordsolver_jw::clue::MAX_CLUE_CANDIDATES;
+use crosswordsolver_jw::cryptic::CrypticDb;
 use crosswordsolver_jw::handlers::{AppState, router};
-use crosswordsolver_jw::index::WordIndex;
+use crosswordsolver_jw::index::{Alphabet, WordIndex};
+use crosswordsolver_jw::lists::WordLists;
+use crosswordsolver_jw::reload::IndexStore;
 use wordnet_db::{LoadMode, WordNet};
 use wordnet_morphy::Morphy;
 
 fn make_state() -> Option<AppState> {
     let (wordnet, morphy) = wordnet_fixture()?;
-    let words = b"apple\nangle\nankle\naddle\nample\n";
+    let words = b"apple;50\nangle;40\nankle\naddle;20\nample;30\nhot dog;50\nhotrod\n";
     let tempdir = tempfile::tempdir().unwrap();
     let path = tempdir.path().join("words.txt");
     std::fs::write(&path, words).unwrap();
     let index = WordIndex::build_from_file(&path).unwrap();
+    let theme_path = tempdir.path().join("theme.txt");
+    std::fs::write(&theme_path, "aspic;60\napple;10\npâté;20\n").unwrap();
+    let french = Arc::new(Alphabet::parse("fr").unwrap());
+    let theme = WordIndex::build_from_file_with_alphabet(&theme_path, french).unwrap();
+    let lists = WordLists::new(
+        "main",
+        [
+            ("main".to_string(), IndexStore::new(index, "test", None)),
+            (
+                "theme".to_string(),
+                IndexStore::new(theme, "theme", Some(theme_path)),
+            ),
+        ],
+    )
+    .unwrap();
     Some(AppState {
-        index: Arc::clone(&index),
+        lists: Arc::new(lists),
         wordnet,
         morphy,
         max_page_size: 500,
         disable_cache: false,
+        admin_token: None,
+        cryptic: Arc::new(CrypticDb::builtin()),
+        reverse: Arc::new(OnceLock::new()),
     })
 }
 
@@ -122,7 +144,7 @@ async fn matches_endpoint_rejects_invalid_pattern() {
     let response = app
         .oneshot(
             Request::builder()
-                .uri("/v1/matches?pattern=a__1e")
+                .uri("/v1/matches?pattern=a__%21e")
                 .body(Body::empty())
                 .unwrap(),
         )
@@ -223,3 +245,1102 @@ async fn anagrams_endpoint_rejects_impossible_pattern() {
             .contains("pattern requires")
     );
 }
+
+#[tokio::test]
+async fn matches_endpoint_ranks_by_score() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=a__le&min_score=30")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let items = body["items"].as_array().unwrap();
+    let words: Vec<&str> = items.iter().map(|i| i["word"].as_str().unwrap()).collect();
+    assert_eq!(words, vec!["apple", "angle", "ample"]);
+    assert_eq!(items[0]["score"], 50);
+}
+
+#[tokio::test]
+async fn matches_endpoint_rejects_unknown_sort() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=a__le&sort=length")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn matches_endpoint_filters_by_enumeration() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=hot___&enum=3,3")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "hotdog");
+    assert_eq!(body["items"][0]["display"], "hot dog");
+
+    let app = router(make_state().unwrap());
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=hot___&enum=3,4")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn matches_endpoint_supports_isomorph_patterns() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=ABBCD&isomorph=true&sort=alpha")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let words: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["addle", "apple"]);
+}
+
+#[tokio::test]
+async fn matches_endpoint_supports_letter_classes() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=a%5Bmn%5D%5B%5Ek%5Dle&sort=alpha")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let words: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["ample", "angle"]);
+}
+
+#[tokio::test]
+async fn matches_endpoint_supports_wildcards_across_lengths() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=*d*")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let words: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["hotdog", "addle", "hotrod"]);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=*d*&min_len=6&max_len=5")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn anagrams_endpoint_supports_subset_mode() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/anagrams?letters=hotdgro%3F&mode=subset&min_len=5&sort=alpha")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["total"], 2);
+    let groups = body["groups"].as_array().unwrap();
+    assert_eq!(groups.len(), 1);
+    assert_eq!(groups[0]["len"], 6);
+    let words: Vec<&str> = groups[0]["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["hotdog", "hotrod"]);
+}
+
+#[tokio::test]
+async fn phrase_anagrams_endpoint_splits_letters() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/v1/anagrams/phrases?letters=apple%20ankle&enum=5,5")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["truncated"], false);
+    let phrases: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["display"].as_str().unwrap())
+        .collect();
+    assert_eq!(phrases, vec!["ankle apple", "apple ankle"]);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/anagrams/phrases?letters=appleankle&max_words=9")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn grid_fill_endpoint_fills_slots() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let fill = |grid: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/grid/fill")
+            .header("content-type", "application/json")
+            .body(Body::from(format!(r#"{{"grid": {grid}}}"#)))
+            .unwrap()
+    };
+
+    let response = app.clone().oneshot(fill(r#"["an..e"]"#)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["filled"], true);
+    assert_eq!(body["grid"][0], "angle");
+    assert_eq!(body["entries"][0]["direction"], "across");
+    assert_eq!(body["entries"][0]["word"], "angle");
+
+    let response = app.clone().oneshot(fill(r#"["zz..."]"#)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["filled"], false);
+
+    let response = app.oneshot(fill(r#"["ab", "c"]"#)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn phrase_anagrams_and_grid_fill_use_the_list() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: String| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            )
+        }
+    };
+    let fill = |body: &str| {
+        let app = app.clone();
+        let request = Request::builder()
+            .method("POST")
+            .uri("/v1/grid/fill")
+            .header("content-type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap();
+        async move {
+            let response = app.oneshot(request).await.unwrap();
+            let status = response.status();
+            let bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&bytes).unwrap(),
+            )
+        }
+    };
+
+    let (_, body) = get_json("/v1/anagrams/phrases?letters=aspicapple&list=theme".into()).await;
+    assert_eq!(body["items"][0]["display"], "apple aspic");
+    let (_, body) = get_json("/v1/anagrams/phrases?letters=aspicapple".into()).await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 0);
+    let (status, _) = get_json("/v1/anagrams/phrases?letters=apple&list=nope".into()).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = fill(r#"{"grid": ["asp.c"], "list": "theme"}"#).await;
+    assert_eq!(body["filled"], true);
+    assert_eq!(body["grid"][0], "aspic");
+    let (_, body) = fill(r#"{"grid": ["asp.c"]}"#).await;
+    assert_eq!(body["filled"], false);
+    let (status, _) = fill(r#"{"grid": ["asp.c"], "list": "nope"}"#).await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // Pre-filled letters are read with the list's alphabet.
+    let (_, body) = fill(r#"{"grid": ["pât."], "list": "theme"}"#).await;
+    assert_eq!(body["grid"][0], "pate");
+    assert_eq!(body["entries"][0]["display"], "pâté");
+}
+
+#[tokio::test]
+async fn matches_endpoint_filters_by_crossings() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=a____&cross=2:hot_o_:5&sort=alpha")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let words: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|i| i["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, vec!["addle", "angle"]);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/v1/matches?pattern=a____&cross=2:hot_o_:5&crossing_mode=annotate&sort=alpha")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["items"][0]["word"], "addle");
+    assert_eq!(body["items"][0]["crossings"], serde_json::json!([1]));
+    assert_eq!(body["items"][1]["word"], "ample");
+    assert_eq!(body["items"][1]["viable"], false);
+}
+
+#[tokio::test]
+async fn admin_reload_swaps_list_and_updates_healthz() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "apple\n").unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(
+        index,
+        "v1",
+        Some(path.clone()),
+    )));
+    state.admin_token = Some("secret".into());
+    let app = router(state);
+
+    let reload = |token: &str| {
+        Request::builder()
+            .method("POST")
+            .uri("/admin/reload")
+            .header("authorization", format!("Bearer {token}"))
+            .body(Body::empty())
+            .unwrap()
+    };
+    let response = app.clone().oneshot(reload("wrong")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    std::fs::write(&path, "apple\nangle\nankle\n").unwrap();
+    let response = app.clone().oneshot(reload("secret")).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/healthz")
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["generation"], 2);
+    assert_eq!(body["words"], 3);
+    assert_ne!(body["list_version"], "v1");
+}
+
+#[tokio::test]
+async fn list_parameter_selects_word_list() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/matches?pattern=a____").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 5);
+
+    let (_, body) = get_json("/v1/matches?pattern=a____&list=theme").await;
+    let words: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, ["aspic", "apple"]);
+
+    let (_, body) = get_json("/v1/anagrams?letters=picas&list=theme").await;
+    assert_eq!(body["items"][0]["word"], "aspic");
+
+    let (_, body) = get_json("/v1/matches?pattern=pat_&list=theme").await;
+    assert_eq!(body["items"][0]["word"], "pate");
+    assert_eq!(body["items"][0]["display"], "pâté");
+
+    let (status, body) = get_json("/v1/matches?pattern=a____&list=nope").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("/v1/lists"));
+
+    let (status, body) = get_json("/v1/lists").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["default"], "main");
+    let lists = body["lists"].as_array().unwrap();
+    assert_eq!(lists.len(), 2);
+    assert_eq!(lists[0]["name"], "main");
+    assert_eq!(lists[0]["default"], true);
+    assert_eq!(lists[0]["words"], 7);
+    assert_eq!(lists[1]["name"], "theme");
+    assert_eq!(lists[1]["words"], 3);
+    assert_eq!(lists[0]["alphabet"], "en");
+    assert_eq!(lists[1]["alphabet"], "fr");
+    assert_eq!(lists[1]["source"], "theme.txt");
+}
+
+#[tokio::test]
+async fn matches_post_applies_overlay() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let post = |body: &'static str| {
+        Request::builder()
+            .method("POST")
+            .uri("/v1/matches")
+            .header("content-type", "application/json")
+            .body(Body::from(body))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(post(
+            r#"{"pattern": "a____", "page_size": 10, "allow": ["aspic;45"], "deny": ["angle"]}"#,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("cache-control").is_none());
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    let words: Vec<_> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, ["apple", "aspic", "ample", "addle", "ankle"]);
+    assert_eq!(body["total"], 5);
+
+    let response = app
+        .oneshot(post(r#"{"pattern": "a____", "allow": ["not a w0rd"]}"#))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn regex_endpoint_searches_and_rejects_bad_patterns() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+    let words = |body: &serde_json::Value| -> Vec<String> {
+        body["items"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .map(|item| item["word"].as_str().unwrap().to_string())
+            .collect()
+    };
+
+    let (status, body) = get_json("/v1/regex?pattern=%28.%29%5C1").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(words(&body), ["apple", "addle"]);
+
+    let (_, body) = get_json("/v1/regex?pattern=%5Ea.*le%24&sort=alpha&page_size=2").await;
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["has_more"], true);
+    assert_eq!(words(&body), ["addle", "ample"]);
+
+    let (_, body) = get_json("/v1/regex?pattern=%5Ehot&max_len=6").await;
+    assert_eq!(body["items"][0]["display"], "hot dog");
+
+    let (status, body) = get_json("/v1/regex?pattern=%28ab").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().starts_with("invalid regex"));
+}
+
+#[tokio::test]
+async fn wordle_endpoint_filters_and_suggests() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    // a n g l e against "ankle": a g, n g, g b, l g, e g.
+    let (status, body) = get_json("/v1/wordle?guesses=angle:GGBGG").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["len"], 5);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "ankle");
+    assert_eq!(body["suggestions"][0]["word"], "ankle");
+    assert_eq!(body["suggestions"][0]["candidate"], true);
+
+    let (_, body) = get_json("/v1/wordle?len=5&suggest=2").await;
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["suggestions"].as_array().unwrap().len(), 2);
+
+    let (status, body) = get_json("/v1/wordle?guesses=angle:GGX").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("feedback"));
+}
+
+#[tokio::test]
+async fn ladder_endpoint_finds_shortest_chain() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/ladder?from=angle&to=ankle").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["steps"], 1);
+    assert_eq!(body["paths"][0][1]["word"], "ankle");
+    assert_eq!(body["truncated"], false);
+
+    let (_, body) = get_json("/v1/ladder?from=apple&to=ankle").await;
+    assert!(body["steps"].is_null());
+    assert!(body["paths"].as_array().unwrap().is_empty());
+
+    let (status, body) = get_json("/v1/ladder?from=angle&to=hotrod").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    assert!(body["error"].as_str().unwrap().contains("same length"));
+}
+
+#[tokio::test]
+async fn matches_endpoint_allows_mismatches() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/matches?pattern=ap_le&max_mismatches=1&sort=alpha").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 5);
+    assert_eq!(body["items"][0]["word"], "apple");
+    assert_eq!(body["items"][0]["mismatches"], serde_json::json!([]));
+    assert_eq!(body["items"][1]["word"], "addle");
+    assert_eq!(body["items"][1]["mismatches"], serde_json::json!([1]));
+
+    let (status, body) =
+        get_json("/v1/matches?pattern=hotdo&max_mismatches=1&distance=levenshtein").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "hotdog");
+    assert_eq!(body["items"][0]["mismatches"], serde_json::json!([5]));
+    assert!(body.get("truncated").is_none());
+
+    let (status, _) = get_json("/v1/matches?pattern=ap_le&max_mismatches=9").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) =
+        get_json("/v1/matches?pattern=ap_le&max_mismatches=1&distance=levenshtein&enum=2,3").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    // Edits shift the slots that digit variables tie together.
+    let (status, _) =
+        get_json("/v1/matches?pattern=1__1&max_mismatches=1&distance=levenshtein").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn hidden_endpoint_finds_words_in_clues() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) =
+        get_json("/v1/cryptic/hidden?clue=Crab%20angle%2C%20lpp%41&pattern=a____").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    assert_eq!(body["items"][0]["word"], "angle");
+    assert_eq!(body["items"][0]["start"], 5);
+    assert_eq!(body["items"][0]["text"], "angle");
+    assert_eq!(body["items"][0]["reversed"], false);
+    assert_eq!(body["items"][1]["word"], "apple");
+    assert_eq!(body["items"][1]["text"], "e, lppA");
+    assert_eq!(body["items"][1]["reversed"], true);
+
+    let (status, _) = get_json("/v1/cryptic/hidden?clue=%20&pattern=a____").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn matches_endpoint_ranks_by_clue() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "cot;60\ncat;50\ncut;40\n").unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/matches?pattern=c_t&clue=Dog,%20say").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 3);
+    assert_eq!(body["ranked"], 3);
+    assert_eq!(body["items"][0]["word"], "cat");
+    assert_eq!(body["items"][0]["clue_score"], 1.0);
+    assert_eq!(
+        body["items"][0]["reasons"],
+        serde_json::json!(["shares a sense with \"dog\""])
+    );
+    // Words WordNet cannot place stay in their usual order after.
+    assert_eq!(body["items"][1]["word"], "cot");
+    assert!(body["items"][1].get("clue_score").is_none());
+
+    let (_, body) = get_json("/v1/matches?pattern=c_t&clue=dog&page=2&page_size=2").await;
+    assert_eq!(body["items"].as_array().unwrap().len(), 1);
+    assert_eq!(body["items"][0]["word"], "cut");
+    assert_eq!(body["has_more"], false);
+}
+
+#[tokio::test]
+async fn matches_clue_ranking_reports_left_out_matches() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    let words: Vec<String> = (0..=MAX_CLUE_CANDIDATES)
+        .map(|n| {
+            let letter = |k: usize| (b'a' + (n / k % 26) as u8) as char;
+            format!("c{}{}{}", letter(676), letter(26), letter(1))
+        })
+        .collect();
+    std::fs::write(&path, words.join("\n")).unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+
+    let last_page = MAX_CLUE_CANDIDATES / 50;
+    let uri = format!("/v1/matches?pattern=c___&clue=dog&page={last_page}&page_size=50");
+    let response = app
+        .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert!(response.headers().get("cache-control").is_none());
+    let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+    let body: serde_json::Value = serde_json::from_slice(&body_bytes).unwrap();
+    assert_eq!(body["total"], MAX_CLUE_CANDIDATES + 1);
+    assert_eq!(body["ranked"], MAX_CLUE_CANDIDATES);
+    assert_eq!(body["items"].as_array().unwrap().len(), 50);
+    assert_eq!(body["has_more"], false);
+}
+
+#[tokio::test]
+async fn synonyms_endpoint_filters_by_pattern() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "animal;70\ncat\n").unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/wordnet/synonyms?word=dogs&pattern=a_i___").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["lemmas"], serde_json::json!(["dog"]));
+    assert_eq!(body["total"], 1);
+    let item = &body["items"][0];
+    assert_eq!(item["word"], "animal");
+    assert_eq!(item["enumeration"], serde_json::json!([6]));
+    assert_eq!(item["path"], serde_json::json!(["hypernyms"]));
+    assert_eq!(item["in_list"], true);
+    assert_eq!(item["score"], 70);
+
+    let (_, body) = get_json("/v1/wordnet/synonyms?word=dog&pattern=______&depth=0").await;
+    assert_eq!(body["total"], 0);
+    let (_, body) = get_json("/v1/wordnet/synonyms?word=dog&pattern=c__").await;
+    assert_eq!(body["items"][0]["word"], "cat");
+    assert_eq!(body["items"][0]["path"], serde_json::json!([]));
+    let (_, body) = get_json("/v1/wordnet/synonyms?word=dog&pattern=c__&enum=1,2").await;
+    assert_eq!(body["total"], 0);
+
+    let (status, _) = get_json("/v1/wordnet/synonyms?word=dog&pattern=c__&depth=4").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json("/v1/wordnet/synonyms?word=dog&pattern=c__&relations=cousins").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn reverse_endpoint_finds_words_from_descriptions() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "cat;40\nanimal\n").unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/wordnet/reverse?q=a%20domestic%20mammal").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 2);
+    let words: Vec<&str> = body["items"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|item| item["word"].as_str().unwrap())
+        .collect();
+    assert_eq!(words, ["dog", "cat"]);
+    assert_eq!(body["items"][0]["in_list"], false);
+    assert_eq!(
+        body["items"][0]["matched"],
+        serde_json::json!(["domestic", "mammal"])
+    );
+
+    // Filters keep only entries of the list.
+    let (_, body) = get_json("/v1/wordnet/reverse?q=domestic%20mammal&len=3").await;
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "cat");
+    assert_eq!(body["items"][0]["score"], 40);
+    let (_, body) = get_json("/v1/wordnet/reverse?q=living%20creature&pattern=a_____").await;
+    assert_eq!(body["items"][0]["word"], "animal");
+
+    let (status, _) = get_json("/v1/wordnet/reverse?q=mammal&pattern=c__&len=4").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+    let (status, _) = get_json("/v1/wordnet/reverse?q=of%20the").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn anagrams_fix_letters_beyond_z() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "año;40\nnoa\n").unwrap();
+    let spanish = Arc::new(Alphabet::parse("es").unwrap());
+    let index = WordIndex::build_from_file_with_alphabet(&path, spanish).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    // "ñ" is the 27th cell of the Spanish alphabet.
+    let (status, body) = get_json("/v1/anagrams?letters=o%C3%B1a&pattern=_%C3%B1_").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["display"], "año");
+    let (status, _) = get_json("/v1/anagrams?letters=ona&pattern=_%C3%B1_").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn wordplay_endpoint_returns_parse_trees() {
+    let Some(mut state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let tempdir = tempfile::tempdir().unwrap();
+    let path = tempdir.path().join("words.txt");
+    std::fs::write(&path, "hot dog;50\nhotrod\nhot;40\nrod;30\n").unwrap();
+    let index = WordIndex::build_from_file(&path).unwrap();
+    state.lists = Arc::new(WordLists::single(IndexStore::new(index, "v1", None)));
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/cryptic/wordplay?pattern=hot___").await;
+    assert_eq!(status, StatusCode::OK);
+    // "hot dog" has no "dog" to be built from.
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "hotrod");
+    assert_eq!(
+        body["items"][0]["parses"],
+        serde_json::json!([{
+            "type": "charade",
+            "parts": [
+                {"type": "word", "word": "hot", "display": "hot", "score": 40},
+                {"type": "word", "word": "rod", "display": "rod", "score": 30},
+            ],
+        }])
+    );
+
+    // Abbreviations take the place of entries with the same letters.
+    let (_, body) = get_json("/v1/cryptic/wordplay?pattern=hotrod&abbrev=gun").await;
+    assert_eq!(
+        body["items"][0]["parses"][0]["parts"][1],
+        serde_json::json!({"type": "fragment", "letters": "rod", "source": "gun"})
+    );
+    let (status, _) = get_json("/v1/cryptic/wordplay?pattern=hotrod&abbrev=zebra").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+
+    let (_, body) = get_json("/v1/cryptic/wordplay?pattern=hot___&kind=container").await;
+    assert_eq!(body["total"], 0);
+    let (status, _) = get_json("/v1/cryptic/wordplay?pattern=hot___&kind=spoonerism").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn cryptic_abbreviations_feed_lookups_and_anagrams() {
+    let Some(state) = make_state() else {
+        eprintln!("skipping: WORDNET_DIR not set");
+        return;
+    };
+    let app = router(state);
+    let get_json = |uri: &'static str| {
+        let app = app.clone();
+        async move {
+            let response = app
+                .oneshot(Request::builder().uri(uri).body(Body::empty()).unwrap())
+                .await
+                .unwrap();
+            let status = response.status();
+            let body_bytes = to_bytes(response.into_body(), 1024 * 1024).await.unwrap();
+            (
+                status,
+                serde_json::from_slice::<serde_json::Value>(&body_bytes).unwrap(),
+            )
+        }
+    };
+
+    let (status, body) = get_json("/v1/cryptic/abbrev?word=About").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["abbreviations"], serde_json::json!(["c", "ca", "re"]));
+    assert_eq!(
+        body["indicators"],
+        serde_json::json!(["anagram", "container"])
+    );
+    let (_, body) = get_json("/v1/cryptic/abbrev?word=zebra").await;
+    assert!(body["abbreviations"].as_array().unwrap().is_empty());
+
+    let (status, body) = get_json("/v1/anagrams?letters=nage&abbrev=learner").await;
+    assert_eq!(status, StatusCode::OK);
+    assert_eq!(body["total"], 1);
+    assert_eq!(body["items"][0]["word"], "angle");
+    assert_eq!(
+        body["items"][0]["fragments"],
+        serde_json::json!([{"letters": "l", "source": "learner"}])
+    );
+
+    let (status, _) = get_json("/v1/anagrams?letters=nage&abbrev=zebra").await;
+    assert_eq!(status, StatusCode::BAD_REQUEST);
+}
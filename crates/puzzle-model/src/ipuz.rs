@@ -0,0 +1,296 @@
+//! ipuz (JSON) reader and writer for the crossword kind.
+//!
+//! Cells in `puzzle` are clue numbers, `0` for unnumbered open cells, the
+//! `block` marker, `null` for omitted cells, or `{"cell": .., "style": ..}`
+//! objects. `solution` and `saved` hold answers and the current fill.
+
+use serde_json::{Map, Value, json};
+
+use crate::{
+    Cell, Clue, Direction, MAX_GRID_SIZE, Puzzle, PuzzleError, grid_len, sort_clues,
+    split_enumeration,
+};
+
+fn err(msg: impl Into<String>) -> PuzzleError {
+    PuzzleError::Ipuz(msg.into())
+}
+
+impl Puzzle {
+    pub fn from_ipuz(text: &str) -> Result<Self, PuzzleError> {
+        let root: Value = serde_json::from_str(text).map_err(|e| err(e.to_string()))?;
+        let is_crossword = root["kind"].as_array().is_some_and(|kinds| {
+            kinds
+                .iter()
+                .any(|k| k.as_str().is_some_and(|k| k.contains("crossword")))
+        });
+        if !is_crossword {
+            return Err(err("kind is not a crossword"));
+        }
+        let width = root["dimensions"]["width"]
+            .as_u64()
+            .ok_or_else(|| err("missing dimensions.width"))? as usize;
+        let height = root["dimensions"]["height"]
+            .as_u64()
+            .ok_or_else(|| err("missing dimensions.height"))? as usize;
+        let len = grid_len(width, height).ok_or_else(|| {
+            err(format!(
+                "grid must be between 1 and {MAX_GRID_SIZE} cells wide and high"
+            ))
+        })?;
+        let block = root["block"].as_str().unwrap_or("#");
+        let empty = match &root["empty"] {
+            Value::String(s) => s.clone(),
+            Value::Number(n) => n.to_string(),
+            _ => "0".to_string(),
+        };
+
+        let layout = grid(&root["puzzle"], width, height)
+            .ok_or_else(|| err("puzzle grid does not match dimensions"))?;
+        let mut cells = Vec::with_capacity(len);
+        for value in layout {
+            let style = value.get("style");
+            let value = value.get("cell").unwrap_or(value);
+            let is_block = value.is_null() || value.as_str() == Some(block);
+            cells.push(Cell {
+                block: is_block,
+                circled: style.is_some_and(|s| s["shapebg"] == "circle"),
+                ..Cell::default()
+            });
+        }
+        for (key, apply) in [("solution", true), ("saved", false)] {
+            let Some(values) = grid(&root[key], width, height) else {
+                continue;
+            };
+            for (cell, value) in cells.iter_mut().zip(values) {
+                let value = value.get("value").unwrap_or(value);
+                let letters = match value {
+                    Value::String(s) if s != block && *s != empty && !s.is_empty() => s.clone(),
+                    _ => continue,
+                };
+                if cell.block {
+                    continue;
+                }
+                if apply {
+                    cell.solution = Some(letters);
+                } else {
+                    cell.fill = Some(letters);
+                }
+            }
+        }
+
+        let mut clues = Vec::new();
+        if let Some(lists) = root["clues"].as_object() {
+            for (key, list) in lists {
+                let direction = match key.split(':').next().unwrap_or_default() {
+                    "Across" => Direction::Across,
+                    "Down" => Direction::Down,
+                    _ => continue,
+                };
+                for item in list.as_array().into_iter().flatten() {
+                    if let Some(clue) = parse_clue(item, direction) {
+                        clues.push(clue);
+                    }
+                }
+            }
+        }
+        sort_clues(&mut clues);
+
+        Ok(Puzzle {
+            title: string(&root["title"]),
+            author: string(&root["author"]),
+            copyright: string(&root["copyright"]),
+            notes: string(&root["notes"]),
+            width,
+            height,
+            cells,
+            clues,
+        })
+    }
+
+    pub fn to_ipuz(&self) -> String {
+        let numbers = self.numbers();
+        let rows = |f: &dyn Fn(usize) -> Value| -> Value {
+            (0..self.height)
+                .map(|row| {
+                    (0..self.width)
+                        .map(|col| f(row * self.width + col))
+                        .collect::<Value>()
+                })
+                .collect()
+        };
+        let puzzle = rows(&|i| {
+            let cell = &self.cells[i];
+            if cell.block {
+                return json!("#");
+            }
+            let number = json!(numbers[i].unwrap_or(0));
+            if cell.circled {
+                json!({ "cell": number, "style": { "shapebg": "circle" } })
+            } else {
+                number
+            }
+        });
+        let solution = rows(&|i| {
+            let cell = &self.cells[i];
+            if cell.block {
+                json!("#")
+            } else {
+                cell.solution.as_deref().map_or(Value::Null, |s| json!(s))
+            }
+        });
+
+        let mut root = Map::new();
+        root.insert("version".into(), json!("http://ipuz.org/v2"));
+        root.insert("kind".into(), json!(["http://ipuz.org/crossword#1"]));
+        for (key, value) in [
+            ("title", &self.title),
+            ("author", &self.author),
+            ("copyright", &self.copyright),
+            ("notes", &self.notes),
+        ] {
+            if let Some(value) = value {
+                root.insert(key.into(), json!(value));
+            }
+        }
+        root.insert(
+            "dimensions".into(),
+            json!({ "width": self.width, "height": self.height }),
+        );
+        root.insert("block".into(), json!("#"));
+        root.insert("empty".into(), json!(0));
+        root.insert("puzzle".into(), puzzle);
+        if self.cells.iter().any(|c| c.solution.is_some()) {
+            root.insert("solution".into(), solution);
+        }
+        if self.cells.iter().any(|c| c.fill.is_some()) {
+            let saved = rows(&|i| {
+                let cell = &self.cells[i];
+                if cell.block {
+                    json!("#")
+                } else {
+                    json!(cell.fill.as_deref().unwrap_or_default())
+                }
+            });
+            root.insert("saved".into(), saved);
+        }
+        let clue_list = |direction: Direction| -> Value {
+            self.clues
+                .iter()
+                .filter(|c| c.direction == direction)
+                .map(|c| {
+                    let mut clue = json!({ "number": c.number, "clue": c.text });
+                    if let Some(enumeration) = &c.enumeration {
+                        clue["enumeration"] = json!(enumeration);
+                    }
+                    clue
+                })
+                .collect()
+        };
+        root.insert(
+            "clues".into(),
+            json!({ "Across": clue_list(Direction::Across), "Down": clue_list(Direction::Down) }),
+        );
+        serde_json::to_string_pretty(&Value::Object(root)).expect("ipuz value serializes")
+    }
+}
+
+/// Flatten a `height` x `width` array of arrays, row-major.
+fn grid(value: &Value, width: usize, height: usize) -> Option<Vec<&Value>> {
+    let rows = value.as_array()?;
+    if rows.len() != height {
+        return None;
+    }
+    let mut cells = Vec::with_capacity(width * height);
+    for row in rows {
+        let row = row.as_array()?;
+        if row.len() != width {
+            return None;
+        }
+        cells.extend(row);
+    }
+    Some(cells)
+}
+
+/// Accepts `[number, "text"]`, `{"number": .., "clue": .., "enumeration": ..}`.
+fn parse_clue(item: &Value, direction: Direction) -> Option<Clue> {
+    let (number, text, enumeration) = match item {
+        Value::Array(parts) => (parts.first()?, parts.get(1)?.as_str()?, None),
+        Value::Object(fields) => (
+            fields.get("number")?,
+            fields.get("clue")?.as_str()?,
+            fields.get("enumeration").and_then(Value::as_str),
+        ),
+        _ => return None,
+    };
+    let number = match number {
+        Value::Number(n) => u32::try_from(n.as_u64()?).ok()?,
+        Value::String(s) => {
+            let digits: String = s.chars().take_while(char::is_ascii_digit).collect();
+            digits.parse().ok()?
+        }
+        _ => return None,
+    };
+    let (text, trailing) = match enumeration {
+        Some(_) => (text, None),
+        None => split_enumeration(text),
+    };
+    Some(Clue {
+        number,
+        direction,
+        text: text.to_string(),
+        enumeration: enumeration.or(trailing).map(str::to_string),
+    })
+}
+
+fn string(value: &Value) -> Option<String> {
+    value.as_str().filter(|s| !s.is_empty()).map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn round_trips_ipuz() {
+        let puzzle = sample();
+        let parsed = Puzzle::from_ipuz(&puzzle.to_ipuz()).unwrap();
+        assert_eq!(parsed, puzzle);
+    }
+
+    #[test]
+    fn reads_compact_ipuz() {
+        let text = r##"{
+            "version": "http://ipuz.org/v2",
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": {"width": 2, "height": 2},
+            "puzzle": [[1, 2], [3, null]],
+            "solution": [["H", "I"], [{"value": "O"}, null]],
+            "clues": {
+                "Across": [[1, "Greeting (2)"]],
+                "Down:Downs": [["1", "Laugh sound"]]
+            }
+        }"##;
+        let puzzle = Puzzle::from_ipuz(text).unwrap();
+        assert!(puzzle.cell(1, 1).block);
+        assert_eq!(puzzle.cell(1, 0).solution.as_deref(), Some("O"));
+        assert_eq!(puzzle.clues[0].enumeration.as_deref(), Some("2"));
+        assert_eq!(puzzle.clues[1].direction, Direction::Down);
+        assert!(Puzzle::from_ipuz(r#"{"kind": ["http://ipuz.org/sudoku#1"]}"#).is_err());
+    }
+
+    #[test]
+    fn rejects_oversized_grid() {
+        let text = r#"{
+            "kind": ["http://ipuz.org/crossword#1"],
+            "dimensions": {"width": 100000, "height": 100000},
+            "puzzle": []
+        }"#;
+        assert!(matches!(Puzzle::from_ipuz(text), Err(PuzzleError::Ipuz(_))));
+        let text = text.replace("100000", "18446744073709551615");
+        assert!(matches!(
+            Puzzle::from_ipuz(&text),
+            Err(PuzzleError::Ipuz(_))
+        ));
+    }
+}
@@ -0,0 +1,302 @@
+//! Crossword Compiler JPZ (XML) reader and writer.
+//!
+//! Only plain XML is handled; zip-compressed `.jpz` files need unpacking
+//! first. Elements are matched by local name, so the namespaces used by
+//! different exporters do not matter.
+
+use std::fmt::Write;
+
+use roxmltree::{Document, Node};
+
+use crate::{
+    Cell, Clue, Direction, MAX_GRID_SIZE, Puzzle, PuzzleError, grid_len, sort_clues,
+    split_enumeration,
+};
+
+fn err(msg: impl Into<String>) -> PuzzleError {
+    PuzzleError::Jpz(msg.into())
+}
+
+impl Puzzle {
+    pub fn from_jpz(text: &str) -> Result<Self, PuzzleError> {
+        let doc = Document::parse(text).map_err(|e| err(e.to_string()))?;
+        let root = doc.root_element();
+        let grid = find(root, "grid").ok_or_else(|| err("missing <grid>"))?;
+        let width = dimension(grid, "width")?;
+        let height = dimension(grid, "height")?;
+
+        let len = grid_len(width, height).ok_or_else(|| {
+            err(format!(
+                "grid must be between 1 and {MAX_GRID_SIZE} cells wide and high"
+            ))
+        })?;
+        let mut cells = vec![Cell::default(); len];
+        for node in grid.children().filter(|n| n.has_tag_name_local("cell")) {
+            let (Some(x), Some(y)) = (coord(node, "x"), coord(node, "y")) else {
+                continue;
+            };
+            if x >= width || y >= height {
+                return Err(err(format!("cell {},{} is outside the grid", x + 1, y + 1)));
+            }
+            let cell = &mut cells[y * width + x];
+            if matches!(node.attribute("type"), Some("block" | "void")) {
+                *cell = Cell::block();
+                continue;
+            }
+            cell.solution = node
+                .attribute("solution")
+                .filter(|s| !s.is_empty())
+                .map(str::to_string);
+            cell.fill = node
+                .attribute("solve-state")
+                .filter(|s| !s.is_empty())
+                .map(str::to_string);
+            cell.circled = node.attribute("background-shape") == Some("circle");
+        }
+
+        let mut clues = Vec::new();
+        let lists = root.descendants().filter(|n| n.has_tag_name_local("clues"));
+        for (i, list) in lists.enumerate() {
+            let title = list
+                .children()
+                .find(|n| n.has_tag_name_local("title"))
+                .map(text_of)
+                .unwrap_or_default()
+                .to_ascii_lowercase();
+            let direction = if title.contains("across") {
+                Direction::Across
+            } else if title.contains("down") || i > 0 {
+                Direction::Down
+            } else {
+                Direction::Across
+            };
+            for node in list.children().filter(|n| n.has_tag_name_local("clue")) {
+                let Some(number) = node
+                    .attribute("number")
+                    .and_then(|n| n.trim().parse::<u32>().ok())
+                else {
+                    continue;
+                };
+                let text = text_of(node);
+                let (text, trailing) = match node.attribute("format") {
+                    Some(_) => (text.trim(), None),
+                    None => split_enumeration(&text),
+                };
+                clues.push(Clue {
+                    number,
+                    direction,
+                    text: text.to_string(),
+                    enumeration: node.attribute("format").or(trailing).map(str::to_string),
+                });
+            }
+        }
+        sort_clues(&mut clues);
+
+        let metadata = find(root, "metadata");
+        let meta = |name: &str| {
+            metadata
+                .and_then(|m| m.children().find(|n| n.has_tag_name_local(name)))
+                .map(text_of)
+                .map(|t| t.trim().to_string())
+                .filter(|t| !t.is_empty())
+        };
+        Ok(Puzzle {
+            title: meta("title"),
+            author: meta("creator"),
+            copyright: meta("copyright"),
+            notes: meta("description"),
+            width,
+            height,
+            cells,
+            clues,
+        })
+    }
+
+    pub fn to_jpz(&self) -> String {
+        let numbers = self.numbers();
+        let mut out = String::new();
+        out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        out.push_str(
+            "<crossword-compiler-applet xmlns=\"http://crossword.info/xml/crossword-compiler-applet\">\n",
+        );
+        out.push_str(
+            "<rectangular-puzzle xmlns=\"http://crossword.info/xml/rectangular-puzzle\">\n",
+        );
+        out.push_str("<metadata>\n");
+        for (tag, value) in [
+            ("title", &self.title),
+            ("creator", &self.author),
+            ("copyright", &self.copyright),
+            ("description", &self.notes),
+        ] {
+            if let Some(value) = value {
+                let _ = writeln!(out, "<{tag}>{}</{tag}>", escape(value));
+            }
+        }
+        out.push_str("</metadata>\n<crossword>\n");
+        let _ = writeln!(
+            out,
+            "<grid width=\"{}\" height=\"{}\">",
+            self.width, self.height
+        );
+        for row in 0..self.height {
+            for col in 0..self.width {
+                let i = row * self.width + col;
+                let cell = &self.cells[i];
+                let _ = write!(out, "<cell x=\"{}\" y=\"{}\"", col + 1, row + 1);
+                if cell.block {
+                    out.push_str(" type=\"block\"/>\n");
+                    continue;
+                }
+                if let Some(solution) = &cell.solution {
+                    let _ = write!(out, " solution=\"{}\"", escape(solution));
+                }
+                if let Some(number) = numbers[i] {
+                    let _ = write!(out, " number=\"{number}\"");
+                }
+                if let Some(fill) = &cell.fill {
+                    let _ = write!(out, " solve-state=\"{}\"", escape(fill));
+                }
+                if cell.circled {
+                    out.push_str(" background-shape=\"circle\"");
+                }
+                out.push_str("/>\n");
+            }
+        }
+        out.push_str("</grid>\n");
+
+        let slots = self.slots();
+        for (id, slot) in slots.iter().enumerate() {
+            let (x, y) = match slot.direction {
+                Direction::Across => (
+                    format!("{}-{}", slot.col + 1, slot.col + slot.len),
+                    (slot.row + 1).to_string(),
+                ),
+                Direction::Down => (
+                    (slot.col + 1).to_string(),
+                    format!("{}-{}", slot.row + 1, slot.row + slot.len),
+                ),
+            };
+            let _ = writeln!(out, "<word id=\"{}\" x=\"{x}\" y=\"{y}\"/>", id + 1);
+        }
+        for (direction, title) in [(Direction::Across, "Across"), (Direction::Down, "Down")] {
+            let _ = writeln!(
+                out,
+                "<clues ordering=\"normal\"><title><b>{title}</b></title>"
+            );
+            for clue in self.clues.iter().filter(|c| c.direction == direction) {
+                out.push_str("<clue");
+                if let Some(id) = slots
+                    .iter()
+                    .position(|s| s.number == clue.number && s.direction == direction)
+                {
+                    let _ = write!(out, " word=\"{}\"", id + 1);
+                }
+                let _ = write!(out, " number=\"{}\"", clue.number);
+                if let Some(enumeration) = &clue.enumeration {
+                    let _ = write!(out, " format=\"{}\"", escape(enumeration));
+                }
+                let _ = writeln!(out, ">{}</clue>", escape(&clue.text));
+            }
+            out.push_str("</clues>\n");
+        }
+        out.push_str("</crossword>\n</rectangular-puzzle>\n</crossword-compiler-applet>\n");
+        out
+    }
+}
+
+trait LocalName {
+    fn has_tag_name_local(&self, name: &str) -> bool;
+}
+
+impl LocalName for Node<'_, '_> {
+    fn has_tag_name_local(&self, name: &str) -> bool {
+        self.is_element() && self.tag_name().name() == name
+    }
+}
+
+fn find<'a, 'input>(root: Node<'a, 'input>, name: &str) -> Option<Node<'a, 'input>> {
+    root.descendants().find(|n| n.has_tag_name_local(name))
+}
+
+/// All text inside a node, including text nested in markup like `<b>`.
+fn text_of(node: Node<'_, '_>) -> String {
+    node.descendants()
+        .filter(|n| n.is_text())
+        .filter_map(|n| n.text())
+        .collect()
+}
+
+fn dimension(grid: Node<'_, '_>, name: &str) -> Result<usize, PuzzleError> {
+    grid.attribute(name)
+        .and_then(|v| v.parse::<usize>().ok())
+        .filter(|v| *v > 0)
+        .ok_or_else(|| err(format!("missing grid {name}")))
+}
+
+/// A 1-based coordinate attribute as a 0-based index.
+fn coord(node: Node<'_, '_>, name: &str) -> Option<usize> {
+    node.attribute(name)?.parse::<usize>().ok()?.checked_sub(1)
+}
+
+fn escape(text: &str) -> String {
+    let mut out = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&apos;"),
+            c => out.push(c),
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tests::sample;
+
+    #[test]
+    fn round_trips_jpz() {
+        let mut puzzle = sample();
+        puzzle.clues[0].text = "Feline & <friend>".into();
+        let parsed = Puzzle::from_jpz(&puzzle.to_jpz()).unwrap();
+        assert_eq!(parsed, puzzle);
+    }
+
+    #[test]
+    fn reads_exported_jpz() {
+        let text = r#"<?xml version="1.0" encoding="UTF-8"?>
+<crossword-compiler xmlns="http://crossword.info/xml/crossword-compiler">
+<rectangular-puzzle xmlns="http://crossword.info/xml/rectangular-puzzle">
+<metadata><title>Tiny</title></metadata>
+<crossword>
+<grid width="2" height="2">
+<cell x="1" y="1" solution="H" number="1"/><cell x="2" y="1" solution="I" number="2"/>
+<cell x="1" y="2" solution="O" number="3" solve-state="O"/><cell x="2" y="2" type="block"/>
+</grid>
+<clues><title><b>Across</b></title><clue word="1" number="1">Greeting <i>(2)</i></clue></clues>
+<clues><title><b>Down</b></title><clue word="2" number="1" format="2">Laugh sound</clue></clues>
+</crossword>
+</rectangular-puzzle>
+</crossword-compiler>"#;
+        let puzzle = Puzzle::from_jpz(text).unwrap();
+        assert_eq!(puzzle.title.as_deref(), Some("Tiny"));
+        assert!(puzzle.cell(1, 1).block);
+        assert_eq!(puzzle.cell(1, 0).fill.as_deref(), Some("O"));
+        assert_eq!(puzzle.clues[0].text, "Greeting");
+        assert_eq!(puzzle.clues[0].enumeration.as_deref(), Some("2"));
+        assert_eq!(puzzle.clues[1].direction, Direction::Down);
+    }
+
+    #[test]
+    fn rejects_oversized_grid() {
+        for size in ["100000", "256", "18446744073709551615"] {
+            let text = format!(r#"<crossword><grid width="{size}" height="{size}"/></crossword>"#);
+            assert!(matches!(Puzzle::from_jpz(&text), Err(PuzzleError::Jpz(_))));
+        }
+    }
+}
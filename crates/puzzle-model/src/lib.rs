@@ -0,0 +1,442 @@
+//! Crossword puzzle model plus readers and writers for common file formats.
+//!
+//! A [`Puzzle`] holds the grid (blocks, solution and current fill per cell),
+//! the clues with their enumerations, and a little metadata. It can be read
+//! from and written back to:
+//!
+//! - Across Lite `.puz` ([`Puzzle::from_puz`], [`Puzzle::to_puz`])
+//! - ipuz JSON ([`Puzzle::from_ipuz`], [`Puzzle::to_ipuz`])
+//! - Crossword Compiler JPZ XML ([`Puzzle::from_jpz`], [`Puzzle::to_jpz`])
+//!
+//! Slots are numbered the standard way from the grid itself, and each one
+//! converts into a `Vec<Option<u8>>` pattern (lowercase ASCII letters, `None`
+//! for open cells), the same shape a solver's pattern parser produces.
+//!
+//! # Example
+//! ```no_run
+//! use puzzle_model::Puzzle;
+//!
+//! # fn main() -> Result<(), Box<dyn std::error::Error>> {
+//! let puzzle = Puzzle::from_path("monday.puz")?;
+//! for entry in puzzle.entries() {
+//!     let clue = entry.clue.map_or("", |c| c.text.as_str());
+//!     println!("{}{:?} {:?} {clue}", entry.slot.number, entry.slot.direction, entry.pattern);
+//! }
+//! # Ok(()) }
+//! ```
+
+use std::path::Path;
+
+use thiserror::Error;
+
+mod ipuz;
+mod jpz;
+mod puz;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub enum Direction {
+    Across,
+    Down,
+}
+
+/// One grid square.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Cell {
+    pub block: bool,
+    /// Answer for the square; more than one letter for rebus squares.
+    pub solution: Option<String>,
+    /// What has been entered so far.
+    pub fill: Option<String>,
+    pub circled: bool,
+}
+
+impl Cell {
+    pub fn block() -> Self {
+        Self {
+            block: true,
+            ..Self::default()
+        }
+    }
+
+    pub fn open(solution: Option<&str>) -> Self {
+        Self {
+            solution: solution.map(str::to_string),
+            ..Self::default()
+        }
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Clue {
+    pub number: u32,
+    pub direction: Direction,
+    pub text: String,
+    /// Enumeration such as `3,4`, taken from the file or a trailing `(3,4)`.
+    pub enumeration: Option<String>,
+}
+
+/// A run of two or more open cells.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Slot {
+    pub number: u32,
+    pub direction: Direction,
+    pub row: usize,
+    pub col: usize,
+    pub len: usize,
+}
+
+/// A slot together with its clue (if the file had one) and current pattern.
+#[derive(Debug, Clone)]
+pub struct Entry<'a> {
+    pub slot: Slot,
+    pub clue: Option<&'a Clue>,
+    pub pattern: Vec<Option<u8>>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct Puzzle {
+    pub title: Option<String>,
+    pub author: Option<String>,
+    pub copyright: Option<String>,
+    pub notes: Option<String>,
+    pub width: usize,
+    pub height: usize,
+    /// Row-major, `width * height` cells.
+    pub cells: Vec<Cell>,
+    pub clues: Vec<Clue>,
+}
+
+/// Most cells across or down in a grid read from a file; the .puz limit.
+pub const MAX_GRID_SIZE: usize = 255;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Format {
+    Puz,
+    Ipuz,
+    Jpz,
+}
+
+impl Format {
+    /// Guess the format from a file extension.
+    pub fn from_path(path: &Path) -> Option<Self> {
+        let ext = path.extension()?.to_str()?.to_ascii_lowercase();
+        match ext.as_str() {
+            "puz" => Some(Self::Puz),
+            "ipuz" | "json" => Some(Self::Ipuz),
+            "jpz" | "xml" => Some(Self::Jpz),
+            _ => None,
+        }
+    }
+
+    /// Guess the format from the first bytes of a file.
+    pub fn detect(bytes: &[u8]) -> Option<Self> {
+        if bytes.get(2..14) == Some(puz::MAGIC) {
+            return Some(Self::Puz);
+        }
+        let start = bytes.iter().position(|b| !b.is_ascii_whitespace())?;
+        match bytes[start] {
+            b'{' => Some(Self::Ipuz),
+            b'<' => Some(Self::Jpz),
+            _ => None,
+        }
+    }
+}
+
+#[derive(Debug, Error)]
+pub enum PuzzleError {
+    #[error("failed to read puzzle: {0}")]
+    Io(#[from] std::io::Error),
+    #[error("unrecognised puzzle format")]
+    UnknownFormat,
+    #[error("invalid .puz file: {0}")]
+    Puz(String),
+    #[error("invalid ipuz file: {0}")]
+    Ipuz(String),
+    #[error("invalid JPZ file: {0}")]
+    Jpz(String),
+    #[error("unsupported puzzle: {0}")]
+    Unsupported(String),
+}
+
+impl Puzzle {
+    /// An empty grid of open cells.
+    pub fn new(width: usize, height: usize) -> Self {
+        Self {
+            width,
+            height,
+            cells: vec![Cell::default(); width * height],
+            ..Self::default()
+        }
+    }
+
+    /// Read a puzzle file, picking the format from its contents or extension.
+    pub fn from_path(path: impl AsRef<Path>) -> Result<Self, PuzzleError> {
+        let path = path.as_ref();
+        let bytes = std::fs::read(path)?;
+        let format = Format::detect(&bytes)
+            .or_else(|| Format::from_path(path))
+            .ok_or(PuzzleError::UnknownFormat)?;
+        Self::parse(&bytes, format)
+    }
+
+    pub fn parse(bytes: &[u8], format: Format) -> Result<Self, PuzzleError> {
+        match format {
+            Format::Puz => Self::from_puz(bytes),
+            Format::Ipuz => Self::from_ipuz(&text(bytes, PuzzleError::Ipuz)?),
+            Format::Jpz if bytes.starts_with(b"PK") => Err(PuzzleError::Unsupported(
+                "zip-compressed JPZ files must be unpacked first".into(),
+            )),
+            Format::Jpz => Self::from_jpz(&text(bytes, PuzzleError::Jpz)?),
+        }
+    }
+
+    pub fn write(&self, format: Format) -> Result<Vec<u8>, PuzzleError> {
+        match format {
+            Format::Puz => self.to_puz(),
+            Format::Ipuz => Ok(self.to_ipuz().into_bytes()),
+            Format::Jpz => Ok(self.to_jpz().into_bytes()),
+        }
+    }
+
+    pub fn cell(&self, row: usize, col: usize) -> &Cell {
+        &self.cells[row * self.width + col]
+    }
+
+    fn is_open(&self, row: usize, col: usize) -> bool {
+        !self.cell(row, col).block
+    }
+
+    /// Every slot in clue-number order, across before down.
+    pub fn slots(&self) -> Vec<Slot> {
+        let mut slots = Vec::new();
+        let mut number = 0;
+        for row in 0..self.height {
+            for col in 0..self.width {
+                if !self.is_open(row, col) {
+                    continue;
+                }
+                let across_len = if col == 0 || !self.is_open(row, col - 1) {
+                    (col..self.width)
+                        .take_while(|c| self.is_open(row, *c))
+                        .count()
+                } else {
+                    0
+                };
+                let down_len = if row == 0 || !self.is_open(row - 1, col) {
+                    (row..self.height)
+                        .take_while(|r| self.is_open(*r, col))
+                        .count()
+                } else {
+                    0
+                };
+                if across_len < 2 && down_len < 2 {
+                    continue;
+                }
+                number += 1;
+                for (direction, len) in
+                    [(Direction::Across, across_len), (Direction::Down, down_len)]
+                {
+                    if len >= 2 {
+                        slots.push(Slot {
+                            number,
+                            direction,
+                            row,
+                            col,
+                            len,
+                        });
+                    }
+                }
+            }
+        }
+        slots
+    }
+
+    /// Clue number of each cell that starts a slot, row-major.
+    pub fn numbers(&self) -> Vec<Option<u32>> {
+        let mut numbers = vec![None; self.cells.len()];
+        for slot in self.slots() {
+            numbers[slot.row * self.width + slot.col] = Some(slot.number);
+        }
+        numbers
+    }
+
+    /// `(row, col)` of every cell in `slot`.
+    pub fn slot_cells(&self, slot: &Slot) -> Vec<(usize, usize)> {
+        (0..slot.len)
+            .map(|i| match slot.direction {
+                Direction::Across => (slot.row, slot.col + i),
+                Direction::Down => (slot.row + i, slot.col),
+            })
+            .collect()
+    }
+
+    /// The slot's current fill as a pattern: a lowercase letter for each
+    /// filled single-letter cell, `None` otherwise.
+    pub fn pattern(&self, slot: &Slot) -> Vec<Option<u8>> {
+        self.slot_cells(slot)
+            .into_iter()
+            .map(|(row, col)| letter(self.cell(row, col).fill.as_deref()))
+            .collect()
+    }
+
+    /// The slot's answer as a pattern, when the file carries a solution.
+    pub fn solution_pattern(&self, slot: &Slot) -> Vec<Option<u8>> {
+        self.slot_cells(slot)
+            .into_iter()
+            .map(|(row, col)| letter(self.cell(row, col).solution.as_deref()))
+            .collect()
+    }
+
+    pub fn clue(&self, number: u32, direction: Direction) -> Option<&Clue> {
+        self.clues
+            .iter()
+            .find(|c| c.number == number && c.direction == direction)
+    }
+
+    /// Every slot with its clue and current pattern, ready to hand to a solver.
+    pub fn entries(&self) -> Vec<Entry<'_>> {
+        self.slots()
+            .into_iter()
+            .map(|slot| Entry {
+                clue: self.clue(slot.number, slot.direction),
+                pattern: self.pattern(&slot),
+                slot,
+            })
+            .collect()
+    }
+}
+
+/// Cells in a `width` x `height` grid, or `None` when either side is 0 or
+/// above [`MAX_GRID_SIZE`].
+fn grid_len(width: usize, height: usize) -> Option<usize> {
+    let sides = 1..=MAX_GRID_SIZE;
+    if !sides.contains(&width) || !sides.contains(&height) {
+        return None;
+    }
+    width.checked_mul(height)
+}
+
+fn letter(value: Option<&str>) -> Option<u8> {
+    let mut chars = value?.chars();
+    match (chars.next(), chars.next()) {
+        (Some(c), None) if c.is_ascii_alphabetic() => Some(c.to_ascii_lowercase() as u8),
+        _ => None,
+    }
+}
+
+fn text(bytes: &[u8], err: fn(String) -> PuzzleError) -> Result<String, PuzzleError> {
+    let text = std::str::from_utf8(bytes).map_err(|e| err(e.to_string()))?;
+    Ok(text.trim_start_matches('\u{feff}').to_string())
+}
+
+/// Put clues in slot order: by number, across before down.
+fn sort_clues(clues: &mut [Clue]) {
+    clues.sort_by_key(|c| (c.number, c.direction));
+}
+
+/// Split a trailing enumeration such as `(3,4)` or `(5-3)` off a clue.
+pub fn split_enumeration(clue: &str) -> (&str, Option<&str>) {
+    let trimmed = clue.trim_end();
+    if let Some(body) = trimmed.strip_suffix(')')
+        && let Some(open) = body.rfind('(')
+    {
+        let inner = &body[open + 1..];
+        let is_enum = !inner.is_empty()
+            && inner.chars().any(|c| c.is_ascii_digit())
+            && inner
+                .chars()
+                .all(|c| c.is_ascii_digit() || matches!(c, ',' | '-' | ' ' | '.'));
+        if is_enum {
+            return (body[..open].trim_end(), Some(inner));
+        }
+    }
+    (trimmed, None)
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// A 3x3 grid with a block in the middle and some fill entered.
+    pub(crate) fn sample() -> Puzzle {
+        let mut puzzle = Puzzle::new(3, 3);
+        let solution = ["cat", "a#o", "bed"];
+        for (row, line) in solution.iter().enumerate() {
+            for (col, ch) in line.chars().enumerate() {
+                let cell = &mut puzzle.cells[row * 3 + col];
+                if ch == '#' {
+                    *cell = Cell::block();
+                } else {
+                    cell.solution = Some(ch.to_ascii_uppercase().to_string());
+                }
+            }
+        }
+        puzzle.cells[0].fill = Some("C".into());
+        puzzle.cells[8].fill = Some("D".into());
+        puzzle.cells[2].circled = true;
+        puzzle.title = Some("Sample".into());
+        puzzle.author = Some("A. Setter".into());
+        puzzle.clues = vec![
+            clue(1, Direction::Across, "Feline", Some("3")),
+            clue(1, Direction::Down, "Taxi", Some("3")),
+            clue(2, Direction::Down, "Large number, informally", Some("3")),
+            clue(3, Direction::Across, "Place to sleep", Some("3")),
+        ];
+        puzzle
+    }
+
+    pub(crate) fn clue(
+        number: u32,
+        direction: Direction,
+        text: &str,
+        enumeration: Option<&str>,
+    ) -> Clue {
+        Clue {
+            number,
+            direction,
+            text: text.to_string(),
+            enumeration: enumeration.map(str::to_string),
+        }
+    }
+
+    #[test]
+    fn numbers_slots_and_builds_patterns() {
+        let puzzle = sample();
+        let entries = puzzle.entries();
+        let summary: Vec<(u32, Direction, Vec<Option<u8>>)> = entries
+            .iter()
+            .map(|e| (e.slot.number, e.slot.direction, e.pattern.clone()))
+            .collect();
+        assert_eq!(
+            summary,
+            vec![
+                (1, Direction::Across, vec![Some(b'c'), None, None]),
+                (1, Direction::Down, vec![Some(b'c'), None, None]),
+                (2, Direction::Down, vec![None, None, Some(b'd')]),
+                (3, Direction::Across, vec![None, None, Some(b'd')]),
+            ]
+        );
+        assert_eq!(entries[2].clue.unwrap().text, "Large number, informally");
+        assert_eq!(
+            puzzle.solution_pattern(&entries[3].slot),
+            vec![Some(b'b'), Some(b'e'), Some(b'd')]
+        );
+    }
+
+    #[test]
+    fn splits_trailing_enumerations() {
+        assert_eq!(split_enumeration("Dorm (5,4)"), ("Dorm", Some("5,4")));
+        assert_eq!(split_enumeration("Hot (dog)"), ("Hot (dog)", None));
+        assert_eq!(split_enumeration("Plain"), ("Plain", None));
+    }
+
+    #[test]
+    fn detects_formats() {
+        assert_eq!(Format::detect(b"  {\"version\": 1}"), Some(Format::Ipuz));
+        assert_eq!(
+            Format::detect(b"<?xml version=\"1.0\"?>"),
+            Some(Format::Jpz)
+        );
+        assert_eq!(Format::from_path(Path::new("a.PUZ")), Some(Format::Puz));
+    }
+}
@@ -0,0 +1,430 @@
+//! Across Lite `.puz` reader and writer.
+//!
+//! Layout: a 0x34-byte header, the solution and fill grids (one byte per
+//! cell, `.` for blocks and `-` for empty fill), NUL-terminated strings
+//! (title, author, copyright, clues in slot order, notes) and optional
+//! extra sections such as `GEXT` (circles) and `GRBS`/`RTBL` (rebus).
+
+use std::collections::BTreeMap;
+
+use crate::{Cell, Clue, Puzzle, PuzzleError, grid_len, split_enumeration};
+
+pub(crate) const MAGIC: &[u8] = b"ACROSS&DOWN\0";
+const HEADER_LEN: usize = 0x34;
+const CIRCLED: u8 = 0x80;
+/// XOR mask for the "masked" checksums in the header.
+const MASK: &[u8; 8] = b"ICHEATED";
+
+fn err(msg: impl Into<String>) -> PuzzleError {
+    PuzzleError::Puz(msg.into())
+}
+
+impl Puzzle {
+    pub fn from_puz(bytes: &[u8]) -> Result<Self, PuzzleError> {
+        // Some files carry junk before the header, so look for the magic.
+        let start = bytes
+            .windows(MAGIC.len())
+            .position(|w| w == MAGIC)
+            .and_then(|i| i.checked_sub(2))
+            .ok_or_else(|| err("missing ACROSS&DOWN header"))?;
+        let data = &bytes[start..];
+        if data.len() < HEADER_LEN {
+            return Err(err("truncated header"));
+        }
+        let width = data[0x2C] as usize;
+        let height = data[0x2D] as usize;
+        let num_clues = u16::from_le_bytes([data[0x2E], data[0x2F]]) as usize;
+        let scrambled = u16::from_le_bytes([data[0x32], data[0x33]]) != 0;
+        let utf8 = data[0x18] >= b'2';
+        let n = width * height;
+        if n == 0 || data.len() < HEADER_LEN + 2 * n {
+            return Err(err("truncated grid"));
+        }
+
+        let solution = &data[HEADER_LEN..HEADER_LEN + n];
+        let fill = &data[HEADER_LEN + n..HEADER_LEN + 2 * n];
+        let mut cells: Vec<Cell> = solution
+            .iter()
+            .zip(fill)
+            .map(|(sol, fill)| {
+                if *sol == b'.' {
+                    return Cell::block();
+                }
+                Cell {
+                    // A scrambled solution is not the real answer.
+                    solution: (!scrambled).then(|| (*sol as char).to_string()),
+                    fill: (*fill != b'-').then(|| (*fill as char).to_string()),
+                    ..Cell::default()
+                }
+            })
+            .collect();
+
+        let mut reader = Reader {
+            data,
+            pos: HEADER_LEN + 2 * n,
+            utf8,
+        };
+        let title = reader.string()?;
+        let author = reader.string()?;
+        let copyright = reader.string()?;
+        let clue_texts = (0..num_clues)
+            .map(|_| reader.string())
+            .collect::<Result<Vec<_>, _>>()?;
+        let notes = reader.string().unwrap_or_default();
+
+        let mut rebus_grid = None;
+        let mut rebus_table = BTreeMap::new();
+        while let Some((name, body)) = reader.section() {
+            match name {
+                b"GEXT" if body.len() == n => {
+                    for (cell, flags) in cells.iter_mut().zip(body) {
+                        cell.circled = flags & CIRCLED != 0;
+                    }
+                }
+                b"GRBS" if body.len() == n => rebus_grid = Some(body),
+                b"RTBL" => {
+                    for entry in reader.decode(body).split(';') {
+                        if let Some((key, value)) = entry.split_once(':')
+                            && let Ok(key) = key.trim().parse::<u8>()
+                        {
+                            rebus_table.insert(key, value.to_string());
+                        }
+                    }
+                }
+                b"RUSR" => {
+                    for (cell, value) in cells.iter_mut().zip(body.split(|b| *b == 0)) {
+                        if !value.is_empty() && !cell.block {
+                            cell.fill = Some(reader.decode(value));
+                        }
+                    }
+                }
+                _ => {}
+            }
+        }
+        if let Some(grid) = rebus_grid
+            && !scrambled
+        {
+            for (cell, key) in cells.iter_mut().zip(grid) {
+                if *key != 0
+                    && let Some(value) = rebus_table.get(&(key - 1))
+                {
+                    cell.solution = Some(value.clone());
+                }
+            }
+        }
+
+        let mut puzzle = Puzzle {
+            title: non_empty(title),
+            author: non_empty(author),
+            copyright: non_empty(copyright),
+            notes: non_empty(notes),
+            width,
+            height,
+            cells,
+            clues: Vec::new(),
+        };
+        let slots = puzzle.slots();
+        if slots.len() != clue_texts.len() {
+            return Err(err(format!(
+                "grid has {} slots but the file has {} clues",
+                slots.len(),
+                clue_texts.len()
+            )));
+        }
+        puzzle.clues = slots
+            .into_iter()
+            .zip(clue_texts)
+            .map(|(slot, text)| {
+                let (text, enumeration) = split_enumeration(&text);
+                Clue {
+                    number: slot.number,
+                    direction: slot.direction,
+                    text: text.to_string(),
+                    enumeration: enumeration.map(str::to_string),
+                }
+            })
+            .collect();
+        Ok(puzzle)
+    }
+
+    /// Write an Across Lite 1.3 file. Every open cell needs a solution.
+    pub fn to_puz(&self) -> Result<Vec<u8>, PuzzleError> {
+        if grid_len(self.width, self.height).is_none() {
+            return Err(PuzzleError::Unsupported(
+                ".puz grids must be between 1 and 255 cells wide and high".into(),
+            ));
+        }
+        let slots = self.slots();
+        if slots.len() > u16::MAX as usize {
+            return Err(PuzzleError::Unsupported("too many clues for .puz".into()));
+        }
+
+        let mut solution = Vec::with_capacity(self.cells.len());
+        let mut fill = Vec::with_capacity(self.cells.len());
+        let mut rebus_keys: Vec<String> = Vec::new();
+        let mut rebus_grid = vec![0u8; self.cells.len()];
+        let mut user_rebus = Vec::new();
+        for (i, cell) in self.cells.iter().enumerate() {
+            if cell.block {
+                solution.push(b'.');
+                fill.push(b'.');
+                user_rebus.push(0);
+                continue;
+            }
+            let answer = cell
+                .solution
+                .as_deref()
+                .filter(|s| !s.is_empty())
+                .ok_or_else(|| {
+                    PuzzleError::Unsupported(".puz needs a solution for every open cell".into())
+                })?
+                .to_ascii_uppercase();
+            solution.push(latin1(&answer)[0]);
+            if answer.chars().count() > 1 {
+                let key = match rebus_keys.iter().position(|k| *k == answer) {
+                    Some(key) => key,
+                    // Keys are stored plus one in a byte.
+                    None if rebus_keys.len() >= 255 => {
+                        return Err(PuzzleError::Unsupported(
+                            ".puz allows at most 255 distinct rebus answers".into(),
+                        ));
+                    }
+                    None => {
+                        rebus_keys.push(answer.clone());
+                        rebus_keys.len() - 1
+                    }
+                };
+                rebus_grid[i] = key as u8 + 1;
+            }
+            match cell.fill.as_deref().filter(|f| !f.is_empty()) {
+                Some(value) => {
+                    let value = value.to_ascii_uppercase();
+                    fill.push(latin1(&value)[0]);
+                    if value.chars().count() > 1 {
+                        user_rebus.extend(latin1(&value));
+                    }
+                }
+                None => fill.push(b'-'),
+            }
+            user_rebus.push(0);
+        }
+
+        let clue_texts: Vec<Vec<u8>> = slots
+            .iter()
+            .map(|slot| {
+                let text = match self.clue(slot.number, slot.direction) {
+                    Some(Clue {
+                        text,
+                        enumeration: Some(enumeration),
+                        ..
+                    }) => format!("{text} ({enumeration})"),
+                    Some(clue) => clue.text.clone(),
+                    None => String::new(),
+                };
+                latin1(&text)
+            })
+            .collect();
+        let title = latin1(self.title.as_deref().unwrap_or_default());
+        let author = latin1(self.author.as_deref().unwrap_or_default());
+        let copyright = latin1(self.copyright.as_deref().unwrap_or_default());
+        let notes = latin1(self.notes.as_deref().unwrap_or_default());
+
+        let mut header = vec![0u8; HEADER_LEN];
+        header[2..14].copy_from_slice(MAGIC);
+        header[0x18..0x1C].copy_from_slice(b"1.3\0");
+        header[0x2C] = self.width as u8;
+        header[0x2D] = self.height as u8;
+        header[0x2E..0x30].copy_from_slice(&(slots.len() as u16).to_le_bytes());
+        header[0x30..0x32].copy_from_slice(&1u16.to_le_bytes());
+
+        let cib = checksum(&header[0x2C..0x34], 0);
+        let text_sum = text_checksum(&title, &author, &copyright, &clue_texts, &notes, 0);
+        let mut global = checksum(&solution, cib);
+        global = checksum(&fill, global);
+        global = text_checksum(&title, &author, &copyright, &clue_texts, &notes, global);
+        let parts = [cib, checksum(&solution, 0), checksum(&fill, 0), text_sum];
+        for (i, part) in parts.iter().enumerate() {
+            header[0x10 + i] = MASK[i] ^ (part & 0xFF) as u8;
+            header[0x14 + i] = MASK[i + 4] ^ (part >> 8) as u8;
+        }
+        header[0..2].copy_from_slice(&global.to_le_bytes());
+        header[0x0E..0x10].copy_from_slice(&cib.to_le_bytes());
+
+        let mut out = header;
+        out.extend(&solution);
+        out.extend(&fill);
+        for text in [&title, &author, &copyright].into_iter().chain(&clue_texts) {
+            out.extend(text);
+            out.push(0);
+        }
+        out.extend(&notes);
+        out.push(0);
+
+        if !rebus_keys.is_empty() {
+            let table: String = rebus_keys
+                .iter()
+                .enumerate()
+                .map(|(key, value)| format!("{key:>2}:{value};"))
+                .collect();
+            write_section(&mut out, b"GRBS", &rebus_grid);
+            write_section(&mut out, b"RTBL", &latin1(&table));
+        }
+        if self.cells.iter().any(|c| c.circled) {
+            let flags: Vec<u8> = self
+                .cells
+                .iter()
+                .map(|c| if c.circled { CIRCLED } else { 0 })
+                .collect();
+            write_section(&mut out, b"GEXT", &flags);
+        }
+        if user_rebus.len() > self.cells.len() {
+            write_section(&mut out, b"RUSR", &user_rebus);
+        }
+        Ok(out)
+    }
+}
+
+struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+    utf8: bool,
+}
+
+impl<'a> Reader<'a> {
+    fn string(&mut self) -> Result<String, PuzzleError> {
+        let rest = &self.data[self.pos.min(self.data.len())..];
+        let end = rest
+            .iter()
+            .position(|b| *b == 0)
+            .ok_or_else(|| err("unterminated string"))?;
+        self.pos += end + 1;
+        Ok(self.decode(&rest[..end]))
+    }
+
+    /// Next extra section as `(name, body)`.
+    fn section(&mut self) -> Option<(&'a [u8], &'a [u8])> {
+        let header = self.data.get(self.pos..self.pos + 8)?;
+        let len = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let body = self.data.get(self.pos + 8..self.pos + 8 + len)?;
+        self.pos += 8 + len + 1;
+        Some((&header[..4], body))
+    }
+
+    fn decode(&self, bytes: &[u8]) -> String {
+        if self.utf8 {
+            String::from_utf8_lossy(bytes).into_owned()
+        } else {
+            bytes.iter().map(|b| *b as char).collect()
+        }
+    }
+}
+
+fn non_empty(value: String) -> Option<String> {
+    (!value.is_empty()).then_some(value)
+}
+
+/// Encode as ISO-8859-1, replacing anything outside it with `?`.
+fn latin1(text: &str) -> Vec<u8> {
+    text.chars()
+        .map(|c| u8::try_from(u32::from(c)).unwrap_or(b'?'))
+        .collect()
+}
+
+fn checksum(data: &[u8], mut sum: u16) -> u16 {
+    for byte in data {
+        sum = sum.rotate_right(1).wrapping_add(u16::from(*byte));
+    }
+    sum
+}
+
+fn text_checksum(
+    title: &[u8],
+    author: &[u8],
+    copyright: &[u8],
+    clues: &[Vec<u8>],
+    notes: &[u8],
+    mut sum: u16,
+) -> u16 {
+    for text in [title, author, copyright] {
+        if !text.is_empty() {
+            sum = checksum(text, sum);
+            sum = checksum(&[0], sum);
+        }
+    }
+    for clue in clues {
+        sum = checksum(clue, sum);
+    }
+    if !notes.is_empty() {
+        sum = checksum(notes, sum);
+        sum = checksum(&[0], sum);
+    }
+    sum
+}
+
+fn write_section(out: &mut Vec<u8>, name: &[u8; 4], body: &[u8]) {
+    out.extend(name);
+    out.extend((body.len() as u16).to_le_bytes());
+    out.extend(checksum(body, 0).to_le_bytes());
+    out.extend(body);
+    out.push(0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::Direction;
+    use crate::tests::sample;
+
+    #[test]
+    fn round_trips_puz() {
+        let puzzle = sample();
+        let bytes = puzzle.to_puz().unwrap();
+        assert_eq!(&bytes[2..14], MAGIC);
+        let parsed = Puzzle::from_puz(&bytes).unwrap();
+        assert_eq!(parsed, puzzle);
+    }
+
+    #[test]
+    fn writes_valid_checksums() {
+        let bytes = sample().to_puz().unwrap();
+        let cib = checksum(&bytes[0x2C..0x34], 0);
+        assert_eq!(u16::from_le_bytes([bytes[0x0E], bytes[0x0F]]), cib);
+        let n = 9;
+        let solution = checksum(&bytes[HEADER_LEN..HEADER_LEN + n], 0);
+        assert_eq!(bytes[0x11], b'C' ^ (solution & 0xFF) as u8);
+    }
+
+    #[test]
+    fn keeps_rebus_squares() {
+        let mut puzzle = sample();
+        puzzle.cells[4] = Cell::open(Some("HEART"));
+        puzzle.clues.retain(|c| c.direction == Direction::Across);
+        let parsed = Puzzle::from_puz(&puzzle.to_puz().unwrap()).unwrap();
+        assert_eq!(parsed.cells[4].solution.as_deref(), Some("HEART"));
+        assert_eq!(parsed.cells[1].solution.as_deref(), Some("A"));
+    }
+
+    #[test]
+    fn rejects_oversized_grid() {
+        assert!(matches!(
+            Puzzle::new(256, 1).to_puz(),
+            Err(PuzzleError::Unsupported(_))
+        ));
+    }
+
+    #[test]
+    fn rejects_too_many_rebus_answers() {
+        let mut puzzle = Puzzle::new(16, 16);
+        for (i, cell) in puzzle.cells.iter_mut().enumerate() {
+            cell.solution = Some(format!("R{i:03}"));
+        }
+        assert!(matches!(puzzle.to_puz(), Err(PuzzleError::Unsupported(_))));
+        puzzle.cells[255].solution = Some("A".into());
+        assert!(puzzle.to_puz().is_ok());
+    }
+
+    #[test]
+    fn rejects_garbage() {
+        assert!(Puzzle::from_puz(b"not a puzzle").is_err());
+    }
+}
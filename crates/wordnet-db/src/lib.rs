@@ -36,6 +36,16 @@
 //! ```
 //!
 //! For a runnable demo, see `cargo run -p wordnet-db --example stats -- <dict>`.
+//!
+//! # Snapshots
+//! [`WordNet::write_snapshot`] saves the parsed tables to a versioned binary
+//! file, and [`WordNet::load_with_snapshot`] decodes them back into the same
+//! maps instead of tokenizing the text files. This is not a zero-copy view:
+//! every table is still rebuilt in memory, and the dictionary files and the
+//! snapshot are hashed on each load, so it saves roughly a third of the
+//! parse time. The snapshot is checked against the dictionary files and
+//! rejected when they differ, so callers can fall back to
+//! [`WordNet::load_with_mode`].
 
 use std::collections::HashMap;
 use std::fs::File;
@@ -48,6 +58,10 @@ use wordnet_types::{
     Frame, Gloss, IndexEntry, Lemma, Pointer, Pos, Synset, SynsetId, SynsetType, decode_st,
 };
 
+mod snapshot;
+
+pub use snapshot::SNAPSHOT_VERSION;
+
 /// Strategy for loading dictionary files.
 #[derive(Clone, Copy, Debug, Eq, PartialEq)]
 pub enum LoadMode {
@@ -214,22 +228,7 @@ impl WordNet {
     /// Load WordNet choosing between mmap and owned buffers at runtime.
     pub fn load_with_mode(dict_dir: impl AsRef<Path>, mode: LoadMode) -> Result<Self> {
         let dir = dict_dir.as_ref();
-        let required = [
-            "data.noun",
-            "data.verb",
-            "data.adj",
-            "data.adv",
-            "index.noun",
-            "index.verb",
-            "index.adj",
-            "index.adv",
-        ];
-        for name in &required {
-            let path = dir.join(name);
-            if !path.exists() {
-                anyhow::bail!("missing required WordNet file: {}", path.display());
-            }
-        }
+        check_required_files(dir)?;
 
         let files = DictFiles::load(dir, mode)?;
 
@@ -429,6 +428,26 @@ impl WordNet {
     }
 }
 
+fn check_required_files(dir: &Path) -> Result<()> {
+    let required = [
+        "data.noun",
+        "data.verb",
+        "data.adj",
+        "data.adv",
+        "index.noun",
+        "index.verb",
+        "index.adj",
+        "index.adv",
+    ];
+    for name in &required {
+        let path = dir.join(name);
+        if !path.exists() {
+            anyhow::bail!("missing required WordNet file: {}", path.display());
+        }
+    }
+    Ok(())
+}
+
 fn load_file(path: PathBuf, mode: LoadMode) -> Result<Buffer> {
     match mode {
         LoadMode::Mmap => {
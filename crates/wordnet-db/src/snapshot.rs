@@ -0,0 +1,410 @@
+//! Binary snapshot of the parsed WordNet tables.
+//!
+//! Text still borrows from the dictionary files, so a snapshot only holds
+//! the tables built by [`WordNet::load_with_mode`]: index entries, synset
+//! records and the verb-frame and sense-count maps, with text stored as
+//! `(file, start, len)` references. Loading one skips tokenizing and
+//! number parsing, but still decodes every entry into fresh maps; the file
+//! is read through a mapping, not used in place.
+//!
+//! The header records [`SNAPSHOT_VERSION`], a checksum of every dictionary
+//! file the references point into, and a checksum of the payload. Any
+//! mismatch is reported as an error so callers can fall back to parsing.
+
+use std::collections::HashMap;
+use std::fs::{self, File};
+use std::path::Path;
+
+use anyhow::{Context, Result, bail};
+use memmap2::Mmap;
+use wordnet_types::{Frame, Pos, SynsetId, SynsetType};
+use xxhash_rust::xxh3::{Xxh3, xxh3_64};
+
+use crate::{
+    DictFiles, FileKind, GlossData, IndexEntryData, LemmaData, LoadMode, PointerData, SynsetData,
+    TextRef, WordNet, check_required_files,
+};
+
+/// Bumped whenever the layout changes; other versions are rejected.
+pub const SNAPSHOT_VERSION: u32 = 1;
+const MAGIC: &[u8; 8] = b"WNSNAP\0\0";
+const HEADER_LEN: usize = 32;
+
+const FILE_KINDS: [FileKind; 10] = [
+    FileKind::DataNoun,
+    FileKind::DataVerb,
+    FileKind::DataAdj,
+    FileKind::DataAdv,
+    FileKind::IndexNoun,
+    FileKind::IndexVerb,
+    FileKind::IndexAdj,
+    FileKind::IndexAdv,
+    FileKind::Frames,
+    FileKind::Cntlist,
+];
+
+impl WordNet {
+    /// Write the parsed tables to `path`. The file is written next to
+    /// `path` and renamed into place.
+    pub fn write_snapshot(&self, path: impl AsRef<Path>) -> Result<()> {
+        let path = path.as_ref();
+        let mut out = Encoder::default();
+
+        out.u32(self.index.len() as u32);
+        for ((pos, key), entry) in &self.index {
+            out.pos(*pos);
+            out.str(key);
+            out.text(entry.lemma);
+            out.u32(entry.synset_cnt);
+            out.u32(entry.p_cnt);
+            out.u32(entry.ptr_symbols.len() as u32);
+            for symbol in &entry.ptr_symbols {
+                out.text(*symbol);
+            }
+            out.u32(entry.sense_cnt);
+            out.u32(entry.tagsense_cnt);
+            out.u32(entry.synset_offsets.len() as u32);
+            for offset in &entry.synset_offsets {
+                out.u32(*offset);
+            }
+        }
+
+        out.u32(self.synsets.len() as u32);
+        for synset in self.synsets.values() {
+            out.pos(synset.id.pos);
+            out.u32(synset.id.offset);
+            out.u8(synset.lex_filenum);
+            out.u8(synset_type_code(synset.synset_type));
+            out.u32(synset.words.len() as u32);
+            for word in &synset.words {
+                out.text(word.text);
+                out.u8(word.lex_id);
+            }
+            out.u32(synset.pointers.len() as u32);
+            for pointer in &synset.pointers {
+                out.text(pointer.symbol);
+                out.pos(pointer.target.pos);
+                out.u32(pointer.target.offset);
+                out.u16(pointer.src_word.unwrap_or(0));
+                out.u16(pointer.dst_word.unwrap_or(0));
+            }
+            out.u32(synset.frames.len() as u32);
+            for frame in &synset.frames {
+                out.u16(frame.frame_number);
+                out.u16(frame.word_number.unwrap_or(0));
+            }
+            out.text(synset.gloss.raw);
+            out.text(synset.gloss.definition);
+            out.u32(synset.gloss.examples.len() as u32);
+            for example in &synset.gloss.examples {
+                out.text(*example);
+            }
+        }
+
+        out.u32(self.verb_frames_text.len() as u32);
+        for (number, text) in &self.verb_frames_text {
+            out.u16(*number);
+            out.text(*text);
+        }
+
+        out.u32(self.sense_counts.len() as u32);
+        for ((lemma, pos, sense), count) in &self.sense_counts {
+            out.str(lemma);
+            out.pos(*pos);
+            out.u32(*sense);
+            out.u32(*count);
+        }
+
+        let payload = out.buf;
+        let mut bytes = Vec::with_capacity(HEADER_LEN + payload.len());
+        bytes.extend_from_slice(MAGIC);
+        bytes.extend_from_slice(&SNAPSHOT_VERSION.to_le_bytes());
+        bytes.extend_from_slice(&0u32.to_le_bytes());
+        bytes.extend_from_slice(&source_checksum(&self.files).to_le_bytes());
+        bytes.extend_from_slice(&xxh3_64(&payload).to_le_bytes());
+        bytes.extend_from_slice(&payload);
+
+        let tmp = path.with_extension("tmp");
+        fs::write(&tmp, &bytes).with_context(|| format!("write {}", tmp.display()))?;
+        fs::rename(&tmp, path).with_context(|| format!("rename to {}", path.display()))?;
+        Ok(())
+    }
+
+    /// Load WordNet from `dict_dir`, taking the parsed tables from a
+    /// snapshot written by [`WordNet::write_snapshot`] instead of parsing the
+    /// files. Fails if the snapshot has another version, is damaged, or was
+    /// built from different dictionary files.
+    pub fn load_with_snapshot(
+        dict_dir: impl AsRef<Path>,
+        snapshot: impl AsRef<Path>,
+        mode: LoadMode,
+    ) -> Result<Self> {
+        let dir = dict_dir.as_ref();
+        let snapshot = snapshot.as_ref();
+        check_required_files(dir)?;
+        let files = DictFiles::load(dir, mode)?;
+
+        let file = File::open(snapshot).with_context(|| format!("open {}", snapshot.display()))?;
+        // SAFETY: snapshots are replaced by rename, never rewritten in place.
+        let map =
+            unsafe { Mmap::map(&file) }.with_context(|| format!("mmap {}", snapshot.display()))?;
+        if map.len() < HEADER_LEN || &map[..MAGIC.len()] != MAGIC {
+            bail!("{} is not a WordNet snapshot", snapshot.display());
+        }
+        let version = u32::from_le_bytes(map[8..12].try_into()?);
+        if version != SNAPSHOT_VERSION {
+            bail!("snapshot version {version} does not match {SNAPSHOT_VERSION}");
+        }
+        if u64::from_le_bytes(map[16..24].try_into()?) != source_checksum(&files) {
+            bail!("snapshot is stale: the dictionary files changed since it was built");
+        }
+        let payload = &map[HEADER_LEN..];
+        if u64::from_le_bytes(map[24..32].try_into()?) != xxh3_64(payload) {
+            bail!("snapshot is corrupt: payload checksum mismatch");
+        }
+
+        let mut input = Decoder { bytes: payload };
+        let mut index = HashMap::new();
+        let mut lemma_to_synsets = HashMap::new();
+        for _ in 0..input.u32()? {
+            let pos = input.pos()?;
+            let key = input.string()?;
+            let lemma = input.text()?;
+            let synset_cnt = input.u32()?;
+            let p_cnt = input.u32()?;
+            let ptr_symbols = input.list(Decoder::text)?;
+            let sense_cnt = input.u32()?;
+            let tagsense_cnt = input.u32()?;
+            let synset_offsets = input.list(Decoder::u32)?;
+            lemma_to_synsets.insert(
+                (pos, key.clone()),
+                synset_offsets
+                    .iter()
+                    .map(|offset| SynsetId {
+                        pos,
+                        offset: *offset,
+                    })
+                    .collect::<Vec<_>>(),
+            );
+            index.insert(
+                (pos, key),
+                IndexEntryData {
+                    lemma,
+                    synset_cnt,
+                    p_cnt,
+                    ptr_symbols,
+                    sense_cnt,
+                    tagsense_cnt,
+                    synset_offsets,
+                },
+            );
+        }
+
+        let mut synsets = HashMap::new();
+        for _ in 0..input.u32()? {
+            let id = SynsetId {
+                pos: input.pos()?,
+                offset: input.u32()?,
+            };
+            let lex_filenum = input.u8()?;
+            let synset_type = synset_type_from_code(input.u8()?)?;
+            let words = input.list(|input| {
+                Ok(LemmaData {
+                    text: input.text()?,
+                    lex_id: input.u8()?,
+                })
+            })?;
+            let pointers = input.list(|input| {
+                Ok(PointerData {
+                    symbol: input.text()?,
+                    target: SynsetId {
+                        pos: input.pos()?,
+                        offset: input.u32()?,
+                    },
+                    src_word: nonzero(input.u16()?),
+                    dst_word: nonzero(input.u16()?),
+                })
+            })?;
+            let frames = input.list(|input| {
+                Ok(Frame {
+                    frame_number: input.u16()?,
+                    word_number: nonzero(input.u16()?),
+                })
+            })?;
+            let gloss = GlossData {
+                raw: input.text()?,
+                definition: input.text()?,
+                examples: input.list(Decoder::text)?,
+            };
+            synsets.insert(
+                id,
+                SynsetData {
+                    id,
+                    lex_filenum,
+                    synset_type,
+                    words,
+                    pointers,
+                    frames,
+                    gloss,
+                },
+            );
+        }
+
+        let mut verb_frames_text = HashMap::new();
+        for _ in 0..input.u32()? {
+            verb_frames_text.insert(input.u16()?, input.text()?);
+        }
+
+        let mut sense_counts = HashMap::new();
+        for _ in 0..input.u32()? {
+            let lemma = input.string()?;
+            let pos = input.pos()?;
+            let sense = input.u32()?;
+            sense_counts.insert((lemma, pos, sense), input.u32()?);
+        }
+        if !input.bytes.is_empty() {
+            bail!("snapshot is corrupt: trailing bytes");
+        }
+
+        Ok(Self {
+            files,
+            index,
+            synsets,
+            lemma_to_synsets,
+            verb_frames_text,
+            sense_counts,
+        })
+    }
+}
+
+/// Checksum over every dictionary file, including which optional ones exist.
+fn source_checksum(files: &DictFiles) -> u64 {
+    let mut hasher = Xxh3::new();
+    for kind in FILE_KINDS {
+        let bytes = files.bytes(kind);
+        hasher.update(&(bytes.len() as u64).to_le_bytes());
+        hasher.update(bytes);
+    }
+    hasher.digest()
+}
+
+/// `FILE_KINDS` lists the kinds in declaration order, so the discriminant
+/// doubles as the position.
+fn file_kind_code(kind: FileKind) -> u8 {
+    kind as u8
+}
+
+fn synset_type_code(synset_type: SynsetType) -> u8 {
+    match synset_type {
+        SynsetType::Noun => b'n',
+        SynsetType::Verb => b'v',
+        SynsetType::Adj => b'a',
+        SynsetType::AdjSatellite => b's',
+        SynsetType::Adv => b'r',
+    }
+}
+
+fn synset_type_from_code(code: u8) -> Result<SynsetType> {
+    SynsetType::from_char(code as char)
+        .with_context(|| format!("snapshot is corrupt: synset type {code}"))
+}
+
+fn nonzero(value: u16) -> Option<u16> {
+    (value != 0).then_some(value)
+}
+
+#[derive(Default)]
+struct Encoder {
+    buf: Vec<u8>,
+}
+
+impl Encoder {
+    fn u8(&mut self, value: u8) {
+        self.buf.push(value);
+    }
+
+    fn u16(&mut self, value: u16) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn u32(&mut self, value: u32) {
+        self.buf.extend_from_slice(&value.to_le_bytes());
+    }
+
+    fn pos(&mut self, pos: Pos) {
+        self.u8(pos.to_char() as u8);
+    }
+
+    fn str(&mut self, value: &str) {
+        self.u32(value.len() as u32);
+        self.buf.extend_from_slice(value.as_bytes());
+    }
+
+    fn text(&mut self, text: TextRef) {
+        self.u8(file_kind_code(text.file));
+        self.u32(text.start as u32);
+        self.u32(text.len as u32);
+    }
+}
+
+struct Decoder<'a> {
+    bytes: &'a [u8],
+}
+
+impl<'a> Decoder<'a> {
+    fn take<const N: usize>(&mut self) -> Result<[u8; N]> {
+        let (head, rest) = self
+            .bytes
+            .split_first_chunk::<N>()
+            .context("snapshot is corrupt: truncated payload")?;
+        self.bytes = rest;
+        Ok(*head)
+    }
+
+    fn u8(&mut self) -> Result<u8> {
+        Ok(self.take::<1>()?[0])
+    }
+
+    fn u16(&mut self) -> Result<u16> {
+        Ok(u16::from_le_bytes(self.take()?))
+    }
+
+    fn u32(&mut self) -> Result<u32> {
+        Ok(u32::from_le_bytes(self.take()?))
+    }
+
+    fn pos(&mut self) -> Result<Pos> {
+        let code = self.u8()?;
+        Pos::from_char(code as char).with_context(|| format!("snapshot is corrupt: pos {code}"))
+    }
+
+    fn string(&mut self) -> Result<String> {
+        let len = self.u32()? as usize;
+        let (text, rest) = self
+            .bytes
+            .split_at_checked(len)
+            .context("snapshot is corrupt: truncated payload")?;
+        self.bytes = rest;
+        Ok(std::str::from_utf8(text)?.to_string())
+    }
+
+    fn text(&mut self) -> Result<TextRef> {
+        let file = *FILE_KINDS
+            .get(self.u8()? as usize)
+            .context("snapshot is corrupt: file kind")?;
+        Ok(TextRef {
+            file,
+            start: self.u32()? as usize,
+            len: self.u32()? as usize,
+        })
+    }
+
+    fn list<T>(&mut self, mut item: impl FnMut(&mut Self) -> Result<T>) -> Result<Vec<T>> {
+        let len = self.u32()? as usize;
+        let mut items = Vec::with_capacity(len.min(self.bytes.len()));
+        for _ in 0..len {
+            items.push(item(self)?);
+        }
+        Ok(items)
+    }
+}
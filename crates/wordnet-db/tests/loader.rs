@@ -1,6 +1,6 @@
 use std::path::PathBuf;
 
-use wordnet_db::WordNet;
+use wordnet_db::{LoadMode, WordNet};
 use wordnet_types::{Pos, SynsetId, SynsetType};
 
 fn fixture_dir() -> PathBuf {
@@ -67,3 +67,44 @@ fn parses_verb_frames() {
     assert_eq!(synset.frames[0].word_number, Some(1));
     assert_eq!(synset.frames[1].word_number, None);
 }
+
+#[test]
+fn snapshot_round_trips_and_detects_changed_files() {
+    let dir = tempfile::tempdir().expect("temp dir");
+    let dict = dir.path().join("dict");
+    std::fs::create_dir(&dict).unwrap();
+    for entry in std::fs::read_dir(fixture_dir()).unwrap() {
+        let entry = entry.unwrap();
+        std::fs::copy(entry.path(), dict.join(entry.file_name())).unwrap();
+    }
+    let snapshot = dir.path().join("wordnet.snap");
+
+    let parsed = WordNet::load(&dict).expect("load fixtures");
+    parsed.write_snapshot(&snapshot).expect("write snapshot");
+    let loaded =
+        WordNet::load_with_snapshot(&dict, &snapshot, LoadMode::Owned).expect("load snapshot");
+    assert_eq!(loaded.index_count(), parsed.index_count());
+    assert_eq!(loaded.synset_count(), parsed.synset_count());
+    assert_eq!(
+        loaded.sense_count(Pos::Noun, "dog", 1740),
+        parsed.sense_count(Pos::Noun, "dog", 1740)
+    );
+    let synset = loaded
+        .get_synset(SynsetId {
+            pos: Pos::Verb,
+            offset: 2500,
+        })
+        .expect("verb synset");
+    assert_eq!(synset.frames[1].word_number, None);
+    let dog = loaded.index_entry(Pos::Noun, "dog").expect("dog");
+    assert_eq!(dog.synset_offsets, &[1740]);
+    assert_eq!(loaded.synsets_for_lemma(Pos::Noun, "dog").len(), 1);
+
+    let mut adverbs = std::fs::read(dict.join("data.adv")).unwrap();
+    adverbs.extend_from_slice(b"\n");
+    std::fs::write(dict.join("data.adv"), adverbs).unwrap();
+    let err = WordNet::load_with_snapshot(&dict, &snapshot, LoadMode::Mmap)
+        .err()
+        .expect("stale snapshot rejected");
+    assert!(err.to_string().contains("stale"));
+}